@@ -18,6 +18,7 @@
 //! - **音频重采样**: 高质量的采样率转换
 //! - **元数据提取**: 获取音频文件的详细信息
 //! - **流式处理**: 支持分块处理的流式重采样
+//! - **音频遮蔽**: 按时间区间把敏感内容替换为静音或提示音
 //! 
 //! ## 设计理念
 //! 
@@ -148,6 +149,9 @@ use rubato::{
     Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
 };
 
+pub mod redact;
+pub use redact::{merge_ranges, redact_samples, write_redacted_wav, RedactionMode, TimeRange};
+
 #[derive(Debug, Error)]
 pub enum AudioError {
     #[error("I/O error: {0}")]
@@ -0,0 +1,364 @@
+//! 音频遮蔽
+//!
+//! 按时间区间把音频中的敏感内容替换为静音或提示音（bleep），
+//! 区间通常来自转录文本的敏感信息检测结果。
+
+use crate::AudioError;
+use hound::{SampleFormat, WavReader, WavWriter};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 提示音首尾的淡入淡出时长（毫秒），避免爆音
+const BLEEP_FADE_MS: u64 = 5;
+
+/// 一段音频时间区间（毫秒，左闭右开）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeRange {
+    /// 开始时间（毫秒）
+    pub start_ms: u64,
+    /// 结束时间（毫秒）
+    pub end_ms: u64,
+}
+
+impl TimeRange {
+    /// 创建时间区间，开始晚于结束时自动交换
+    pub fn new(start_ms: u64, end_ms: u64) -> Self {
+        Self {
+            start_ms: start_ms.min(end_ms),
+            end_ms: start_ms.max(end_ms),
+        }
+    }
+
+    /// 区间时长（毫秒）
+    pub fn duration_ms(&self) -> u64 {
+        self.end_ms - self.start_ms
+    }
+}
+
+/// 向每个区间两侧扩展 `padding_ms`，排序并合并重叠或相邻的区间
+///
+/// 词级时间戳通常有几十毫秒的误差，适当扩展可以避免敏感内容的首尾漏出。
+pub fn merge_ranges(ranges: &[TimeRange], padding_ms: u64) -> Vec<TimeRange> {
+    let mut padded: Vec<TimeRange> = ranges
+        .iter()
+        .map(|range| {
+            TimeRange::new(
+                range.start_ms.saturating_sub(padding_ms),
+                range.end_ms.saturating_add(padding_ms),
+            )
+        })
+        .collect();
+    padded.sort_by_key(|range| range.start_ms);
+
+    let mut merged: Vec<TimeRange> = Vec::with_capacity(padded.len());
+    for range in padded {
+        match merged.last_mut() {
+            Some(last) if range.start_ms <= last.end_ms => {
+                last.end_ms = last.end_ms.max(range.end_ms);
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// 遮蔽方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum RedactionMode {
+    /// 替换为静音
+    #[default]
+    Silence,
+    /// 替换为正弦提示音
+    Bleep {
+        /// 频率（Hz）
+        frequency_hz: f32,
+        /// 幅度（0.0-1.0）
+        amplitude: f32,
+    },
+}
+
+impl RedactionMode {
+    /// 常用的 1kHz 提示音
+    pub fn bleep() -> Self {
+        RedactionMode::Bleep {
+            frequency_hz: 1000.0,
+            amplitude: 0.3,
+        }
+    }
+}
+
+/// 在内存中遮蔽交错排列的 f32 样本（范围 -1.0..=1.0）
+///
+/// ## 参数
+///
+/// * `samples` - 交错排列的样本，多声道时每一帧的所有声道都会被替换
+/// * `sample_rate` - 采样率 (Hz)
+/// * `channels` - 声道数
+/// * `ranges` - 需要遮蔽的时间区间，超出音频长度的部分会被忽略
+/// * `mode` - 遮蔽方式
+pub fn redact_samples(
+    samples: &mut [f32],
+    sample_rate: u32,
+    channels: u16,
+    ranges: &[TimeRange],
+    mode: RedactionMode,
+) {
+    let channels = channels.max(1) as usize;
+    let frames = samples.len() / channels;
+    for_each_redacted_frame(frames, sample_rate, ranges, mode, |frame, value| {
+        samples[frame * channels..(frame + 1) * channels].fill(value);
+    });
+}
+
+/// 读取 WAV 文件，遮蔽指定区间后写入新的 WAV 文件
+///
+/// 输出保持输入的采样率、声道数与位深，区间之外的样本原样保留；输出可以与输入为同一文件。
+/// 其他格式可先用 [`ensure_whisper_compatible`](crate::ensure_whisper_compatible) 转换为 WAV。
+///
+/// ## 错误
+///
+/// - `AudioError::FileNotFound`: 输入文件不存在
+/// - `AudioError::DecodeError`: 输入不是有效的 WAV 文件
+/// - `AudioError::EncodeError`: 写入输出文件失败
+///
+/// ## 使用示例
+///
+/// ```rust,no_run
+/// use rs_voice_toolkit_audio::{write_redacted_wav, AudioError, RedactionMode, TimeRange};
+///
+/// fn redact_call() -> Result<(), AudioError> {
+///     let ranges = [TimeRange::new(3_200, 7_850)];
+///     write_redacted_wav("call.wav", "call_redacted.wav", &ranges, RedactionMode::bleep())?;
+///     Ok(())
+/// }
+/// ```
+pub fn write_redacted_wav<P: AsRef<Path>, Q: AsRef<Path>>(
+    input: P,
+    output: Q,
+    ranges: &[TimeRange],
+    mode: RedactionMode,
+) -> Result<(), AudioError> {
+    let input = input.as_ref();
+    if !input.exists() {
+        return Err(AudioError::FileNotFound(format!("{}", input.display())));
+    }
+
+    let mut reader = WavReader::open(input).map_err(|e| AudioError::DecodeError {
+        reason: format!("打开 WAV 失败: {e}"),
+    })?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+
+    // 先读取并遮蔽全部样本再创建输出文件，输出与输入为同一路径时也不会截断原始录音
+    match spec.sample_format {
+        SampleFormat::Float => {
+            let mut samples = reader
+                .samples::<f32>()
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| AudioError::DecodeError {
+                    reason: format!("读取 WAV 样本失败: {e}"),
+                })?;
+            drop(reader);
+            redact_samples(&mut samples, spec.sample_rate, spec.channels, ranges, mode);
+            write_wav_samples(output.as_ref(), spec, samples)?;
+        }
+        SampleFormat::Int => {
+            let mut samples = reader
+                .samples::<i32>()
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| AudioError::DecodeError {
+                    reason: format!("读取 WAV 样本失败: {e}"),
+                })?;
+            drop(reader);
+            let full_scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            let max = (1i64 << (spec.bits_per_sample - 1)) - 1;
+            let frames = samples.len() / channels;
+            for_each_redacted_frame(frames, spec.sample_rate, ranges, mode, |frame, value| {
+                let value = ((value * full_scale).round() as i64).clamp(-max - 1, max) as i32;
+                samples[frame * channels..(frame + 1) * channels].fill(value);
+            });
+            write_wav_samples(output.as_ref(), spec, samples)?;
+        }
+    }
+
+    log::info!(
+        "已遮蔽 {} 个区间: {} -> {}",
+        ranges.len(),
+        input.display(),
+        output.as_ref().display()
+    );
+    Ok(())
+}
+
+/// 以 `spec` 创建 WAV 文件并写入全部样本
+fn write_wav_samples<S: hound::Sample>(
+    path: &Path,
+    spec: hound::WavSpec,
+    samples: Vec<S>,
+) -> Result<(), AudioError> {
+    let encode_error = |e: hound::Error| AudioError::EncodeError {
+        reason: format!("写入 WAV 失败: {e}"),
+    };
+    let mut writer = WavWriter::create(path, spec).map_err(encode_error)?;
+    for sample in samples {
+        writer.write_sample(sample).map_err(encode_error)?;
+    }
+    writer.finalize().map_err(encode_error)
+}
+
+/// 计算每个需要遮蔽的帧的替换值（-1.0..=1.0）
+fn for_each_redacted_frame(
+    frames: usize,
+    sample_rate: u32,
+    ranges: &[TimeRange],
+    mode: RedactionMode,
+    mut replace: impl FnMut(usize, f32),
+) {
+    let to_frame = |ms: u64| ((ms as u128 * sample_rate as u128 / 1000) as usize).min(frames);
+    let fade_frames = to_frame(BLEEP_FADE_MS).max(1);
+
+    for range in ranges {
+        let start = to_frame(range.start_ms);
+        let end = to_frame(range.end_ms);
+        for frame in start..end {
+            let value = match mode {
+                RedactionMode::Silence => 0.0,
+                RedactionMode::Bleep {
+                    frequency_hz,
+                    amplitude,
+                } => {
+                    let offset = frame - start;
+                    let fade = (offset.min(end - 1 - frame) as f32 / fade_frames as f32).min(1.0);
+                    let t = offset as f32 / sample_rate as f32;
+                    (2.0 * std::f32::consts::PI * frequency_hz * t).sin()
+                        * amplitude.clamp(0.0, 1.0)
+                        * fade
+                }
+            };
+            replace(frame, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hound::WavSpec;
+
+    #[test]
+    fn test_merge_ranges() {
+        let ranges = [
+            TimeRange::new(5_000, 6_000),
+            TimeRange::new(1_000, 2_000),
+            TimeRange::new(2_050, 3_000),
+        ];
+        let merged = merge_ranges(&ranges, 50);
+        assert_eq!(
+            merged,
+            vec![TimeRange::new(950, 3_050), TimeRange::new(4_950, 6_050)]
+        );
+        assert_eq!(TimeRange::new(300, 100).duration_ms(), 200);
+    }
+
+    #[test]
+    fn test_redact_samples() {
+        let mut samples = vec![0.5f32; 2 * 1000];
+        redact_samples(
+            &mut samples,
+            1000,
+            2,
+            &[TimeRange::new(100, 200), TimeRange::new(990, 2_000)],
+            RedactionMode::Silence,
+        );
+        assert_eq!(samples[2 * 99], 0.5);
+        assert!(samples[2 * 100..2 * 200].iter().all(|s| *s == 0.0));
+        assert_eq!(samples[2 * 200 + 1], 0.5);
+        assert!(samples[2 * 990..].iter().all(|s| *s == 0.0));
+
+        let mut samples = vec![0.0f32; 16_000];
+        redact_samples(
+            &mut samples,
+            16_000,
+            1,
+            &[TimeRange::new(0, 1_000)],
+            RedactionMode::bleep(),
+        );
+        let peak = samples.iter().fold(0.0f32, |max, s| max.max(s.abs()));
+        assert!(peak > 0.25 && peak <= 0.3);
+        assert_eq!(samples[0], 0.0);
+    }
+
+    #[test]
+    fn test_write_redacted_wav() {
+        let dir = std::env::temp_dir().join(format!("redact_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("input.wav");
+        let output = dir.join("output.wav");
+
+        let spec = WavSpec {
+            channels: 1,
+            sample_rate: 16_000,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = WavWriter::create(&input, spec).unwrap();
+        for _ in 0..16_000 {
+            writer.write_sample(1000i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        write_redacted_wav(
+            &input,
+            &output,
+            &[TimeRange::new(250, 500)],
+            RedactionMode::Silence,
+        )
+        .unwrap();
+
+        let mut reader = WavReader::open(&output).unwrap();
+        assert_eq!(reader.spec(), spec);
+        let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+        assert_eq!(samples.len(), 16_000);
+        assert_eq!(samples[3_999], 1000);
+        assert!(samples[4_000..8_000].iter().all(|s| *s == 0));
+        assert_eq!(samples[8_000], 1000);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_write_redacted_wav_in_place() {
+        let dir = std::env::temp_dir().join(format!("redact_in_place_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("call.wav");
+
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: 16_000,
+            bits_per_sample: 32,
+            sample_format: SampleFormat::Float,
+        };
+        let mut writer = WavWriter::create(&path, spec).unwrap();
+        for _ in 0..16_000 * 2 {
+            writer.write_sample(0.5f32).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        write_redacted_wav(
+            &path,
+            &path,
+            &[TimeRange::new(0, 500)],
+            RedactionMode::Silence,
+        )
+        .unwrap();
+
+        let mut reader = WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec(), spec);
+        let samples: Vec<f32> = reader.samples::<f32>().map(Result::unwrap).collect();
+        assert_eq!(samples.len(), 16_000 * 2);
+        assert!(samples[..16_000].iter().all(|s| *s == 0.0));
+        assert!(samples[16_000..].iter().all(|s| *s == 0.5));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -109,9 +109,9 @@
 //! - 建议在稳定的系统环境下进行测试
 //! - 测试结果可能因系统负载而有所波动
 
-use std::path::PathBuf;
-use std::time::Instant;
 use log::info;
+use rs_voice_toolkit_stt::{bench, WhisperConfig};
+use std::path::PathBuf;
 
 /// STT 性能基准测试示例
 /// 
@@ -162,85 +162,54 @@ async fn main() {
         std::process::exit(1);
     }
 
-    let model = PathBuf::from(&args[1]);
+    let model = &args[1];
     let audio = PathBuf::from(&args[2]);
     let iters: usize = args.get(3).and_then(|s| s.parse().ok()).unwrap_or(3);
 
     // 显示测试配置
-    let model_path = model.display();
-    let audio_path = audio.display();
     info!("🚀 性能基准测试");
     info!("{}", "=".repeat(50));
     info!("📊 测试配置:");
-    info!("  模型: {model_path}");
-    info!("  音频: {audio_path}");
+    info!("  模型: {model}");
+    info!("  音频: {}", audio.display());
     info!("  迭代次数: {iters}");
-    
-    // 验证文件存在性
-    if !model.exists() {
-        log::error!("❌ 模型文件不存在: {model_path}");
-        std::process::exit(1);
-    }
-    
+
     if !audio.exists() {
-        log::error!("❌ 音频文件不存在: {audio_path}");
+        log::error!("❌ 音频文件不存在: {}", audio.display());
         std::process::exit(1);
     }
 
-    info!("\n🔥 预热阶段 - 首次加载模型...");
-    // 预热一次，避免首次加载影响测试结果
-    match rs_voice_toolkit_stt::transcribe_file(&model, &audio).await {
-        Ok(_) => info!("✅ 预热完成"),
+    // 预热与逐次计时由 bench::run 完成
+    let report = match bench::run(WhisperConfig::new(model), [&audio], iters).await {
+        Ok(report) => report,
         Err(e) => {
-            log::error!("❌ 预热失败: {e}");
+            log::error!("❌ 基准测试失败: {e}");
             std::process::exit(1);
         }
-    }
+    };
 
-    info!("\n📈 开始性能测试...");
-    let mut total_rt_factor = 0.0f64;
-    let mut total_ms = 0u128;
-    let mut results = Vec::new();
-
-    for i in 0..iters {
-        let t0 = Instant::now();
-        
-        match rs_voice_toolkit_stt::transcribe_file(&model, &audio).await {
-            Ok(result) => {
-                let dt = t0.elapsed().as_millis();
-                let rtf = result.real_time_factor();
-                total_rt_factor += rtf;
-                total_ms += dt;
-                results.push((rtf, dt, result.text.clone()));
-                
-                info!(
-                    "🔄 迭代 {i}: RTF={rtf:.3}, 用时={dt} ms, 文本='{}'",
-                    result.text
-                );
-            }
-            Err(e) => {
-                log::error!("❌ 迭代 {i} 失败: {e}");
-                // 继续测试其他迭代，但记录错误
-            }
-        }
-        
-        // 在迭代之间稍作停顿，避免过热
-        if i < iters - 1 {
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-        }
+    for run in &report.runs {
+        info!(
+            "🔄 迭代 {}: RTF={:.3}, 用时={} ms",
+            run.iteration, run.rtf, run.processing_ms
+        );
     }
 
-    // 计算并显示统计结果
-    let avg_rtf = total_rt_factor / results.len() as f64;
-    let avg_ms = total_ms as f64 / results.len() as f64;
-    
+    // 显示统计结果
     info!("\n📊 测试结果统计:");
     info!("{}", "=".repeat(50));
-    info!("✅ 成功完成迭代: {}/{}", results.len(), iters);
-    info!("📈 平均 RTF: {avg_rtf:.3}");
-    info!("⏱️  平均用时: {avg_ms:.0} ms");
-    
+    info!("✅ 成功完成迭代: {}/{}", report.runs.len(), iters);
+    info!("⏳ 模型加载: {} ms", report.model_load_ms);
+    info!(
+        "📈 RTF: 平均 {:.3}, p50 {:.3}, p90 {:.3}",
+        report.rtf.mean, report.rtf.p50, report.rtf.p90
+    );
+    info!("⏱️  平均用时: {:.0} ms", report.processing_ms.mean);
+    info!("🔤 Token 速率: {:.1} token/s", report.tokens_per_second);
+    info!("💾 内存峰值: {:.1} MB", report.peak_memory_mb);
+
     // 性能评估
+    let avg_rtf = report.rtf.mean;
     info!("\n🎯 性能评估:");
     if avg_rtf < 0.5 {
         info!("⭐ 性能评级: 优秀 (RTF < 0.5) - 可以实时处理");
@@ -251,19 +220,16 @@ async fn main() {
     } else {
         info!("⭐ 性能评级: 较慢 (RTF >= 2.0) - 需要优化");
     }
-    
+
     // 稳定性分析
-    if results.len() > 1 {
-        let rtfs: Vec<f64> = results.iter().map(|(rtf, _, _)| *rtf).collect();
-        let min_rtf = rtfs.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let max_rtf = rtfs.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-        let stability = 1.0 - (max_rtf - min_rtf) / avg_rtf;
-        
+    if report.runs.len() > 1 && avg_rtf > 0.0 {
+        let stability = 1.0 - (report.rtf.max - report.rtf.min) / avg_rtf;
+
         info!("📊 稳定性分析:");
-        info!("  最小 RTF: {min_rtf:.3}");
-        info!("  最大 RTF: {max_rtf:.3}");
+        info!("  最小 RTF: {:.3}", report.rtf.min);
+        info!("  最大 RTF: {:.3}", report.rtf.max);
         info!("  稳定性评分: {:.1}%", stability * 100.0);
-        
+
         if stability > 0.9 {
             info!("  🟢 性能非常稳定");
         } else if stability > 0.7 {
@@ -272,6 +238,6 @@ async fn main() {
             info!("  🔴 性能波动较大");
         }
     }
-    
+
     info!("\n🎉 性能测试完成！");
 }
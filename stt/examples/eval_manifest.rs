@@ -0,0 +1,92 @@
+//! 识别准确率评估示例
+//!
+//! 转录清单中的所有音频，与参考文本比对后把逐文件与整体的 WER/CER 以 JSON 输出到标准输出。
+//! 指定最大错误率时，超过阈值或有文件转录失败则以非零状态退出，可在 CI 中为模型或配置变更把关。
+//!
+//! # 清单格式
+//!
+//! JSON Lines，每行一个音频；相对路径相对于清单文件所在目录：
+//!
+//! ```text
+//! {"audio": "audio/jfk.wav", "reference": "And so my fellow Americans ..."}
+//! {"audio": "audio/bank_audio.m4a", "reference": "您好，这里是银行客服中心"}
+//! ```
+//!
+//! # 使用方法
+//!
+//! ```bash
+//! # 英文，按词计算 WER
+//! cargo run -p rs-voice-toolkit-stt --example eval_manifest -- tiny fixtures/manifest.jsonl
+//!
+//! # 中文，按字计算 CER，错误率超过 15% 时失败
+//! cargo run -p rs-voice-toolkit-stt --example eval_manifest -- models/ggml-small.bin fixtures/manifest_zh.jsonl 0.15 zh
+//! ```
+//!
+//! # 参数说明
+//!
+//! - `<model>`: 模型文件路径或模型名称
+//! - `<manifest>`: 清单文件路径
+//! - `[max_error_rate]`: 可选，允许的最大整体错误率
+//! - `[language]`: 可选，`zh` 时按中文规则规范化并计算 CER，同时作为转录语言
+
+use rs_voice_toolkit_stt::{EvalConfig, TextNormalizer, WhisperConfig, WhisperTranscriber};
+
+/// 用法: cargo run -p rs-voice-toolkit-stt --example eval_manifest -- <model> <manifest> [max_error_rate] [language]
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 3 {
+        log::error!(
+            "用法: cargo run -p rs-voice-toolkit-stt --example eval_manifest -- <model> <manifest> [max_error_rate] [language]"
+        );
+        std::process::exit(2);
+    }
+
+    let max_error_rate: Option<f64> = args.get(3).and_then(|s| s.parse().ok());
+    let language = args.get(4).map(String::as_str);
+
+    let mut whisper_config = WhisperConfig::new(&args[1]);
+    if let Some(language) = language {
+        whisper_config = whisper_config.with_language(language);
+    }
+    let transcriber = match WhisperTranscriber::new(whisper_config) {
+        Ok(transcriber) => transcriber,
+        Err(e) => {
+            log::error!("❌ 加载模型失败: {e}");
+            std::process::exit(2);
+        }
+    };
+
+    let config = EvalConfig::default()
+        .with_normalizer(TextNormalizer::for_language(language))
+        .with_alignment(true);
+    let report = match transcriber.evaluate_manifest_file(&args[2], &config).await {
+        Ok(report) => report,
+        Err(e) => {
+            log::error!("❌ 评估失败: {e}");
+            std::process::exit(2);
+        }
+    };
+
+    match report.to_json() {
+        Ok(json) => println!("{json}"),
+        Err(e) => {
+            log::error!("❌ 输出报告失败: {e}");
+            std::process::exit(2);
+        }
+    }
+
+    if let Some(max_error_rate) = max_error_rate {
+        if !report.passes(max_error_rate) {
+            log::error!(
+                "❌ 整体错误率 {:.2}% 超过阈值 {:.2}%（失败文件 {} 个）",
+                report.error_rate * 100.0,
+                max_error_rate * 100.0,
+                report.failed_files
+            );
+            std::process::exit(1);
+        }
+    }
+}
@@ -1,239 +1,69 @@
-use std::path::PathBuf;
-use std::process;
-use std::time::Instant;
-use sysinfo::{Pid, System};
 use log::info;
+use rs_voice_toolkit_stt::bench::{self, BenchBaseline};
+use rs_voice_toolkit_stt::WhisperConfig;
 
 /// 性能基线测试工具
-/// 记录 RTF、内存使用、处理时间等关键指标
+/// 记录 RTF 分位数、模型加载时间、内存峰值与 token 速率，可与基线文件比较
+///
+/// 用法: cargo run -p rs-voice-toolkit-stt --example performance_baseline -- <model> <audio_path> [iters] [baseline] [threshold_percent]
+///
+/// - `baseline`: 基线文件（JSON，或如 `specs/stt-performance-baseline.md` 这样内嵌 JSON 代码块的 Markdown）
+/// - `threshold_percent`: 允许的变差百分比，默认 10；存在回退时以状态 1 退出
 #[tokio::main]
 async fn main() {
+    env_logger::init();
+
     let args: Vec<String> = std::env::args().collect();
     if args.len() < 3 {
         log::error!(
-            "用法: cargo run -p stt --example performance_baseline -- <model_path> <audio_path> [iters]"
+            "用法: cargo run -p rs-voice-toolkit-stt --example performance_baseline -- <model> <audio_path> [iters] [baseline] [threshold_percent]"
         );
-        std::process::exit(1);
+        std::process::exit(2);
     }
 
-    let model = PathBuf::from(&args[1]);
-    let audio = PathBuf::from(&args[2]);
     let iters: usize = args.get(3).and_then(|s| s.parse().ok()).unwrap_or(5);
+    let baseline = args.get(4);
+    let threshold: f64 = args.get(5).and_then(|s| s.parse().ok()).unwrap_or(10.0);
 
     info!("=== STT 性能基线测试 ===");
-    let model_path = model.display();
-    info!("模型: {model_path}");
-    let audio_path = audio.display();
-    info!("音频: {audio_path}");
-    info!("迭代次数: {iters}");
-    info!("");
-
-    // 初始化系统监控
-    let mut system = System::new_all();
-    let pid = process::id();
-
-    // 预热一次，避免首次加载影响
-    info!("预热中...");
-    let _ = rs_voice_toolkit_stt::transcribe_file(&model, &audio).await;
-    info!("预热完成\n");
-
-    let mut metrics = PerformanceMetrics::new();
-
-    for i in 0..iters {
-        info!("--- 迭代 {} ---", i + 1);
-
-        // 记录开始状态
-        system.refresh_all();
-        let start_memory = get_process_memory(&system, pid);
-        let start_time = Instant::now();
-
-        // 执行转录
-        let result = rs_voice_toolkit_stt::transcribe_file(&model, &audio)
-            .await
-            .expect("转录失败");
-
-        let elapsed = start_time.elapsed();
-
-        // 记录结束状态
-        system.refresh_all();
-        let end_memory = get_process_memory(&system, pid);
-
-        // 计算指标
-        let rtf = result.real_time_factor();
-        let processing_time_ms = elapsed.as_millis();
-        let memory_delta_mb = (end_memory as i64 - start_memory as i64) / 1024 / 1024;
-        let audio_duration_s = result.audio_duration as f64 / 1000.0;
-        let confidence = result.average_confidence();
-
-        // 记录指标
-        metrics.add_measurement(Measurement {
-            rtf,
-            processing_time_ms,
-            memory_delta_mb,
-            audio_duration_s,
-            confidence,
-            text_length: result.text.len(),
-            segment_count: result.segments.len(),
-        });
-
-        info!("  RTF: {rtf:.3}");
-        info!("  处理时间: {processing_time_ms} ms");
-        info!("  内存变化: {memory_delta_mb} MB");
-        info!("  音频时长: {audio_duration_s:.1} s");
-        info!("  平均置信度: {confidence:.3}");
-        let text_len = result.text.len();
-        info!("  文本长度: {text_len} 字符");
-        let segment_count = result.segments.len();
-        info!("  段落数: {segment_count}");
-        let preview_text = result.text.chars().take(50).collect::<String>();
-        info!("  文本: '{preview_text}'");
-        info!("");
-    }
-
-    // 输出统计结果
-    metrics.print_summary();
-}
-
-fn get_process_memory(system: &System, pid: u32) -> u64 {
-    system
-        .process(Pid::from(pid as usize))
-        .map(|p| p.memory())
-        .unwrap_or(0)
-}
-
-#[derive(Debug)]
-struct Measurement {
-    rtf: f64,
-    processing_time_ms: u128,
-    memory_delta_mb: i64,
-    audio_duration_s: f64,
-    confidence: f32,
-    text_length: usize,
-    segment_count: usize,
-}
-
-struct PerformanceMetrics {
-    measurements: Vec<Measurement>,
-}
-
-impl PerformanceMetrics {
-    fn new() -> Self {
-        Self {
-            measurements: Vec::new(),
+    let report = match bench::run(WhisperConfig::new(&args[1]), [&args[2]], iters).await {
+        Ok(report) => report,
+        Err(e) => {
+            log::error!("基准测试失败: {e}");
+            std::process::exit(2);
         }
-    }
+    };
 
-    fn add_measurement(&mut self, measurement: Measurement) {
-        self.measurements.push(measurement);
+    match report.to_json() {
+        Ok(json) => println!("{json}"),
+        Err(e) => log::error!("输出报告失败: {e}"),
     }
 
-    fn print_summary(&self) {
-        if self.measurements.is_empty() {
-            return;
-        }
-
-        info!("=== 性能基线统计 ===");
-
-        // RTF 统计
-        let rtfs: Vec<f64> = self.measurements.iter().map(|m| m.rtf).collect();
-        let avg_rtf = rtfs.iter().sum::<f64>() / rtfs.len() as f64;
-        let min_rtf = rtfs.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let max_rtf = rtfs.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-
-        info!("RTF (实时因子):");
-        info!("  平均: {avg_rtf:.3}");
-        info!("  最小: {min_rtf:.3}");
-        info!("  最大: {max_rtf:.3}");
-
-        // 处理时间统计
-        let times: Vec<u128> = self
-            .measurements
-            .iter()
-            .map(|m| m.processing_time_ms)
-            .collect();
-        let avg_time = times.iter().sum::<u128>() as f64 / times.len() as f64;
-        let min_time = *times.iter().min().unwrap();
-        let max_time = *times.iter().max().unwrap();
-
-        info!("\n处理时间 (ms):");
-        info!("  平均: {avg_time:.3}");
-        info!("  最小: {min_time}");
-        info!("  最大: {max_time}");
-
-        // 内存使用统计
-        let memories: Vec<i64> = self
-            .measurements
-            .iter()
-            .map(|m| m.memory_delta_mb)
-            .collect();
-        let avg_memory = memories.iter().sum::<i64>() as f64 / memories.len() as f64;
-        let min_memory = *memories.iter().min().unwrap();
-        let max_memory = *memories.iter().max().unwrap();
-
-        info!("\n内存变化 (MB):");
-        info!("  平均: {avg_memory:.1}");
-        info!("  最小: {min_memory}");
-        info!("  最大: {max_memory}");
-
-        // 置信度统计
-        let confidences: Vec<f32> = self.measurements.iter().map(|m| m.confidence).collect();
-        let avg_confidence = confidences.iter().sum::<f32>() / confidences.len() as f32;
-        let min_confidence = confidences.iter().fold(f32::INFINITY, |a, &b| a.min(b));
-        let max_confidence = confidences.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
-
-        info!("\n置信度:");
-        info!("  平均: {avg_confidence:.3}");
-        info!("  最小: {min_confidence:.3}");
-        info!("  最大: {max_confidence:.3}");
-
-        // 音频时长
-        if let Some(first) = self.measurements.first() {
-            info!("\n音频信息:");
-            let duration = first.audio_duration_s;
-            info!("  时长: {duration:.1} 秒");
-            let avg_text_length = self
-                .measurements
-                .iter()
-                .map(|m| m.text_length)
-                .sum::<usize>() as f64
-                / self.measurements.len() as f64;
-            info!("  平均文本长度: {avg_text_length:.0} 字符");
-            let avg_segment_count = self
-                .measurements
-                .iter()
-                .map(|m| m.segment_count)
-                .sum::<usize>() as f64
-                / self.measurements.len() as f64;
-            info!("  平均段落数: {avg_segment_count:.1}");
-        }
-
-        // 性能评估
-        info!("\n=== 性能评估 ===");
-        if avg_rtf < 0.3 {
-            info!("✅ 性能优秀: RTF < 0.3，实时性能良好");
-        } else if avg_rtf < 1.0 {
-            info!("⚠️  性能一般: RTF < 1.0，可实时处理但有延迟");
-        } else {
-            info!("❌ 性能较差: RTF >= 1.0，无法实时处理");
-        }
-
-        if avg_confidence > 0.8 {
-            info!("✅ 识别质量优秀: 平均置信度 > 0.8");
-        } else if avg_confidence > 0.6 {
-            info!("⚠️  识别质量一般: 平均置信度 > 0.6");
-        } else {
-            info!("❌ 识别质量较差: 平均置信度 <= 0.6");
-        }
-
-        info!("\n建议:");
-        if avg_rtf > 0.5 {
-            info!("- 考虑使用更小的模型或优化硬件配置");
-        }
-        if avg_memory > 100.0 {
-            info!("- 内存使用较高，注意内存泄漏");
-        }
-        if avg_confidence < 0.7 {
-            info!("- 考虑使用更大的模型或改善音频质量");
+    let Some(baseline) = baseline else {
+        return;
+    };
+    let baseline = match BenchBaseline::load(baseline) {
+        Ok(baseline) => baseline,
+        Err(e) => {
+            log::error!("读取基线失败: {e}");
+            std::process::exit(2);
         }
+    };
+
+    let comparison = report.compare(&baseline, threshold);
+    info!("=== 与基线比较（允许变差 {threshold}%）===");
+    for metric in &comparison.metrics {
+        info!(
+            "{} {}: 基线 {:.3}，当前 {:.3}（{:+.1}%）",
+            if metric.regressed { "❌" } else { "✅" },
+            metric.name,
+            metric.baseline,
+            metric.current,
+            metric.change_percent
+        );
+    }
+    if comparison.has_regressions() {
+        log::error!("检测到性能回退");
+        std::process::exit(1);
     }
 }
@@ -6,7 +6,9 @@ use rs_voice_toolkit_stt::audio::utils::read_wav_file;
 use rs_voice_toolkit_stt::{self, AudioConfig};
 use log::info;
 #[cfg(feature = "streaming")]
-use rs_voice_toolkit_stt::{create_custom_streaming_transcriber, StreamingConfig, StreamingEvent};
+use rs_voice_toolkit_stt::{
+    create_custom_streaming_transcriber, InputFormat, StreamingConfig, StreamingEvent,
+};
 
 #[cfg(feature = "streaming")]
 #[tokio::main]
@@ -69,6 +71,24 @@ async fn main() {
                         info!("[转录] {text}");
                     }
                 }
+                StreamingEvent::Partial(partial) => {
+                    info!(
+                        "[部分 #{}.{}] {} | {}",
+                        partial.utterance_id,
+                        partial.revision,
+                        partial.committed,
+                        partial.unconfirmed
+                    );
+                }
+                StreamingEvent::Final(final_result) => {
+                    info!(
+                        "[最终 #{}] {}",
+                        final_result.utterance_id, final_result.committed
+                    );
+                }
+                StreamingEvent::AudioDropped { samples, total } => {
+                    log::warn!("[事件] 丢弃 {samples} 个样本（累计 {total}）")
+                }
                 StreamingEvent::SpeechStart => info!("[事件] 语音开始"),
                 StreamingEvent::SpeechEnd => info!("[事件] 语音结束"),
                 StreamingEvent::Silence => info!("[事件] 静音"),
@@ -79,6 +99,8 @@ async fn main() {
 
     // 读取 WAV 并分块推送
     let audio = read_wav_file(&audio_path).expect("读取WAV失败");
+    // 声明输入格式，推送时自动下混并重采样到 16kHz 单声道
+    transcriber.set_input_format(InputFormat::from(&audio.config));
     let samples = audio.samples;
     let sr = audio.config.sample_rate as usize;
     let chunk = (sr as u64 * chunk_ms / 1000) as usize; // 自定义块大小
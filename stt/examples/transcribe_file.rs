@@ -21,12 +21,21 @@
 //! 
 //! # 使用 base 模型转录音频文件
 //! cargo run -p rs-voice-toolkit-stt --example transcribe_file -- models/ggml-base.bin audio/speech.wav
+//! 
+//! # 对比解码策略（质量 vs RTF）
+//! cargo run -p rs-voice-toolkit-stt --example transcribe_file -- models/ggml-base.bin fixtures/audio/jfk.wav --best-of=5
+//! cargo run -p rs-voice-toolkit-stt --example transcribe_file -- models/ggml-base.bin fixtures/audio/jfk.wav --beam=5
 //! ```
 //! 
 //! ## 参数说明
 //! 
 //! - `<model_path>`: Whisper 模型文件的路径（.bin 格式）
 //! - `<audio_path>`: 要转录的音频文件路径
+//! - `--best-of=<n>`: 使用贪心解码，候选数为 n（默认 1）
+//! - `--beam=<size>`: 使用束搜索解码，束宽为 size
+//! - `--patience=<p>`: 束搜索耐心系数（默认 -1.0）
+//! - `--lang=<code>`: 指定语言（如 zh、en）
+//! - `--words`: 输出词级时间戳
 //! 
 //! # 支持的音频格式
 //! 
@@ -93,7 +102,7 @@
 use log::{info, warn};
 use std::env;
 use std::path::PathBuf;
-use rs_voice_toolkit_stt::transcribe_file;
+use rs_voice_toolkit_stt::{DecodingStrategy, WhisperConfig, WhisperTranscriber};
 
 /// 文件转录示例
 /// 
@@ -137,7 +146,7 @@ use rs_voice_toolkit_stt::transcribe_file;
 /// - 长音频文件建议分段处理
 /// - 首次运行后，模型会被缓存，后续运行会更快
 /// 
-/// # 用法: cargo run -p stt --example transcribe_file -- <model_path> <audio_path>
+/// # 用法: cargo run -p stt --example transcribe_file -- <model_path> <audio_path> [--best-of=<n>] [--beam=<size>] [--patience=<p>] [--lang=<code>] [--words]
 #[tokio::main]
 async fn main() {
     // 初始化日志系统
@@ -146,7 +155,7 @@ async fn main() {
     // 解析命令行参数
     let args: Vec<String> = env::args().collect();
     if args.len() < 3 {
-        log::error!("用法: cargo run -p rs-voice-toolkit-stt --example transcribe_file -- <model_path> <audio_path>");
+        log::error!("用法: cargo run -p rs-voice-toolkit-stt --example transcribe_file -- <model_path> <audio_path> [--best-of=<n>] [--beam=<size>] [--patience=<p>] [--lang=<code>] [--words]");
         log::error!("示例:");
         log::error!("  cargo run -p rs-voice-toolkit-stt --example transcribe_file -- models/ggml-tiny.bin audio/hello.wav");
         log::error!("  cargo run -p rs-voice-toolkit-stt --example transcribe_file -- models/ggml-base.bin audio/speech.wav");
@@ -156,6 +165,35 @@ async fn main() {
     let model_path = PathBuf::from(&args[1]);
     let audio_path = PathBuf::from(&args[2]);
 
+    // 解析可选参数
+    let mut best_of: i32 = 1;
+    let mut beam_size: Option<i32> = None;
+    let mut patience: f32 = -1.0;
+    let mut language: Option<String> = None;
+    let mut word_timestamps = false;
+
+    for arg in args.iter().skip(3) {
+        if let Some(val) = arg.strip_prefix("--best-of=") {
+            best_of = val.parse().unwrap_or(1);
+        } else if let Some(val) = arg.strip_prefix("--beam=") {
+            beam_size = val.parse().ok();
+        } else if let Some(val) = arg.strip_prefix("--patience=") {
+            patience = val.parse().unwrap_or(-1.0);
+        } else if let Some(val) = arg.strip_prefix("--lang=") {
+            language = Some(val.to_string());
+        } else if arg == "--words" {
+            word_timestamps = true;
+        }
+    }
+
+    let strategy = match beam_size {
+        Some(beam_size) => DecodingStrategy::BeamSearch {
+            beam_size,
+            patience,
+        },
+        None => DecodingStrategy::Greedy { best_of },
+    };
+
     // 验证模型文件是否存在
     if !model_path.exists() {
         let model_display = model_path.display();
@@ -183,12 +221,25 @@ async fn main() {
     info!("🎙️  开始转录文件: {audio_display}");
     let model_display = model_path.display();
     info!("🤖 使用模型: {model_display}");
+    info!("🔍 解码策略: {strategy:?}");
+
+    let mut config = WhisperConfig::new(&model_path)
+        .with_sampling_strategy(strategy)
+        .with_word_timestamps(word_timestamps);
+    if let Some(language) = language {
+        config = config.with_language(language);
+    }
 
     // 记录开始时间
     let start_time = std::time::Instant::now();
 
     // 执行转录
-    match transcribe_file(&model_path, &audio_path).await {
+    let result = match WhisperTranscriber::new(config) {
+        Ok(transcriber) => transcriber.transcribe_file(&audio_path).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
         Ok(result) => {
             let elapsed = start_time.elapsed();
             
@@ -236,6 +287,15 @@ async fn main() {
                         segment.confidence,
                         segment.text
                     );
+                    for word in &segment.words {
+                        info!(
+                            "     [{:.2}s - {:.2}s] {:.3}: {}",
+                            word.start_time as f64 / 1000.0,
+                            word.end_time as f64 / 1000.0,
+                            word.probability,
+                            word.text
+                        );
+                    }
                 }
                 
                 if result.segments.len() > 3 {
@@ -7,7 +7,7 @@ pub mod resampler;
 pub mod utils;
 
 pub use converter::AudioConverter;
-pub use resampler::AudioResampler;
+pub use resampler::{AdvancedResampler, AudioResampler, ResampleQuality};
 pub use utils::*;
 
 // 重新导出独立音频模块的类型
@@ -5,6 +5,7 @@
 use super::AudioConfig;
 use crate::error::{SttError, SttResult};
 use log::info;
+use serde::{Deserialize, Serialize};
 
 use rubato::{
     Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
@@ -162,11 +163,12 @@ impl AudioResampler {
 }
 
 /// 重采样质量设置
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ResampleQuality {
     /// 快速重采样（低质量）
     Fast,
     /// 标准重采样（中等质量）
+    #[default]
     Standard,
     /// 高质量重采样（慢速）
     High,
@@ -212,7 +214,7 @@ impl AdvancedResampler {
 
     /// 使用指定质量进行重采样
     pub fn resample_with_quality(&self, input: &[f32]) -> SttResult<Vec<f32>> {
-        if !self.base.needs_resampling() {
+        if !self.base.needs_resampling() || input.is_empty() {
             return Ok(input.to_vec());
         }
 
@@ -248,14 +250,29 @@ impl AdvancedResampler {
             input_channels[channel].push(sample);
         }
 
-        let output_channels = resampler
+        let mut output_channels = resampler
             .process(&input_channels, None)
             .map_err(|e| SttError::ResamplingError(format!("高质量重采样失败: {e}")))?;
 
+        // sinc 滤波器会引入固定的输出延迟；补零冲刷出尾部并丢弃开头的延迟帧，
+        // 使输出与输入在时间轴上对齐（转录时间戳依赖于此）
+        let delay = resampler.output_delay();
         let output_frames = (input_frames as f64 * self.base.ratio()).round() as usize;
+        while output_channels[0].len() < delay + output_frames {
+            let tail = resampler
+                .process_partial(None::<&[Vec<f32>]>, None)
+                .map_err(|e| SttError::ResamplingError(format!("高质量重采样失败: {e}")))?;
+            if tail[0].is_empty() {
+                break;
+            }
+            for (ch_data, tail_data) in output_channels.iter_mut().zip(tail) {
+                ch_data.extend(tail_data);
+            }
+        }
+
         let mut output = Vec::with_capacity(output_frames * self.base.channels);
 
-        for frame in 0..output_frames {
+        for frame in delay..delay + output_frames {
             for ch_data in output_channels.iter().take(self.base.channels) {
                 if frame < ch_data.len() {
                     output.push(ch_data[frame]);
@@ -0,0 +1,459 @@
+//! 多文件批量转录
+//!
+//! 所有文件共享同一个已加载的模型上下文，由固定数量的工作任务各持有一个解码状态并行转录；
+//! 每个文件先在阻塞线程上完成格式转换与读取，只有解码经过转录器的推理并发限制
+//! （`max_concurrent_inference`）。
+//! 每个文件完成（或失败）后按完成顺序回调，全部结束后返回汇总：音频总时长、整体实时因子与失败列表。
+
+use crate::error::{SttError, SttResult};
+use crate::progress::TranscribeOptions;
+use crate::whisper::{load_audio_file, InferenceLimiter, TranscriptionResult, WhisperTranscriber};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use whisper_rs::WhisperState;
+
+/// 批量转录配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchConfig {
+    /// 同时转录的文件数（即解码状态池大小，不超过转录器的 `max_concurrent_inference`，
+    /// 两者默认均为 2）
+    pub max_concurrency: usize,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self { max_concurrency: 2 }
+    }
+}
+
+impl BatchConfig {
+    /// 设置同时转录的文件数（至少为 1）
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+}
+
+/// 单个文件的转录结果
+#[derive(Debug)]
+pub struct BatchItem {
+    /// 文件在输入列表中的序号
+    pub index: usize,
+    /// 文件路径
+    pub path: PathBuf,
+    /// 转录结果或错误
+    pub result: SttResult<TranscriptionResult>,
+}
+
+/// 转录失败的文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchFailure {
+    /// 文件路径
+    pub path: PathBuf,
+    /// 错误信息
+    pub error: String,
+}
+
+/// 批量转录汇总
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchSummary {
+    /// 文件总数
+    pub total_files: usize,
+    /// 成功转录的文件数
+    pub succeeded: usize,
+    /// 成功转录的音频总时长（毫秒）
+    pub audio_duration: u64,
+    /// 整个批次的耗时（毫秒，墙钟时间）
+    pub processing_time: u64,
+    /// 失败的文件
+    pub failures: Vec<BatchFailure>,
+}
+
+impl BatchSummary {
+    /// 成功转录的音频总时长（小时）
+    pub fn audio_hours(&self) -> f64 {
+        self.audio_duration as f64 / 3_600_000.0
+    }
+
+    /// 整体实时因子（批次耗时 / 音频总时长），并行度越高越小
+    pub fn real_time_factor(&self) -> f64 {
+        if self.audio_duration == 0 {
+            return 0.0;
+        }
+        self.processing_time as f64 / self.audio_duration as f64
+    }
+
+    /// 记录一个文件的结果
+    fn record(&mut self, item: &BatchItem) {
+        match &item.result {
+            Ok(result) => {
+                self.succeeded += 1;
+                self.audio_duration += result.audio_duration;
+            }
+            Err(e) => self.failures.push(BatchFailure {
+                path: item.path.clone(),
+                error: e.to_string(),
+            }),
+        }
+    }
+}
+
+/// 按 glob 模式收集待转录文件，结果按路径排序
+///
+/// 传入目录时收集该目录下的所有文件（不递归）。
+pub fn collect_files(pattern: &str) -> SttResult<Vec<PathBuf>> {
+    let pattern = if Path::new(pattern).is_dir() {
+        Path::new(pattern).join("*").to_string_lossy().into_owned()
+    } else {
+        pattern.to_string()
+    };
+
+    let entries = glob::glob(&pattern)
+        .map_err(|e| SttError::ConfigError(format!("无效的文件匹配模式 {pattern}: {e}")))?;
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| SttError::IoError(e.into_error()))?;
+        if path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+impl WhisperTranscriber {
+    /// 并行转录多个文件
+    ///
+    /// 模型只加载一次；`config.max_concurrency` 个工作任务各持有一个解码状态，
+    /// 并发数同时受转录器的 `max_concurrent_inference` 限制。
+    /// 每个文件完成后按完成顺序调用 `on_item`，单个文件失败（包括推理线程崩溃）不会中断整个批次。
+    pub async fn transcribe_batch<I, P, F>(
+        &self,
+        paths: I,
+        config: &BatchConfig,
+        mut on_item: F,
+    ) -> SttResult<BatchSummary>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+        F: FnMut(&BatchItem),
+    {
+        let start = std::time::Instant::now();
+        let queue: VecDeque<(usize, PathBuf)> = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_path_buf())
+            .enumerate()
+            .collect();
+
+        let mut summary = BatchSummary {
+            total_files: queue.len(),
+            ..Default::default()
+        };
+        if queue.is_empty() {
+            return Ok(summary);
+        }
+
+        let workers = batch_workers(
+            config.max_concurrency,
+            self.config().max_concurrent_inference,
+            queue.len(),
+        );
+        info!("开始批量转录: {}个文件，并发数 {}", queue.len(), workers);
+
+        // 先创建好状态池，创建失败时直接返回错误
+        let states = (0..workers)
+            .map(|_| self.create_state())
+            .collect::<SttResult<Vec<_>>>()?;
+
+        let queue = Arc::new(Mutex::new(queue));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        for state in states {
+            let transcriber = self.clone();
+            let queue = Arc::clone(&queue);
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let mut state = Some(state);
+                while let Some((index, path)) = next_file(&queue) {
+                    let result = transcribe_batch_file(&transcriber, &mut state, &path).await;
+                    let item = BatchItem {
+                        index,
+                        path,
+                        result,
+                    };
+                    if tx.send(item).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        while let Some(item) = rx.recv().await {
+            if let Err(e) = &item.result {
+                warn!("转录失败 {}: {e}", item.path.display());
+            }
+            summary.record(&item);
+            on_item(&item);
+        }
+
+        summary.processing_time = start.elapsed().as_millis() as u64;
+        info!(
+            "批量转录完成: 成功 {}/{}，音频 {:.2} 小时，整体实时因子: {:.3}x",
+            summary.succeeded,
+            summary.total_files,
+            summary.audio_hours(),
+            summary.real_time_factor()
+        );
+
+        Ok(summary)
+    }
+
+    /// 按 glob 模式（如 `recordings/*.wav`）或目录并行转录文件
+    pub async fn transcribe_glob<F>(
+        &self,
+        pattern: &str,
+        config: &BatchConfig,
+        on_item: F,
+    ) -> SttResult<BatchSummary>
+    where
+        F: FnMut(&BatchItem),
+    {
+        let files = collect_files(pattern)?;
+        self.transcribe_batch(files, config, on_item).await
+    }
+}
+
+/// 转录批次中的一个文件：先在阻塞线程上转换并读入音频，只有解码占用推理名额
+async fn transcribe_batch_file(
+    transcriber: &WhisperTranscriber,
+    state: &mut Option<WhisperState>,
+    path: &Path,
+) -> SttResult<TranscriptionResult> {
+    let audio_data = load_audio_file(path).await?;
+
+    // 推理线程崩溃后状态随之丢失，为下一个文件重新创建
+    let worker_state = match state.take() {
+        Some(worker_state) => worker_state,
+        None => transcriber.create_state()?,
+    };
+    let job_transcriber = transcriber.clone();
+    let (worker_state, result) =
+        run_with_state(transcriber.limiter(), worker_state, move |state| {
+            job_transcriber.transcribe_with_state(
+                state,
+                &audio_data,
+                None,
+                &TranscribeOptions::default(),
+            )
+        })
+        .await;
+    *state = worker_state;
+    result
+}
+
+/// 在推理并发限制下以 `state` 执行 `job`，返回执行后的状态与结果
+///
+/// 推理线程崩溃时状态无法收回（返回 `None`），结果为错误。
+async fn run_with_state<S, T, F>(
+    limiter: &InferenceLimiter,
+    state: S,
+    job: F,
+) -> (Option<S>, SttResult<T>)
+where
+    S: Send + 'static,
+    T: Send + 'static,
+    F: FnOnce(&mut S) -> SttResult<T> + Send + 'static,
+{
+    let result = limiter
+        .run(move || {
+            let mut state = state;
+            let result = job(&mut state);
+            Ok((state, result))
+        })
+        .await;
+    match result {
+        Ok((state, result)) => (Some(state), result),
+        Err(e) => (None, Err(e)),
+    }
+}
+
+/// 批次的工作任务数：请求的并发数受推理并发上限与文件数约束
+///
+/// 推理并发上限小于请求的并发数时记录警告。
+fn batch_workers(max_concurrency: usize, max_concurrent_inference: usize, files: usize) -> usize {
+    if max_concurrent_inference < max_concurrency {
+        warn!(
+            "批量转录并发数 {max_concurrency} 超过推理并发上限 {max_concurrent_inference}，实际并发数降为 {max_concurrent_inference}"
+        );
+    }
+    max_concurrency
+        .min(max_concurrent_inference)
+        .clamp(1, files.max(1))
+}
+
+/// 从共享队列取下一个文件
+fn next_file(queue: &Mutex<VecDeque<(usize, PathBuf)>>) -> Option<(usize, PathBuf)> {
+    queue.lock().ok()?.pop_front()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::whisper::WhisperConfig;
+
+    fn item(path: &str, result: SttResult<TranscriptionResult>) -> BatchItem {
+        BatchItem {
+            index: 0,
+            path: PathBuf::from(path),
+            result,
+        }
+    }
+
+    fn transcription(audio_duration: u64) -> TranscriptionResult {
+        TranscriptionResult {
+            text: "hello".to_string(),
+            language: None,
+            language_detection: None,
+            segments: Vec::new(),
+            processing_time: 0,
+            audio_duration,
+            decisions: Vec::new(),
+            skipped_duration: 0,
+        }
+    }
+
+    #[test]
+    fn test_summary_record() {
+        let mut summary = BatchSummary {
+            total_files: 3,
+            ..Default::default()
+        };
+        summary.record(&item("a.wav", Ok(transcription(1_800_000))));
+        summary.record(&item("b.wav", Ok(transcription(1_800_000))));
+        summary.record(&item(
+            "c.wav",
+            Err(SttError::FileNotFound("c.wav".to_string())),
+        ));
+        summary.processing_time = 360_000;
+
+        assert_eq!(summary.succeeded, 2);
+        assert_eq!(summary.audio_hours(), 1.0);
+        assert!((summary.real_time_factor() - 0.1).abs() < 1e-9);
+        assert_eq!(summary.failures.len(), 1);
+        assert_eq!(summary.failures[0].path, PathBuf::from("c.wav"));
+        assert!(summary.failures[0].error.contains("c.wav"));
+
+        assert_eq!(BatchSummary::default().real_time_factor(), 0.0);
+    }
+
+    #[test]
+    fn test_collect_files() {
+        let dir = std::env::temp_dir().join(format!("stt_batch_test_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        for name in ["b.wav", "a.wav", "notes.txt"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let pattern = dir.join("*.wav").to_string_lossy().into_owned();
+        let files = collect_files(&pattern).unwrap();
+        assert_eq!(files, vec![dir.join("a.wav"), dir.join("b.wav")]);
+
+        // 目录：收集全部文件，不包含子目录
+        let files = collect_files(dir.to_str().unwrap()).unwrap();
+        assert_eq!(files.len(), 3);
+
+        assert!(collect_files("[").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_with_state() {
+        let limiter = InferenceLimiter::new(1);
+        let (state, result) = run_with_state(&limiter, 1, |state| {
+            *state += 1;
+            Ok(*state * 10)
+        })
+        .await;
+        assert_eq!(state, Some(2));
+        assert_eq!(result.unwrap(), 20);
+
+        // 推理线程崩溃时返回失败结果，状态无法收回
+        let (state, result) = run_with_state(&limiter, 1, |_: &mut i32| -> SttResult<()> {
+            panic!("decode")
+        })
+        .await;
+        assert_eq!(state, None);
+        assert!(matches!(result, Err(SttError::TranscriptionError(_))));
+    }
+
+    #[test]
+    fn test_batch_workers() {
+        let default_inference = WhisperConfig::default().max_concurrent_inference;
+        assert_eq!(
+            batch_workers(
+                BatchConfig::default().max_concurrency,
+                default_inference,
+                10
+            ),
+            2
+        );
+        assert_eq!(batch_workers(4, 2, 10), 2);
+        assert_eq!(batch_workers(4, 8, 3), 3);
+        assert_eq!(batch_workers(1, 1, 0), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_default_batch_runs_states_concurrently() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        // 默认配置下的推理并发限制与工作任务数
+        let limiter = InferenceLimiter::new(WhisperConfig::default().max_concurrent_inference);
+        let workers = batch_workers(
+            BatchConfig::default().max_concurrency,
+            WhisperConfig::default().max_concurrent_inference,
+            4,
+        );
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let jobs: Vec<_> = (0..workers)
+            .map(|worker| {
+                let limiter = limiter.clone();
+                let running = Arc::clone(&running);
+                let peak = Arc::clone(&peak);
+                tokio::spawn(async move {
+                    run_with_state(&limiter, worker, move |_state| {
+                        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        std::thread::sleep(Duration::from_millis(100));
+                        running.fetch_sub(1, Ordering::SeqCst);
+                        Ok(())
+                    })
+                    .await
+                })
+            })
+            .collect();
+        for job in jobs {
+            let (state, result) = job.await.unwrap();
+            assert!(state.is_some());
+            result.unwrap();
+        }
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_next_file() {
+        let queue = Mutex::new(VecDeque::from([(0, PathBuf::from("a.wav"))]));
+        assert_eq!(next_file(&queue), Some((0, PathBuf::from("a.wav"))));
+        assert_eq!(next_file(&queue), None);
+    }
+}
@@ -0,0 +1,575 @@
+//! 转录性能基准测试
+//!
+//! [`run`] 加载模型并对一组音频重复转录，统计模型加载时间、实时因子（RTF）分位数、
+//! 每秒解码 token 数与进程内存峰值。报告可序列化为 JSON，也可以与保存的基线
+//! （JSON 文件，或如 `specs/stt-performance-baseline.md` 这样内嵌 JSON 代码块的 Markdown）比较，
+//! 超出允许百分比的指标标记为性能回退。
+
+use crate::batch::BatchFailure;
+use crate::error::{SttError, SttResult};
+use crate::model::ModelType;
+use crate::progress::TranscribeOptions;
+use crate::whisper::{ComputeBackend, WhisperConfig, WhisperTranscriber};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use sysinfo::System;
+
+/// 内存采样间隔
+const MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_millis(20);
+
+/// 一组数值的分布
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Percentiles {
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl Percentiles {
+    /// 计算分位数（最近秩法）；输入为空时全部为 0
+    pub fn from_values(values: &[f64]) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let rank = |p: f64| {
+            let index = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+            sorted[index.clamp(1, sorted.len()) - 1]
+        };
+
+        Self {
+            min: sorted[0],
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p50: rank(50.0),
+            p90: rank(90.0),
+            p99: rank(99.0),
+            max: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+/// 单次转录的测量结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchRun {
+    /// 音频文件
+    pub input: PathBuf,
+    /// 迭代序号（从 0 开始）
+    pub iteration: usize,
+    /// 音频时长（毫秒）
+    pub audio_duration_ms: u64,
+    /// 转录耗时（毫秒，包含音频读取与转换）
+    pub processing_ms: u64,
+    /// 实时因子（转录耗时 / 音频时长）
+    pub rtf: f64,
+    /// 输出文本的 token 数
+    pub tokens: usize,
+}
+
+/// 基准测试报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchReport {
+    /// 模型文件
+    pub model: PathBuf,
+    /// 模型类型
+    pub model_type: ModelType,
+    /// 推理后端
+    pub backend: ComputeBackend,
+    /// 推理线程数
+    pub n_threads: i32,
+    /// 每个输入的迭代次数
+    pub iterations: usize,
+    /// 模型加载耗时（毫秒）
+    pub model_load_ms: u64,
+    /// 实时因子分布
+    pub rtf: Percentiles,
+    /// 单次转录耗时分布（毫秒）
+    pub processing_ms: Percentiles,
+    /// 每秒输出的 token 数（全部运行累计）
+    pub tokens_per_second: f64,
+    /// 进程内存峰值（MB，含模型加载）
+    pub peak_memory_mb: f64,
+    /// 每次运行的测量结果
+    pub runs: Vec<BenchRun>,
+    /// 转录失败的运行
+    pub failures: Vec<BatchFailure>,
+}
+
+impl BenchReport {
+    /// 输出为格式化的 JSON
+    pub fn to_json(&self) -> SttResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// 与基线比较，变差超过 `threshold_percent`% 的指标标记为回退
+    pub fn compare(&self, baseline: &BenchBaseline, threshold_percent: f64) -> BenchComparison {
+        let current = BenchBaseline::from(self);
+        let metrics = [
+            ("rtf_p50", baseline.rtf_p50, current.rtf_p50, false),
+            ("rtf_p90", baseline.rtf_p90, current.rtf_p90, false),
+            (
+                "model_load_ms",
+                baseline.model_load_ms,
+                current.model_load_ms,
+                false,
+            ),
+            (
+                "peak_memory_mb",
+                baseline.peak_memory_mb,
+                current.peak_memory_mb,
+                false,
+            ),
+            (
+                "tokens_per_second",
+                baseline.tokens_per_second,
+                current.tokens_per_second,
+                true,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, baseline, current, higher_is_better)| {
+            let (baseline, current) = (baseline?, current?);
+            Some(MetricComparison::new(
+                name,
+                baseline,
+                current,
+                higher_is_better,
+                threshold_percent,
+            ))
+        })
+        .collect();
+
+        BenchComparison {
+            threshold_percent,
+            metrics,
+        }
+    }
+}
+
+/// 性能基线：用于比较的关键指标，缺失的指标不参与比较
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BenchBaseline {
+    #[serde(default)]
+    pub rtf_p50: Option<f64>,
+    #[serde(default)]
+    pub rtf_p90: Option<f64>,
+    #[serde(default)]
+    pub model_load_ms: Option<f64>,
+    #[serde(default)]
+    pub peak_memory_mb: Option<f64>,
+    #[serde(default)]
+    pub tokens_per_second: Option<f64>,
+}
+
+impl From<&BenchReport> for BenchBaseline {
+    fn from(report: &BenchReport) -> Self {
+        Self {
+            rtf_p50: Some(report.rtf.p50),
+            rtf_p90: Some(report.rtf.p90),
+            model_load_ms: Some(report.model_load_ms as f64),
+            peak_memory_mb: Some(report.peak_memory_mb),
+            tokens_per_second: Some(report.tokens_per_second),
+        }
+    }
+}
+
+impl BenchBaseline {
+    /// 读取基线文件：`.md` 文件取第一个 ```json 代码块，其他文件整体按 JSON 解析
+    pub fn load<P: AsRef<Path>>(path: P) -> SttResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        if path.extension().is_some_and(|ext| ext == "md") {
+            Self::from_markdown(&content)
+        } else {
+            Ok(serde_json::from_str(&content)?)
+        }
+    }
+
+    /// 从 Markdown 中第一个 ```json 代码块解析基线
+    pub fn from_markdown(markdown: &str) -> SttResult<Self> {
+        let block = markdown
+            .split_once("```json")
+            .and_then(|(_, rest)| rest.split_once("```"))
+            .map(|(block, _)| block)
+            .ok_or_else(|| SttError::ConfigError("基线文档中没有 ```json 代码块".to_string()))?;
+        Ok(serde_json::from_str(block)?)
+    }
+
+    /// 以 JSON 保存基线
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> SttResult<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// 单项指标的比较结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricComparison {
+    /// 指标名称
+    pub name: String,
+    /// 基线值
+    pub baseline: f64,
+    /// 当前值
+    pub current: f64,
+    /// 相对基线的变化百分比（正数表示数值变大）
+    pub change_percent: f64,
+    /// 是否变差超过阈值
+    pub regressed: bool,
+}
+
+impl MetricComparison {
+    fn new(
+        name: &str,
+        baseline: f64,
+        current: f64,
+        higher_is_better: bool,
+        threshold_percent: f64,
+    ) -> Self {
+        let change_percent = if baseline == 0.0 {
+            0.0
+        } else {
+            (current - baseline) / baseline * 100.0
+        };
+        let worse_percent = if higher_is_better {
+            -change_percent
+        } else {
+            change_percent
+        };
+
+        Self {
+            name: name.to_string(),
+            baseline,
+            current,
+            change_percent,
+            regressed: worse_percent > threshold_percent,
+        }
+    }
+}
+
+/// 与基线的比较结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchComparison {
+    /// 允许的变差百分比
+    pub threshold_percent: f64,
+    /// 各项指标
+    pub metrics: Vec<MetricComparison>,
+}
+
+impl BenchComparison {
+    /// 是否存在性能回退
+    pub fn has_regressions(&self) -> bool {
+        self.metrics.iter().any(|metric| metric.regressed)
+    }
+
+    /// 回退的指标
+    pub fn regressions(&self) -> impl Iterator<Item = &MetricComparison> {
+        self.metrics.iter().filter(|metric| metric.regressed)
+    }
+}
+
+/// 运行基准测试
+///
+/// 加载模型（计入加载耗时），用第一个输入预热一次，然后对每个输入各转录 `iterations` 次。
+/// 整个过程在后台线程中采样进程内存，得到内存峰值。
+pub async fn run<I, P>(
+    config: WhisperConfig,
+    inputs: I,
+    iterations: usize,
+) -> SttResult<BenchReport>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let inputs: Vec<PathBuf> = inputs
+        .into_iter()
+        .map(|path| path.as_ref().to_path_buf())
+        .collect();
+    if inputs.is_empty() {
+        return Err(SttError::ConfigError(
+            "基准测试至少需要一个音频文件".to_string(),
+        ));
+    }
+    let iterations = iterations.max(1);
+
+    let memory = MemorySampler::start();
+
+    let load_start = Instant::now();
+    let transcriber = WhisperTranscriber::new(config)?;
+    let model_load_ms = load_start.elapsed().as_millis() as u64;
+    info!("模型加载耗时: {model_load_ms} ms");
+
+    // 预热，不计入统计
+    if let Err(e) = transcriber.transcribe_file(&inputs[0]).await {
+        warn!("预热失败: {e}");
+    }
+
+    let mut runs = Vec::new();
+    let mut failures = Vec::new();
+    for iteration in 0..iterations {
+        for input in &inputs {
+            let start = Instant::now();
+            match transcribe_counting_tokens(&transcriber, input).await {
+                Ok((audio_duration_ms, tokens)) => {
+                    let processing_ms = start.elapsed().as_millis() as u64;
+                    let rtf = if audio_duration_ms == 0 {
+                        0.0
+                    } else {
+                        processing_ms as f64 / audio_duration_ms as f64
+                    };
+                    info!(
+                        "迭代 {iteration} {}: RTF={rtf:.3}, 用时={processing_ms} ms",
+                        input.display()
+                    );
+                    runs.push(BenchRun {
+                        input: input.clone(),
+                        iteration,
+                        audio_duration_ms,
+                        processing_ms,
+                        rtf,
+                        tokens,
+                    });
+                }
+                Err(e) => {
+                    warn!("迭代 {iteration} {} 失败: {e}", input.display());
+                    failures.push(BatchFailure {
+                        path: input.clone(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    let peak_memory_bytes = memory.stop();
+    if runs.is_empty() {
+        return Err(SttError::TranscriptionError(
+            "基准测试没有成功的运行".to_string(),
+        ));
+    }
+
+    let model_info = transcriber.model_info();
+    let report = summarize(
+        runs,
+        failures,
+        BenchContext {
+            model: model_info.path.clone(),
+            model_type: model_info.model_type,
+            backend: transcriber.config().backend(),
+            n_threads: transcriber.config().n_threads,
+            iterations,
+            model_load_ms,
+            peak_memory_mb: peak_memory_bytes as f64 / (1024.0 * 1024.0),
+        },
+    );
+    info!(
+        "基准测试完成: RTF p50={:.3} p90={:.3}，{:.1} token/s，内存峰值 {:.1} MB",
+        report.rtf.p50, report.rtf.p90, report.tokens_per_second, report.peak_memory_mb
+    );
+
+    Ok(report)
+}
+
+/// 转录一个文件，返回音频时长与输出 token 数
+async fn transcribe_counting_tokens(
+    transcriber: &WhisperTranscriber,
+    input: &Path,
+) -> SttResult<(u64, usize)> {
+    let result = transcriber
+        .transcribe_file_with_options(input, TranscribeOptions::default())
+        .await?;
+    let tokens = transcriber.count_tokens(&result.text)?;
+    Ok((result.audio_duration, tokens))
+}
+
+/// 汇总报告所需的运行环境信息
+struct BenchContext {
+    model: PathBuf,
+    model_type: ModelType,
+    backend: ComputeBackend,
+    n_threads: i32,
+    iterations: usize,
+    model_load_ms: u64,
+    peak_memory_mb: f64,
+}
+
+fn summarize(
+    runs: Vec<BenchRun>,
+    failures: Vec<BatchFailure>,
+    context: BenchContext,
+) -> BenchReport {
+    let rtfs: Vec<f64> = runs.iter().map(|run| run.rtf).collect();
+    let times: Vec<f64> = runs.iter().map(|run| run.processing_ms as f64).collect();
+    let total_tokens: usize = runs.iter().map(|run| run.tokens).sum();
+    let total_ms: u64 = runs.iter().map(|run| run.processing_ms).sum();
+    let tokens_per_second = if total_ms == 0 {
+        0.0
+    } else {
+        total_tokens as f64 * 1000.0 / total_ms as f64
+    };
+
+    BenchReport {
+        model: context.model,
+        model_type: context.model_type,
+        backend: context.backend,
+        n_threads: context.n_threads,
+        iterations: context.iterations,
+        model_load_ms: context.model_load_ms,
+        rtf: Percentiles::from_values(&rtfs),
+        processing_ms: Percentiles::from_values(&times),
+        tokens_per_second,
+        peak_memory_mb: context.peak_memory_mb,
+        runs,
+        failures,
+    }
+}
+
+/// 在后台线程中周期性采样当前进程的常驻内存，记录峰值
+struct MemorySampler {
+    stop: Arc<AtomicBool>,
+    peak: Arc<AtomicU64>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MemorySampler {
+    fn start() -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let peak = Arc::new(AtomicU64::new(0));
+        let handle = sysinfo::get_current_pid().ok().map(|pid| {
+            let stop = Arc::clone(&stop);
+            let peak = Arc::clone(&peak);
+            std::thread::spawn(move || {
+                let mut system = System::new();
+                loop {
+                    if system.refresh_process(pid) {
+                        if let Some(process) = system.process(pid) {
+                            peak.fetch_max(process.memory(), Ordering::Relaxed);
+                        }
+                    }
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    std::thread::sleep(MEMORY_SAMPLE_INTERVAL);
+                }
+            })
+        });
+
+        Self { stop, peak, handle }
+    }
+
+    /// 停止采样，返回内存峰值（字节）
+    fn stop(mut self) -> u64 {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+        self.peak.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bench_run(processing_ms: u64, tokens: usize) -> BenchRun {
+        BenchRun {
+            input: PathBuf::from("a.wav"),
+            iteration: 0,
+            audio_duration_ms: 10_000,
+            processing_ms,
+            rtf: processing_ms as f64 / 10_000.0,
+            tokens,
+        }
+    }
+
+    fn report(runs: Vec<BenchRun>) -> BenchReport {
+        summarize(
+            runs,
+            Vec::new(),
+            BenchContext {
+                model: PathBuf::from("models/ggml-tiny.bin"),
+                model_type: ModelType::Tiny,
+                backend: ComputeBackend::Cpu,
+                n_threads: 4,
+                iterations: 1,
+                model_load_ms: 200,
+                peak_memory_mb: 100.0,
+            },
+        )
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let values: Vec<f64> = (1..=10).map(f64::from).collect();
+        let percentiles = Percentiles::from_values(&values);
+        assert_eq!(percentiles.min, 1.0);
+        assert_eq!(percentiles.p50, 5.0);
+        assert_eq!(percentiles.p90, 9.0);
+        assert_eq!(percentiles.p99, 10.0);
+        assert_eq!(percentiles.max, 10.0);
+        assert_eq!(percentiles.mean, 5.5);
+        assert_eq!(Percentiles::from_values(&[]), Percentiles::default());
+    }
+
+    #[test]
+    fn test_summarize_and_json() {
+        let report = report(vec![bench_run(1000, 50), bench_run(3000, 50)]);
+        assert_eq!(report.rtf.p50, 0.1);
+        assert_eq!(report.processing_ms.max, 3000.0);
+        assert_eq!(report.tokens_per_second, 25.0);
+
+        let json = report.to_json().unwrap();
+        let parsed: BenchReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, report);
+    }
+
+    #[test]
+    fn test_compare_with_baseline() {
+        let report = report(vec![bench_run(1000, 50)]);
+        let baseline = BenchBaseline {
+            rtf_p50: Some(0.08),
+            model_load_ms: Some(190.0),
+            tokens_per_second: Some(60.0),
+            ..Default::default()
+        };
+
+        let comparison = report.compare(&baseline, 10.0);
+        assert_eq!(comparison.metrics.len(), 3);
+        let regressed: Vec<&str> = comparison.regressions().map(|m| m.name.as_str()).collect();
+        // RTF 变慢 25%，token 速率下降约 17%；加载时间只慢约 5%
+        assert_eq!(regressed, vec!["rtf_p50", "tokens_per_second"]);
+        assert!((comparison.metrics[0].change_percent - 25.0).abs() < 1e-9);
+
+        let comparison = report.compare(&BenchBaseline::from(&report), 10.0);
+        assert!(!comparison.has_regressions());
+    }
+
+    #[test]
+    fn test_baseline_from_markdown() {
+        let markdown = "# 基线\n\n```json\n{\"rtf_p50\": 0.019, \"peak_memory_mb\": 18.0}\n```\n";
+        let baseline = BenchBaseline::from_markdown(markdown).unwrap();
+        assert_eq!(baseline.rtf_p50, Some(0.019));
+        assert_eq!(baseline.peak_memory_mb, Some(18.0));
+        assert_eq!(baseline.tokens_per_second, None);
+        assert!(BenchBaseline::from_markdown("没有代码块").is_err());
+
+        // 未记录的指标（null）不参与比较
+        let markdown = "```json\n{\"rtf_p50\": 0.019, \"peak_memory_mb\": null}\n```";
+        let baseline = BenchBaseline::from_markdown(markdown).unwrap();
+        assert_eq!(baseline.peak_memory_mb, None);
+    }
+
+    #[test]
+    fn test_memory_sampler() {
+        let sampler = MemorySampler::start();
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(sampler.stop() > 0);
+    }
+}
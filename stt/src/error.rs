@@ -51,6 +51,14 @@ pub enum SttError {
     #[error("IO错误: {0}")]
     IoError(#[from] std::io::Error),
 
+    /// 序列化错误
+    #[error("序列化错误: {0}")]
+    SerializationError(String),
+
+    /// 任务已被取消
+    #[error("转录已取消")]
+    Cancelled,
+
     /// 其他错误
     #[error("其他错误: {0}")]
     Other(String),
@@ -80,6 +88,13 @@ impl From<rubato::ResampleError> for SttError {
     }
 }
 
+/// 从serde_json错误转换
+impl From<serde_json::Error> for SttError {
+    fn from(err: serde_json::Error) -> Self {
+        SttError::SerializationError(err.to_string())
+    }
+}
+
 // 注意：如需从外部音频库错误转换，请在调用处进行显式映射，避免耦合具体错误类型
 
 /// 错误辅助函数
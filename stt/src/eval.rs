@@ -0,0 +1,687 @@
+//! 识别准确率评估
+//!
+//! 计算词错误率（WER）与字错误率（CER，用于中文），给出插入/删除/替换的分解与逐词对齐；
+//! 并提供按清单批量评估的运行器：清单列出音频与参考文本，结果按文件与整体汇总为 JSON，
+//! 可用于在更换模型或配置时按准确率把关。
+
+use crate::batch::BatchConfig;
+use crate::error::{SttError, SttResult};
+use crate::text::display_width;
+use crate::whisper::WhisperTranscriber;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 文本规范化规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextNormalizer {
+    /// 只合并空白
+    None,
+    /// 英文：转小写，连字符视为空格，去掉标点（保留词内撇号）
+    #[default]
+    English,
+    /// 中文：全角转半角，转小写，去掉标点与空白
+    Chinese,
+}
+
+impl TextNormalizer {
+    /// 根据语言代码选择规则：中文、粤语、日语按字评估，其余按词评估
+    pub fn for_language(language: Option<&str>) -> Self {
+        match language {
+            Some("zh" | "yue" | "ja") => TextNormalizer::Chinese,
+            _ => TextNormalizer::English,
+        }
+    }
+
+    /// 该规则默认的评估单位
+    pub fn default_unit(&self) -> ErrorUnit {
+        match self {
+            TextNormalizer::Chinese => ErrorUnit::Char,
+            TextNormalizer::None | TextNormalizer::English => ErrorUnit::Word,
+        }
+    }
+
+    /// 规范化文本
+    pub fn normalize(&self, text: &str) -> String {
+        let normalized: String = match self {
+            TextNormalizer::None => text.to_string(),
+            TextNormalizer::English => normalize_english(text),
+            TextNormalizer::Chinese => text
+                .chars()
+                .map(to_halfwidth)
+                .flat_map(char::to_lowercase)
+                .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+                .collect(),
+        };
+        normalized.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// 规范化并切分为评估单位
+    pub fn tokenize(&self, text: &str, unit: ErrorUnit) -> Vec<String> {
+        let normalized = self.normalize(text);
+        match unit {
+            ErrorUnit::Word => normalized.split_whitespace().map(str::to_string).collect(),
+            ErrorUnit::Char => normalized
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(String::from)
+                .collect(),
+        }
+    }
+}
+
+/// 英文规范化
+fn normalize_english(text: &str) -> String {
+    let chars: Vec<char> = text
+        .chars()
+        .map(|c| if matches!(c, '‘' | '’') { '\'' } else { c })
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            if c.is_alphanumeric() || c.is_whitespace() {
+                c
+            } else if c == '\'' {
+                // 只保留词内撇号（如 don't）
+                let inside = i > 0
+                    && chars[i - 1].is_alphanumeric()
+                    && chars.get(i + 1).is_some_and(|n| n.is_alphanumeric());
+                if inside {
+                    c
+                } else {
+                    ' '
+                }
+            } else {
+                ' '
+            }
+        })
+        .collect()
+}
+
+/// 全角字符转半角
+fn to_halfwidth(c: char) -> char {
+    match c {
+        '\u{3000}' => ' ',
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// 评估单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorUnit {
+    /// 按词（WER）
+    Word,
+    /// 按字（CER）
+    Char,
+}
+
+/// 编辑操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EditOp {
+    /// 一致
+    Match,
+    /// 替换
+    Substitution,
+    /// 删除（参考中有、识别结果中没有）
+    Deletion,
+    /// 插入（识别结果中多出）
+    Insertion,
+}
+
+/// 对齐中的一步
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlignmentStep {
+    /// 编辑操作
+    pub op: EditOp,
+    /// 参考文本中的单位（插入时为 None）
+    pub reference: Option<String>,
+    /// 识别结果中的单位（删除时为 None）
+    pub hypothesis: Option<String>,
+}
+
+/// 错误计数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorCounts {
+    /// 参考文本的单位数
+    pub reference_len: usize,
+    /// 一致的单位数
+    pub hits: usize,
+    /// 替换数
+    pub substitutions: usize,
+    /// 删除数
+    pub deletions: usize,
+    /// 插入数
+    pub insertions: usize,
+}
+
+impl ErrorCounts {
+    /// 错误总数
+    pub fn errors(&self) -> usize {
+        self.substitutions + self.deletions + self.insertions
+    }
+
+    /// 错误率：(S + D + I) / N；参考为空时，有插入记为 1.0
+    pub fn error_rate(&self) -> f64 {
+        if self.reference_len == 0 {
+            return if self.insertions > 0 { 1.0 } else { 0.0 };
+        }
+        self.errors() as f64 / self.reference_len as f64
+    }
+
+    /// 累加另一组计数
+    pub fn add(&mut self, other: &ErrorCounts) {
+        self.reference_len += other.reference_len;
+        self.hits += other.hits;
+        self.substitutions += other.substitutions;
+        self.deletions += other.deletions;
+        self.insertions += other.insertions;
+    }
+}
+
+/// 一对文本的评估结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Evaluation {
+    /// 评估单位
+    pub unit: ErrorUnit,
+    /// 错误计数
+    pub counts: ErrorCounts,
+    /// 错误率
+    pub error_rate: f64,
+    /// 逐单位对齐
+    pub alignment: Vec<AlignmentStep>,
+}
+
+impl Evaluation {
+    /// 以三行文本展示对齐：参考、识别结果与操作（S/D/I）
+    pub fn alignment_text(&self) -> String {
+        let mut reference = Vec::new();
+        let mut hypothesis = Vec::new();
+        let mut ops = Vec::new();
+        for step in &self.alignment {
+            let r = step.reference.as_deref().unwrap_or("*");
+            let h = step.hypothesis.as_deref().unwrap_or("*");
+            let width = display_width(r).max(display_width(h));
+            let op = match step.op {
+                EditOp::Match => "",
+                EditOp::Substitution => "S",
+                EditOp::Deletion => "D",
+                EditOp::Insertion => "I",
+            };
+            reference.push(pad(r, width));
+            hypothesis.push(pad(h, width));
+            ops.push(pad(op, width));
+        }
+        format!(
+            "REF: {}\nHYP: {}\nOPS: {}",
+            reference.join(" "),
+            hypothesis.join(" "),
+            ops.join(" ").trim_end()
+        )
+    }
+}
+
+fn pad(text: &str, width: usize) -> String {
+    format!("{text}{}", " ".repeat(width - display_width(text)))
+}
+
+/// 计算两个文本的错误率，评估单位由规范化规则决定（英文按词，中文按字）
+pub fn evaluate(reference: &str, hypothesis: &str, normalizer: TextNormalizer) -> Evaluation {
+    evaluate_with_unit(reference, hypothesis, normalizer, normalizer.default_unit())
+}
+
+/// 词错误率（WER）
+pub fn word_error_rate(
+    reference: &str,
+    hypothesis: &str,
+    normalizer: TextNormalizer,
+) -> Evaluation {
+    evaluate_with_unit(reference, hypothesis, normalizer, ErrorUnit::Word)
+}
+
+/// 字错误率（CER）
+pub fn char_error_rate(
+    reference: &str,
+    hypothesis: &str,
+    normalizer: TextNormalizer,
+) -> Evaluation {
+    evaluate_with_unit(reference, hypothesis, normalizer, ErrorUnit::Char)
+}
+
+/// 按指定单位计算错误率
+pub fn evaluate_with_unit(
+    reference: &str,
+    hypothesis: &str,
+    normalizer: TextNormalizer,
+    unit: ErrorUnit,
+) -> Evaluation {
+    let reference = normalizer.tokenize(reference, unit);
+    let hypothesis = normalizer.tokenize(hypothesis, unit);
+    let alignment = align(&reference, &hypothesis);
+
+    let mut counts = ErrorCounts {
+        reference_len: reference.len(),
+        ..Default::default()
+    };
+    for step in &alignment {
+        match step.op {
+            EditOp::Match => counts.hits += 1,
+            EditOp::Substitution => counts.substitutions += 1,
+            EditOp::Deletion => counts.deletions += 1,
+            EditOp::Insertion => counts.insertions += 1,
+        }
+    }
+
+    Evaluation {
+        unit,
+        error_rate: counts.error_rate(),
+        counts,
+        alignment,
+    }
+}
+
+/// 最小编辑距离对齐（Levenshtein），编辑代价相同时优先替换，其次删除、插入
+fn align(reference: &[String], hypothesis: &[String]) -> Vec<AlignmentStep> {
+    let n = reference.len();
+    let m = hypothesis.len();
+    let width = m + 1;
+    let mut cost = vec![0u32; (n + 1) * width];
+    for i in 0..=n {
+        cost[i * width] = i as u32;
+    }
+    for (j, cell) in cost.iter_mut().take(width).enumerate() {
+        *cell = j as u32;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let same = reference[i - 1] == hypothesis[j - 1];
+            let diagonal = cost[(i - 1) * width + j - 1] + u32::from(!same);
+            let deletion = cost[(i - 1) * width + j] + 1;
+            let insertion = cost[i * width + j - 1] + 1;
+            cost[i * width + j] = diagonal.min(deletion).min(insertion);
+        }
+    }
+
+    // 回溯
+    let mut steps = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        let current = cost[i * width + j];
+        if i > 0 && j > 0 {
+            let same = reference[i - 1] == hypothesis[j - 1];
+            if current == cost[(i - 1) * width + j - 1] + u32::from(!same) {
+                steps.push(AlignmentStep {
+                    op: if same {
+                        EditOp::Match
+                    } else {
+                        EditOp::Substitution
+                    },
+                    reference: Some(reference[i - 1].clone()),
+                    hypothesis: Some(hypothesis[j - 1].clone()),
+                });
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+        if i > 0 && current == cost[(i - 1) * width + j] + 1 {
+            steps.push(AlignmentStep {
+                op: EditOp::Deletion,
+                reference: Some(reference[i - 1].clone()),
+                hypothesis: None,
+            });
+            i -= 1;
+        } else {
+            steps.push(AlignmentStep {
+                op: EditOp::Insertion,
+                reference: None,
+                hypothesis: Some(hypothesis[j - 1].clone()),
+            });
+            j -= 1;
+        }
+    }
+    steps.reverse();
+    steps
+}
+
+/// 评估清单中的一项
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// 音频文件路径（相对路径相对于清单文件所在目录）
+    pub audio: PathBuf,
+    /// 参考文本
+    pub reference: String,
+}
+
+/// 读取评估清单（JSON Lines，每行 `{"audio": "...", "reference": "..."}`，空行与 `#` 开头的行被忽略）
+pub fn load_manifest<P: AsRef<Path>>(path: P) -> SttResult<Vec<ManifestEntry>> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)?;
+    let base = path.parent().unwrap_or(Path::new(""));
+
+    let mut entries = Vec::new();
+    for (line_no, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut entry: ManifestEntry = serde_json::from_str(line).map_err(|e| {
+            SttError::SerializationError(format!(
+                "清单 {} 第 {} 行解析失败: {e}",
+                path.display(),
+                line_no + 1
+            ))
+        })?;
+        if entry.audio.is_relative() {
+            entry.audio = base.join(&entry.audio);
+        }
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// 清单评估配置
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct EvalConfig {
+    /// 文本规范化规则
+    pub normalizer: TextNormalizer,
+    /// 评估单位；None 时由规范化规则决定
+    pub unit: Option<ErrorUnit>,
+    /// 是否在报告中包含逐文件对齐
+    pub include_alignment: bool,
+    /// 并行转录配置
+    pub batch: BatchConfig,
+}
+
+impl EvalConfig {
+    /// 设置文本规范化规则
+    pub fn with_normalizer(mut self, normalizer: TextNormalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// 设置评估单位
+    pub fn with_unit(mut self, unit: ErrorUnit) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    /// 设置是否在报告中包含对齐
+    pub fn with_alignment(mut self, include: bool) -> Self {
+        self.include_alignment = include;
+        self
+    }
+
+    /// 设置并行转录配置
+    pub fn with_batch(mut self, batch: BatchConfig) -> Self {
+        self.batch = batch;
+        self
+    }
+
+    fn unit(&self) -> ErrorUnit {
+        self.unit.unwrap_or_else(|| self.normalizer.default_unit())
+    }
+}
+
+/// 单个文件的评估结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileScore {
+    /// 音频文件路径
+    pub audio: PathBuf,
+    /// 参考文本
+    pub reference: String,
+    /// 识别结果（转录失败时为 None）
+    pub hypothesis: Option<String>,
+    /// 错误计数
+    pub counts: ErrorCounts,
+    /// 错误率
+    pub error_rate: f64,
+    /// 对齐（未启用时为空）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alignment: Vec<AlignmentStep>,
+    /// 转录错误
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 清单评估报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalReport {
+    /// 评估单位
+    pub unit: ErrorUnit,
+    /// 文本规范化规则
+    pub normalizer: TextNormalizer,
+    /// 按清单顺序的逐文件结果
+    pub files: Vec<FileScore>,
+    /// 全部文件累加的错误计数（转录失败的文件按全部删除计入）
+    pub aggregate: ErrorCounts,
+    /// 整体错误率
+    pub error_rate: f64,
+    /// 转录失败的文件数
+    pub failed_files: usize,
+}
+
+impl EvalReport {
+    /// 由逐文件结果汇总报告
+    pub fn from_files(files: Vec<FileScore>, config: &EvalConfig) -> Self {
+        let mut aggregate = ErrorCounts::default();
+        for file in &files {
+            aggregate.add(&file.counts);
+        }
+        Self {
+            unit: config.unit(),
+            normalizer: config.normalizer,
+            failed_files: files.iter().filter(|file| file.error.is_some()).count(),
+            error_rate: aggregate.error_rate(),
+            aggregate,
+            files,
+        }
+    }
+
+    /// 整体错误率不超过 `max_error_rate` 且没有转录失败的文件
+    pub fn passes(&self, max_error_rate: f64) -> bool {
+        self.failed_files == 0 && self.error_rate <= max_error_rate
+    }
+
+    /// 输出为格式化的 JSON
+    pub fn to_json(&self) -> SttResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// 对一个文件的识别结果打分；转录失败时参考文本全部计为删除
+fn score_file(
+    entry: &ManifestEntry,
+    hypothesis: Result<String, String>,
+    config: &EvalConfig,
+) -> FileScore {
+    let (hypothesis, error) = match hypothesis {
+        Ok(text) => (Some(text), None),
+        Err(e) => (None, Some(e)),
+    };
+    let evaluation = evaluate_with_unit(
+        &entry.reference,
+        hypothesis.as_deref().unwrap_or(""),
+        config.normalizer,
+        config.unit(),
+    );
+
+    FileScore {
+        audio: entry.audio.clone(),
+        reference: entry.reference.clone(),
+        hypothesis,
+        counts: evaluation.counts,
+        error_rate: evaluation.error_rate,
+        alignment: if config.include_alignment {
+            evaluation.alignment
+        } else {
+            Vec::new()
+        },
+        error,
+    }
+}
+
+impl WhisperTranscriber {
+    /// 转录清单中的所有音频并与参考文本比对
+    pub async fn evaluate_manifest(
+        &self,
+        entries: &[ManifestEntry],
+        config: &EvalConfig,
+    ) -> SttResult<EvalReport> {
+        let mut hypotheses: Vec<Option<Result<String, String>>> = vec![None; entries.len()];
+        self.transcribe_batch(
+            entries.iter().map(|entry| &entry.audio),
+            &config.batch,
+            |item| {
+                let text = match &item.result {
+                    Ok(result) => Ok(result.text.clone()),
+                    Err(e) => Err(e.to_string()),
+                };
+                hypotheses[item.index] = Some(text);
+            },
+        )
+        .await?;
+
+        let files = entries
+            .iter()
+            .zip(hypotheses)
+            .map(|(entry, hypothesis)| {
+                let hypothesis = hypothesis.unwrap_or_else(|| Err("未返回转录结果".to_string()));
+                score_file(entry, hypothesis, config)
+            })
+            .collect();
+
+        Ok(EvalReport::from_files(files, config))
+    }
+
+    /// 读取清单文件并评估
+    pub async fn evaluate_manifest_file<P: AsRef<Path>>(
+        &self,
+        manifest: P,
+        config: &EvalConfig,
+    ) -> SttResult<EvalReport> {
+        let entries = load_manifest(manifest)?;
+        self.evaluate_manifest(&entries, config).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_error_rate_breakdown() {
+        let evaluation = word_error_rate(
+            "The quick brown fox jumps",
+            "the quick brown box jumps high",
+            TextNormalizer::English,
+        );
+        assert_eq!(evaluation.counts.substitutions, 1);
+        assert_eq!(evaluation.counts.insertions, 1);
+        assert_eq!(evaluation.counts.deletions, 0);
+        assert_eq!(evaluation.counts.hits, 4);
+        assert!((evaluation.error_rate - 0.4).abs() < 1e-9);
+        assert_eq!(evaluation.alignment[3].op, EditOp::Substitution);
+        assert_eq!(evaluation.alignment[5].op, EditOp::Insertion);
+
+        let evaluation = word_error_rate("a b c", "a c", TextNormalizer::English);
+        assert_eq!(evaluation.counts.deletions, 1);
+        assert_eq!(
+            evaluation.alignment[1],
+            AlignmentStep {
+                op: EditOp::Deletion,
+                reference: Some("b".to_string()),
+                hypothesis: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_char_error_rate_chinese() {
+        let evaluation = evaluate(
+            "今天天气很好，我们去公园。",
+            "今天天汽很好我们去公园吧",
+            TextNormalizer::Chinese,
+        );
+        assert_eq!(evaluation.unit, ErrorUnit::Char);
+        assert_eq!(evaluation.counts.reference_len, 11);
+        assert_eq!(evaluation.counts.substitutions, 1);
+        assert_eq!(evaluation.counts.insertions, 1);
+        assert!((evaluation.error_rate - 2.0 / 11.0).abs() < 1e-9);
+        assert!(evaluation.alignment_text().contains("OPS:"));
+    }
+
+    #[test]
+    fn test_normalization_rules() {
+        assert_eq!(
+            TextNormalizer::English.normalize("Don't stop -- it's 'well-known'!"),
+            "don't stop it's well known"
+        );
+        assert_eq!(
+            TextNormalizer::Chinese.normalize("ＡＢＣ１２３，你好！ Hello。"),
+            "abc123你好 hello"
+        );
+        assert_eq!(TextNormalizer::None.normalize("  A,  b "), "A, b");
+        assert_eq!(
+            TextNormalizer::for_language(Some("zh")),
+            TextNormalizer::Chinese
+        );
+
+        // 规范化后完全一致
+        let evaluation = evaluate("Hello, World!", "hello world", TextNormalizer::English);
+        assert_eq!(evaluation.error_rate, 0.0);
+    }
+
+    #[test]
+    fn test_empty_reference() {
+        assert_eq!(evaluate("", "", TextNormalizer::English).error_rate, 0.0);
+        assert_eq!(
+            evaluate("", "extra", TextNormalizer::English).error_rate,
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_manifest_and_report() {
+        let dir = std::env::temp_dir().join(format!("stt_eval_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let manifest = dir.join("manifest.jsonl");
+        std::fs::write(
+            &manifest,
+            "# 注释\n{\"audio\": \"a.wav\", \"reference\": \"hello world\"}\n\n{\"audio\": \"/abs/b.wav\", \"reference\": \"good morning\"}\n",
+        )
+        .unwrap();
+
+        let entries = load_manifest(&manifest).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].audio, dir.join("a.wav"));
+        assert_eq!(entries[1].audio, PathBuf::from("/abs/b.wav"));
+
+        let config = EvalConfig::default().with_alignment(true);
+        let files = vec![
+            score_file(&entries[0], Ok("Hello, world.".to_string()), &config),
+            score_file(&entries[1], Err("文件未找到: b.wav".to_string()), &config),
+        ];
+        let report = EvalReport::from_files(files, &config);
+        assert_eq!(report.failed_files, 1);
+        assert_eq!(report.aggregate.reference_len, 4);
+        assert_eq!(report.aggregate.deletions, 2);
+        assert!((report.error_rate - 0.5).abs() < 1e-9);
+        assert!(!report.passes(0.6));
+
+        let json = report.to_json().unwrap();
+        let parsed: EvalReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, report);
+
+        std::fs::write(&manifest, "{\"audio\": 1}\n").unwrap();
+        assert!(matches!(
+            load_manifest(&manifest),
+            Err(SttError::SerializationError(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
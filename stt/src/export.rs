@@ -0,0 +1,656 @@
+//! 转录结果导出
+//!
+//! 将 [`TranscriptionResult`] 渲染为 SRT、WebVTT、TTML、带时间戳的纯文本以及 JSON。
+//! 字幕按每行最大宽度、每条字幕最多行数和最长持续时间切分；
+//! 段内有词级时间戳时按词计时，否则按字数在段内插值。
+
+use crate::error::{SttError, SttResult};
+use crate::text::{display_width, is_cjk, is_cjk_or_fullwidth};
+use crate::whisper::{TranscriptionResult, TranscriptionSegment};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::str::FromStr;
+
+/// JSON 导出格式的版本号，字段发生不兼容变化时递增
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    /// SubRip 字幕
+    Srt,
+    /// WebVTT 字幕
+    WebVtt,
+    /// TTML（W3C Timed Text）字幕
+    Ttml,
+    /// 带时间戳的纯文本，每段一行
+    Text,
+    /// JSON（带版本号的完整转录结果）
+    Json,
+}
+
+impl ExportFormat {
+    /// 文件扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Srt => "srt",
+            ExportFormat::WebVtt => "vtt",
+            ExportFormat::Ttml => "ttml",
+            ExportFormat::Text => "txt",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    /// 根据文件扩展名推断格式
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        path.as_ref()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| ext.parse().ok())
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = SttError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "srt" => Ok(ExportFormat::Srt),
+            "vtt" | "webvtt" => Ok(ExportFormat::WebVtt),
+            "ttml" | "xml" => Ok(ExportFormat::Ttml),
+            "txt" | "text" => Ok(ExportFormat::Text),
+            "json" => Ok(ExportFormat::Json),
+            other => Err(SttError::ConfigError(format!("不支持的导出格式: {other}"))),
+        }
+    }
+}
+
+/// 字幕切分选项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportOptions {
+    /// 每行最大显示宽度（中日韩字符计为 2）
+    pub max_chars_per_line: usize,
+    /// 每条字幕最多行数
+    pub max_lines_per_cue: usize,
+    /// 每条字幕最长持续时间（毫秒）
+    pub max_cue_duration_ms: u64,
+    /// 段内有词级时间戳时是否按词计时
+    pub use_word_timings: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            max_chars_per_line: 42,
+            max_lines_per_cue: 2,
+            max_cue_duration_ms: 7000,
+            use_word_timings: true,
+        }
+    }
+}
+
+impl ExportOptions {
+    /// 设置每行最大显示宽度
+    pub fn with_max_chars_per_line(mut self, max_chars: usize) -> Self {
+        self.max_chars_per_line = max_chars.max(1);
+        self
+    }
+
+    /// 设置每条字幕最多行数
+    pub fn with_max_lines_per_cue(mut self, max_lines: usize) -> Self {
+        self.max_lines_per_cue = max_lines.max(1);
+        self
+    }
+
+    /// 设置每条字幕最长持续时间（毫秒）
+    pub fn with_max_cue_duration_ms(mut self, duration_ms: u64) -> Self {
+        self.max_cue_duration_ms = duration_ms.max(1);
+        self
+    }
+
+    /// 设置是否按词计时
+    pub fn with_word_timings(mut self, enable: bool) -> Self {
+        self.use_word_timings = enable;
+        self
+    }
+}
+
+/// 一条字幕
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cue {
+    /// 开始时间（毫秒）
+    pub start_time: u64,
+    /// 结束时间（毫秒）
+    pub end_time: u64,
+    /// 字幕行
+    pub lines: Vec<String>,
+}
+
+/// 计时单元（一个词或一个中日韩字）
+struct TimedUnit {
+    text: String,
+    start_time: u64,
+    end_time: u64,
+}
+
+/// 将转录结果切分为字幕
+///
+/// 字幕不会跨越段边界；单个词超过行宽时独占一行。
+pub fn build_cues(result: &TranscriptionResult, options: &ExportOptions) -> Vec<Cue> {
+    let max_width = options.max_chars_per_line.max(1);
+    let max_lines = options.max_lines_per_cue.max(1);
+    let mut cues = Vec::new();
+
+    for segment in &result.segments {
+        let mut cue: Option<Cue> = None;
+
+        for unit in segment_units(segment, options.use_word_timings) {
+            if let Some(current) = cue.as_mut() {
+                let too_long =
+                    unit.end_time.saturating_sub(current.start_time) > options.max_cue_duration_ms;
+                if !too_long && append_unit(current, &unit, max_width, max_lines) {
+                    continue;
+                }
+                cues.extend(cue.take());
+            }
+
+            cue = Some(Cue {
+                start_time: unit.start_time,
+                end_time: unit.end_time,
+                lines: vec![unit.text],
+            });
+        }
+
+        cues.extend(cue);
+    }
+
+    cues
+}
+
+/// 尝试把单元追加到字幕末尾，放不下时返回 false
+fn append_unit(cue: &mut Cue, unit: &TimedUnit, max_width: usize, max_lines: usize) -> bool {
+    let Some(line) = cue.lines.last_mut() else {
+        return false;
+    };
+
+    let separator = if needs_space(line, &unit.text) {
+        " "
+    } else {
+        ""
+    };
+    if display_width(line) + display_width(separator) + display_width(&unit.text) <= max_width {
+        line.push_str(separator);
+        line.push_str(&unit.text);
+    } else if cue.lines.len() < max_lines {
+        cue.lines.push(unit.text.clone());
+    } else {
+        return false;
+    }
+
+    cue.end_time = cue.end_time.max(unit.end_time);
+    true
+}
+
+/// 段落的词拼接后（忽略空白）是否与段落文本一致
+fn words_match_text(segment: &TranscriptionSegment) -> bool {
+    if segment.words.is_empty() {
+        return false;
+    }
+    let words = segment
+        .words
+        .iter()
+        .flat_map(|word| word.text.chars())
+        .filter(|c| !c.is_whitespace());
+    let text = segment.text.chars().filter(|c| !c.is_whitespace());
+    words.eq(text)
+}
+
+/// 段内的计时单元：优先使用词级时间戳，否则按字数在段内插值
+///
+/// 词文本与段落文本不一致（如段落经后处理改写）时按段落文本插值，避免输出改写前的文本。
+fn segment_units(segment: &TranscriptionSegment, use_word_timings: bool) -> Vec<TimedUnit> {
+    if use_word_timings && words_match_text(segment) {
+        return segment
+            .words
+            .iter()
+            .map(|word| TimedUnit {
+                text: word.text.clone(),
+                start_time: word.start_time,
+                end_time: word.end_time.max(word.start_time),
+            })
+            .collect();
+    }
+
+    let texts = split_text(&segment.text);
+    let total_chars: usize = texts.iter().map(|t| t.chars().count()).sum();
+    let duration = segment.end_time.saturating_sub(segment.start_time);
+    let mut consumed = 0;
+
+    texts
+        .into_iter()
+        .map(|text| {
+            let start = consumed;
+            consumed += text.chars().count();
+            TimedUnit {
+                start_time: segment.start_time + duration * start as u64 / total_chars as u64,
+                end_time: segment.start_time + duration * consumed as u64 / total_chars as u64,
+                text,
+            }
+        })
+        .collect()
+}
+
+/// 按词切分文本，中日韩文字按字切分，标点附着在前一个单元上
+fn split_text(text: &str) -> Vec<String> {
+    let mut units: Vec<String> = Vec::new();
+    let mut word = String::new();
+    let mut after_space = false;
+
+    for c in text.chars() {
+        let is_space = c.is_whitespace();
+        if is_space {
+            if !word.is_empty() {
+                units.push(std::mem::take(&mut word));
+            }
+        } else if is_cjk(c) {
+            if !word.is_empty() {
+                units.push(std::mem::take(&mut word));
+            }
+            units.push(c.to_string());
+        } else if !c.is_alphanumeric() && word.is_empty() && !after_space && !units.is_empty() {
+            if let Some(last) = units.last_mut() {
+                last.push(c);
+            }
+        } else {
+            word.push(c);
+        }
+        after_space = is_space;
+    }
+    if !word.is_empty() {
+        units.push(word);
+    }
+
+    units
+}
+
+/// 两个单元之间是否需要空格（中日韩字之间不加空格）
+fn needs_space(left: &str, right: &str) -> bool {
+    let left_cjk = left.chars().last().is_some_and(is_cjk_or_fullwidth);
+    let right_cjk = right.chars().next().is_some_and(is_cjk_or_fullwidth);
+    !(left.is_empty() || left_cjk || right_cjk)
+}
+
+/// 格式化时间戳 HH:MM:SS{sep}mmm
+fn format_timestamp(ms: u64, separator: char) -> String {
+    let hours = ms / 3_600_000;
+    let minutes = ms / 60_000 % 60;
+    let seconds = ms / 1000 % 60;
+    let millis = ms % 1000;
+    format!("{hours:02}:{minutes:02}:{seconds:02}{separator}{millis:03}")
+}
+
+/// 渲染为 SRT 字幕
+pub fn to_srt(result: &TranscriptionResult, options: &ExportOptions) -> String {
+    let mut output = String::new();
+    for (index, cue) in build_cues(result, options).iter().enumerate() {
+        let _ = writeln!(output, "{}", index + 1);
+        let _ = writeln!(
+            output,
+            "{} --> {}",
+            format_timestamp(cue.start_time, ','),
+            format_timestamp(cue.end_time, ',')
+        );
+        for line in &cue.lines {
+            let _ = writeln!(output, "{line}");
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// 渲染为 WebVTT 字幕
+pub fn to_webvtt(result: &TranscriptionResult, options: &ExportOptions) -> String {
+    let mut output = String::from("WEBVTT\n\n");
+    for cue in build_cues(result, options) {
+        let _ = writeln!(
+            output,
+            "{} --> {}",
+            format_timestamp(cue.start_time, '.'),
+            format_timestamp(cue.end_time, '.')
+        );
+        for line in &cue.lines {
+            let _ = writeln!(output, "{}", escape_webvtt(line));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// 渲染为 TTML 字幕
+pub fn to_ttml(result: &TranscriptionResult, options: &ExportOptions) -> String {
+    let language = result.language.as_deref().unwrap_or("und");
+    let mut output = String::new();
+    output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        output,
+        "<tt xmlns=\"http://www.w3.org/ns/ttml\" xml:lang=\"{}\">",
+        escape_xml(language)
+    );
+    output.push_str("  <body>\n    <div>\n");
+    for cue in build_cues(result, options) {
+        let lines: Vec<String> = cue.lines.iter().map(|line| escape_xml(line)).collect();
+        let _ = writeln!(
+            output,
+            "      <p begin=\"{}\" end=\"{}\">{}</p>",
+            format_timestamp(cue.start_time, '.'),
+            format_timestamp(cue.end_time, '.'),
+            lines.join("<br/>")
+        );
+    }
+    output.push_str("    </div>\n  </body>\n</tt>\n");
+    output
+}
+
+/// 渲染为带时间戳的纯文本，每段一行
+pub fn to_timestamped_text(result: &TranscriptionResult) -> String {
+    let mut output = String::new();
+    for segment in &result.segments {
+        let _ = writeln!(
+            output,
+            "[{} --> {}] {}",
+            format_timestamp(segment.start_time, '.'),
+            format_timestamp(segment.end_time, '.'),
+            segment.text
+        );
+    }
+    output
+}
+
+/// JSON 导出的外层结构
+#[derive(Serialize)]
+struct JsonExport<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    result: &'a TranscriptionResult,
+}
+
+/// 渲染为 JSON
+///
+/// 输出为带 `schema_version` 的完整转录结果，可用 [`from_json`] 读回。
+pub fn to_json(result: &TranscriptionResult) -> SttResult<String> {
+    let export = JsonExport {
+        schema_version: JSON_SCHEMA_VERSION,
+        result,
+    };
+    Ok(serde_json::to_string_pretty(&export)?)
+}
+
+/// 从 [`to_json`] 的输出读回转录结果
+pub fn from_json(json: &str) -> SttResult<TranscriptionResult> {
+    Ok(serde_json::from_str(json)?)
+}
+
+/// 按指定格式导出
+pub fn export(
+    result: &TranscriptionResult,
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> SttResult<String> {
+    match format {
+        ExportFormat::Srt => Ok(to_srt(result, options)),
+        ExportFormat::WebVtt => Ok(to_webvtt(result, options)),
+        ExportFormat::Ttml => Ok(to_ttml(result, options)),
+        ExportFormat::Text => Ok(to_timestamped_text(result)),
+        ExportFormat::Json => to_json(result),
+    }
+}
+
+/// 导出到文件，格式由文件扩展名决定
+pub fn export_to_file<P: AsRef<Path>>(
+    result: &TranscriptionResult,
+    path: P,
+    options: &ExportOptions,
+) -> SttResult<()> {
+    let path = path.as_ref();
+    let format = ExportFormat::from_path(path).ok_or_else(|| {
+        SttError::ConfigError(format!("无法根据扩展名确定导出格式: {}", path.display()))
+    })?;
+    std::fs::write(path, export(result, format, options)?)?;
+    Ok(())
+}
+
+/// 转义 XML 特殊字符
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 转义 WebVTT 字幕文本中的特殊字符（`&`、`<`、`>`，同时避免出现 `-->`）
+fn escape_webvtt(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::words::TranscriptionWord;
+
+    fn segment(start_time: u64, end_time: u64, text: &str) -> TranscriptionSegment {
+        TranscriptionSegment {
+            start_time,
+            end_time,
+            text: text.to_string(),
+            confidence: 0.9,
+            words: Vec::new(),
+        }
+    }
+
+    fn result(segments: Vec<TranscriptionSegment>) -> TranscriptionResult {
+        TranscriptionResult {
+            text: segments
+                .iter()
+                .map(|s| s.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            language: Some("en".to_string()),
+            language_detection: None,
+            segments,
+            processing_time: 100,
+            audio_duration: 10_000,
+            decisions: Vec::new(),
+            skipped_duration: 0,
+        }
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0, ','), "00:00:00,000");
+        assert_eq!(format_timestamp(3_723_456, '.'), "01:02:03.456");
+    }
+
+    #[test]
+    fn test_srt_and_webvtt() {
+        let result = result(vec![
+            segment(0, 2000, "Hello world."),
+            segment(2500, 4000, "Second <line> & more"),
+        ]);
+        let options = ExportOptions::default();
+
+        let srt = to_srt(&result, &options);
+        assert_eq!(
+            srt,
+            "1\n00:00:00,000 --> 00:00:02,000\nHello world.\n\n\
+             2\n00:00:02,500 --> 00:00:04,000\nSecond <line> & more\n\n"
+        );
+
+        let vtt = to_webvtt(&result, &options);
+        assert_eq!(
+            vtt,
+            "WEBVTT\n\n00:00:00.000 --> 00:00:02.000\nHello world.\n\n\
+             00:00:02.500 --> 00:00:04.000\nSecond &lt;line&gt; &amp; more\n\n"
+        );
+        let arrow = TranscriptionResult {
+            text: "a --> b".to_string(),
+            segments: vec![segment(0, 1000, "a --> b")],
+            ..result.clone()
+        };
+        assert!(to_webvtt(&arrow, &options).contains("\na --&gt; b\n"));
+
+        let ttml = to_ttml(&result, &options);
+        assert!(ttml.contains("xml:lang=\"en\""));
+        assert!(ttml.contains(
+            "<p begin=\"00:00:02.500\" end=\"00:00:04.000\">Second &lt;line&gt; &amp; more</p>"
+        ));
+
+        let text = to_timestamped_text(&result);
+        assert_eq!(
+            text.lines().next(),
+            Some("[00:00:00.000 --> 00:00:02.000] Hello world.")
+        );
+    }
+
+    #[test]
+    fn test_line_breaking_by_width() {
+        let result = result(vec![segment(
+            0,
+            6000,
+            "ask not what your country can do for you ask what you can do for your country",
+        )]);
+        let options = ExportOptions::default()
+            .with_max_chars_per_line(20)
+            .with_max_lines_per_cue(2);
+
+        let cues = build_cues(&result, &options);
+        assert!(cues.len() > 1);
+        for cue in &cues {
+            assert!(cue.lines.len() <= 2);
+            for line in &cue.lines {
+                assert!(line.len() <= 20, "行过长: {line}");
+            }
+        }
+        // 插值得到的时间连续且覆盖整个段
+        assert_eq!(cues.first().unwrap().start_time, 0);
+        assert_eq!(cues.last().unwrap().end_time, 6000);
+        for pair in cues.windows(2) {
+            assert!(pair[0].end_time <= pair[1].start_time);
+        }
+    }
+
+    #[test]
+    fn test_cjk_wrapping() {
+        let result = result(vec![segment(
+            0,
+            4000,
+            "欢迎致电招商银行，请问有什么可以帮您？",
+        )]);
+        let options = ExportOptions::default()
+            .with_max_chars_per_line(16)
+            .with_max_lines_per_cue(1);
+
+        let cues = build_cues(&result, &options);
+        let lines: Vec<_> = cues.iter().flat_map(|c| c.lines.iter()).collect();
+        assert_eq!(
+            lines,
+            vec!["欢迎致电招商银", "行，请问有什么可", "以帮您？"]
+        );
+        for line in &lines {
+            assert!(display_width(line) <= 16, "行过宽: {line}");
+            // 中文之间不插入空格，标点不出现在行首
+            assert!(!line.contains(' '));
+            assert!(!line.starts_with('，') && !line.starts_with('？'));
+        }
+        let joined: String = lines.iter().map(|line| line.as_str()).collect();
+        assert_eq!(joined, "欢迎致电招商银行，请问有什么可以帮您？");
+    }
+
+    #[test]
+    fn test_word_timings_and_duration_limit() {
+        let words = ["one", "two", "three", "four"]
+            .iter()
+            .enumerate()
+            .map(|(i, text)| TranscriptionWord {
+                start_time: i as u64 * 1000,
+                end_time: i as u64 * 1000 + 800,
+                text: text.to_string(),
+                probability: 0.9,
+            })
+            .collect();
+        let mut seg = segment(0, 4000, "one two three four");
+        seg.words = words;
+        let result = result(vec![seg]);
+
+        let options = ExportOptions::default().with_max_cue_duration_ms(2000);
+        let cues = build_cues(&result, &options);
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].lines, vec!["one two"]);
+        assert_eq!((cues[0].start_time, cues[0].end_time), (0, 1800));
+        assert_eq!(cues[1].lines, vec!["three four"]);
+        assert_eq!((cues[1].start_time, cues[1].end_time), (2000, 3800));
+    }
+
+    #[test]
+    fn test_post_processed_text_overrides_words() {
+        let mut seg = segment(0, 2000, "i have twenty five apples");
+        seg.words = seg
+            .text
+            .split(' ')
+            .enumerate()
+            .map(|(i, text)| TranscriptionWord {
+                start_time: i as u64 * 400,
+                end_time: i as u64 * 400 + 400,
+                text: format!(" {text}"),
+                probability: 0.9,
+            })
+            .collect();
+        let mut result = result(vec![seg]);
+        crate::postprocess::PostProcessConfig::default().apply(&mut result);
+
+        // 词文本仍是改写前的内容，字幕按段落文本输出
+        let srt = to_srt(&result, &ExportOptions::default());
+        assert!(srt.contains("I have 25 apples"), "{srt}");
+        assert!(!srt.contains("twenty"), "{srt}");
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let result = result(vec![segment(0, 1000, "hello")]);
+        let json = to_json(&result).unwrap();
+        assert!(json.contains("\"schema_version\": 1"));
+
+        let parsed = from_json(&json).unwrap();
+        assert_eq!(parsed.text, result.text);
+        assert_eq!(parsed.segments.len(), 1);
+        assert_eq!(parsed.segments[0].end_time, 1000);
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(ExportFormat::from_path("out.SRT"), Some(ExportFormat::Srt));
+        assert_eq!(
+            ExportFormat::from_path("out.vtt"),
+            Some(ExportFormat::WebVtt)
+        );
+        assert_eq!(ExportFormat::from_path("out"), None);
+        assert!("docx".parse::<ExportFormat>().is_err());
+    }
+}
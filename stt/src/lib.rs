@@ -172,20 +172,83 @@ pub use error::{SttError, SttResult};
 
 // 导入音频处理模块
 pub mod audio;
-pub use audio::{AudioConfig, AudioData, AudioFormat};
+pub use audio::{AudioConfig, AudioData, AudioFormat, ResampleQuality};
 
 // 导入Whisper转录模块
 pub mod whisper;
 pub use whisper::{
     transcribe_file, transcribe_file_with_config, transcribe_file_with_language,
-    transcribe_file_with_transcriber, TranscriptionResult, TranscriptionSegment, WhisperConfig,
-    WhisperTranscriber,
+    transcribe_file_with_transcriber, ComputeBackend, DecodingStrategy, DtwPreset,
+    LanguageDetection, LanguageProbability, TranscriptionResult, TranscriptionSegment, VadMode,
+    WhisperConfig, WhisperTranscriber,
 };
 
+// 导入多文件批量转录模块
+pub mod batch;
+pub use batch::{BatchConfig, BatchFailure, BatchItem, BatchSummary};
+
+// 导入识别准确率评估模块
+pub mod eval;
+pub use eval::{
+    char_error_rate, evaluate, load_manifest, word_error_rate, AlignmentStep, EditOp, ErrorCounts,
+    ErrorUnit, EvalConfig, EvalReport, Evaluation, FileScore, ManifestEntry, TextNormalizer,
+};
+
+// 导入性能基准测试模块
+pub mod bench;
+pub use bench::{
+    BenchBaseline, BenchComparison, BenchReport, BenchRun, MetricComparison, Percentiles,
+};
+
+// 导入转录文本后处理模块
+pub mod postprocess;
+pub use postprocess::PostProcessConfig;
+
+// 导入简繁中文输出模块
+pub mod script;
+pub use script::{to_simplified, to_traditional, OutputScript};
+
+// 导入敏感信息遮蔽模块
+pub mod redact;
+pub use redact::{
+    MaskStyle, PiiKind, PiiMatch, RedactedTranscript, RedactionSpan, Redactor, SpanTiming,
+};
+
+// 导入结果导出模块
+pub mod export;
+pub use export::{ExportFormat, ExportOptions};
+
+// 导入长音频分块转录模块
+pub mod long_form;
+pub use long_form::{ChunkStrategy, ChunkTranscription, LongFormConfig};
+
+// 导入模型文件解析模块
+pub mod model;
+pub use model::{read_model_info, ModelInfo, ModelType, QuantizationType};
+
+// 导入本地模型注册表模块
+pub mod registry;
+pub use registry::{Checksum, KnownModel, ModelEntry, ModelRegistry, ModelStatus};
+
+// 导入取消与进度模块
+pub mod progress;
+pub use progress::{CancellationToken, TranscribeOptions, TranscriptionProgress};
+
 // 导入VAD模块
 pub mod vad;
 pub use vad::SimpleVad;
 
+// 导入词级时间戳模块
+pub mod words;
+pub use words::TranscriptionWord;
+
+// 导入解码质量检查模块
+pub mod quality;
+pub use quality::{DecodeAttempt, QualityIssue, SegmentDecision, SegmentVerdict};
+
+// 导入文本工具模块（仅供内部使用）
+mod text;
+
 #[cfg(test)]
 mod integration_tests {
     use super::*;
@@ -195,17 +258,17 @@ mod integration_tests {
         // 定位 fixtures 模型与音频
         let crate_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         let root_dir = crate_dir.parent().expect("stt crate has parent");
-        let model = root_dir.join("fixtures/models/ggml-tiny.bin");
+        let registry = ModelRegistry::new([root_dir.join("fixtures/models")]);
         let audio = root_dir.join("fixtures/audio/jfk.wav");
 
-        if !model.exists() || !audio.exists() {
+        let Some(model) = registry.find("tiny").filter(|_| audio.exists()) else {
             eprintln!(
-                "跳过: 缺少 fixtures 模型或音频 ({} , {})",
-                model.display(),
+                "跳过: 缺少 fixtures 模型或音频 ({:?} , {})",
+                registry.dirs(),
                 audio.display()
             );
             return;
-        }
+        };
 
         let result = transcribe_file(&model, &audio)
             .await
@@ -221,11 +284,8 @@ mod integration_tests {
         let crate_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         let root_dir = crate_dir.parent().expect("stt crate has parent");
         // 测试多个模型
-        let models = [
-            "ggml-tiny.bin",
-            "ggml-small.bin",
-            "ggml-medium.bin"
-        ];
+        let registry = ModelRegistry::new([root_dir.join("fixtures/models")]);
+        let models = ["tiny", "small", "medium"];
         let audio = root_dir.join("fixtures/audio/bank_audio.m4a");
 
         if !audio.exists() {
@@ -234,12 +294,10 @@ mod integration_tests {
         }
 
         for model_name in models {
-            let model = root_dir.join("fixtures/models/").join(model_name);
-            
-            if !model.exists() {
-                println!("跳过: 缺少模型文件: {}", model.display());
+            let Some(model) = registry.find(model_name) else {
+                println!("跳过: 缺少模型: {model_name}");
                 continue;
-            }
+            };
 
             println!("\n开始测试bank_audio.m4a文件的转录，使用模型: {}", model.display());
             
@@ -275,19 +333,14 @@ mod integration_tests {
         }
 
         // 尝试的模型列表
-        let models_to_test = [
-            "ggml-tiny.bin",
-            "ggml-small.bin",
-            "ggml-medium.bin",
-        ];
+        let registry = ModelRegistry::new([root_dir.join("fixtures/models")]);
+        let models_to_test = ["tiny", "small", "medium"];
 
         for model_name in models_to_test {
-            let model = root_dir.join("fixtures/models/").join(model_name);
-            
-            if !model.exists() {
-                println!("跳过: 缺少模型文件: {}", model.display());
+            let Some(model) = registry.find(model_name) else {
+                println!("跳过: 缺少模型: {model_name}");
                 continue;
-            }
+            };
 
             println!("\n测试模型: {}", model_name);
             match transcribe_file(&model, &audio).await {
@@ -312,6 +365,6 @@ mod integration_tests {
 pub mod streaming;
 #[cfg(feature = "streaming")]
 pub use streaming::{
-    create_custom_streaming_transcriber, create_streaming_transcriber, StreamingConfig,
-    StreamingEvent, StreamingTranscriber,
+    create_custom_streaming_transcriber, create_streaming_transcriber, InputFormat, InputSamples,
+    OverflowPolicy, StreamingConfig, StreamingEvent, StreamingHypothesis, StreamingTranscriber,
 };
@@ -0,0 +1,726 @@
+//! 长音频分块转录
+//!
+//! 将长音频按固定窗口（带重叠）或在静音处切分，逐块转录并拼接为一个
+//! [`TranscriptionResult`]。每块完成后通过回调输出该块的段，时间戳均为相对整段音频的绝对时间。
+//! 从文件转录时按块读取 WAV，不会把整段音频载入内存。
+
+use crate::audio::{AudioConfig, AudioData, ResampleQuality};
+use crate::error::{SttError, SttResult};
+use crate::text::is_cjk;
+use crate::vad::SimpleVad;
+use crate::whisper::{
+    convert_for_whisper, ms_to_samples, run_audio_job, samples_to_ms, TranscriptionResult,
+    TranscriptionSegment, WhisperTranscriber, WHISPER_SAMPLE_RATE,
+};
+use crate::words::TranscriptionWord;
+use hound::{SampleFormat, WavReader};
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use std::ops::Range;
+use std::path::Path;
+
+/// 重叠去重时最多比较的单元数（词或中日韩字）
+const MAX_OVERLAP_UNITS: usize = 32;
+
+/// 重叠去重时至少匹配的单元数，避免误删偶然重复的单字
+const MIN_OVERLAP_UNITS: usize = 2;
+
+/// 切块策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChunkStrategy {
+    /// 固定窗口，相邻块之间保留 `overlap_ms` 的重叠
+    FixedWindow,
+    /// 在窗口末尾 `split_search_ms` 范围内寻找能量最低处切分，块之间不重叠
+    VadSilence,
+}
+
+/// 长音频转录配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LongFormConfig {
+    /// 切块策略
+    pub strategy: ChunkStrategy,
+    /// 每块最长时长（毫秒）
+    pub chunk_duration_ms: u64,
+    /// 固定窗口模式下相邻块的重叠时长（毫秒）
+    pub overlap_ms: u64,
+    /// 静音切分模式下在块末尾搜索切分点的范围（毫秒）
+    pub split_search_ms: u64,
+    /// 是否把上一块的文本作为下一块的解码上下文
+    pub carry_context: bool,
+}
+
+impl Default for LongFormConfig {
+    fn default() -> Self {
+        Self {
+            strategy: ChunkStrategy::VadSilence,
+            chunk_duration_ms: 30_000,
+            overlap_ms: 2_000,
+            split_search_ms: 5_000,
+            carry_context: true,
+        }
+    }
+}
+
+impl LongFormConfig {
+    /// 设置切块策略
+    pub fn with_strategy(mut self, strategy: ChunkStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// 设置每块最长时长（毫秒）
+    pub fn with_chunk_duration_ms(mut self, duration_ms: u64) -> Self {
+        self.chunk_duration_ms = duration_ms;
+        self
+    }
+
+    /// 设置固定窗口模式下的重叠时长（毫秒）
+    pub fn with_overlap_ms(mut self, overlap_ms: u64) -> Self {
+        self.overlap_ms = overlap_ms;
+        self
+    }
+
+    /// 设置静音切分的搜索范围（毫秒）
+    pub fn with_split_search_ms(mut self, search_ms: u64) -> Self {
+        self.split_search_ms = search_ms;
+        self
+    }
+
+    /// 设置是否携带上一块文本作为上下文
+    pub fn with_carry_context(mut self, carry_context: bool) -> Self {
+        self.carry_context = carry_context;
+        self
+    }
+
+    /// 验证配置
+    pub fn validate(&self) -> SttResult<()> {
+        if self.chunk_duration_ms < 1_000 {
+            return Err(SttError::ConfigError("分块时长不能小于1秒".to_string()));
+        }
+
+        if self.strategy == ChunkStrategy::FixedWindow
+            && self.overlap_ms * 2 >= self.chunk_duration_ms
+        {
+            return Err(SttError::ConfigError(
+                "重叠时长必须小于分块时长的一半".to_string(),
+            ));
+        }
+
+        if self.strategy == ChunkStrategy::VadSilence
+            && self.split_search_ms * 2 >= self.chunk_duration_ms
+        {
+            return Err(SttError::ConfigError(
+                "切分点搜索范围必须小于分块时长的一半".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// 相邻块的实际重叠时长（毫秒）
+    fn effective_overlap_ms(&self) -> u64 {
+        match self.strategy {
+            ChunkStrategy::FixedWindow => self.overlap_ms,
+            ChunkStrategy::VadSilence => 0,
+        }
+    }
+}
+
+/// 单块转录结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkTranscription {
+    /// 块序号（从 0 开始）
+    pub index: usize,
+    /// 块开始时间（毫秒，绝对时间）
+    pub start_time: u64,
+    /// 块结束时间（毫秒，绝对时间）
+    pub end_time: u64,
+    /// 本块新增的段（已去除与上一块重叠的部分，时间戳为绝对时间）
+    pub segments: Vec<TranscriptionSegment>,
+}
+
+/// 分块读取的样本来源
+enum SampleSource {
+    /// 已在内存中的 16kHz 单声道样本
+    Memory { samples: Vec<f32>, position: usize },
+    /// 按需读取的 16kHz 单声道 WAV 文件
+    Wav {
+        reader: WavReader<BufReader<File>>,
+        format: SampleFormat,
+        scale: f32,
+    },
+}
+
+impl SampleSource {
+    /// 打开 WAV 文件；非 16kHz 单声道时整体读取并转换
+    fn open_wav(path: &Path, quality: ResampleQuality) -> SttResult<Self> {
+        let reader = WavReader::open(path)
+            .map_err(|e| SttError::AudioFileError(format!("打开WAV文件失败: {e}")))?;
+        let spec = reader.spec();
+
+        if spec.sample_rate != WHISPER_SAMPLE_RATE || spec.channels != 1 {
+            let audio = crate::audio::utils::read_wav_file(path)?;
+            let samples = WhisperTranscriber::prepare_audio_samples(&audio, quality)?;
+            return Ok(SampleSource::Memory {
+                samples,
+                position: 0,
+            });
+        }
+
+        let scale = match (spec.sample_format, spec.bits_per_sample) {
+            (SampleFormat::Float, _) => 1.0,
+            (SampleFormat::Int, 16) => i16::MAX as f32,
+            (SampleFormat::Int, 24) => 8388607.0,
+            (SampleFormat::Int, 32) => i32::MAX as f32,
+            (_, bits) => {
+                return Err(SttError::UnsupportedFormat(format!(
+                    "不支持的位深度: {bits}"
+                )));
+            }
+        };
+
+        Ok(SampleSource::Wav {
+            reader,
+            format: spec.sample_format,
+            scale,
+        })
+    }
+
+    /// 向缓冲区补充样本直到长度达到 `target`，返回来源是否已读完
+    fn fill(&mut self, buffer: &mut Vec<f32>, target: usize) -> SttResult<bool> {
+        let wanted = target.saturating_sub(buffer.len());
+        match self {
+            SampleSource::Memory { samples, position } => {
+                let end = (*position + wanted).min(samples.len());
+                buffer.extend_from_slice(&samples[*position..end]);
+                *position = end;
+                Ok(*position >= samples.len())
+            }
+            SampleSource::Wav {
+                reader,
+                format,
+                scale,
+            } => {
+                let before = buffer.len();
+                let read_error =
+                    |e: hound::Error| SttError::AudioFileError(format!("读取样本失败: {e}"));
+                match format {
+                    SampleFormat::Float => {
+                        for sample in reader.samples::<f32>().take(wanted) {
+                            buffer.push(sample.map_err(read_error)?);
+                        }
+                    }
+                    SampleFormat::Int => {
+                        for sample in reader.samples::<i32>().take(wanted) {
+                            buffer.push(sample.map_err(read_error)? as f32 / *scale);
+                        }
+                    }
+                }
+                Ok(buffer.len() - before < wanted)
+            }
+        }
+    }
+
+    /// 在阻塞线程上补充样本，返回来源、缓冲区与来源是否已读完
+    async fn fill_blocking(
+        mut self,
+        mut buffer: Vec<f32>,
+        target: usize,
+    ) -> SttResult<(Self, Vec<f32>, bool)> {
+        run_audio_job(move || {
+            let exhausted = self.fill(&mut buffer, target)?;
+            Ok((self, buffer, exhausted))
+        })
+        .await
+    }
+}
+
+impl WhisperTranscriber {
+    /// 分块转录长音频
+    ///
+    /// 每块完成后调用 `on_chunk`，返回拼接后的完整结果。
+    pub async fn transcribe_long_audio<F>(
+        &self,
+        audio_data: &AudioData,
+        config: &LongFormConfig,
+        on_chunk: F,
+    ) -> SttResult<TranscriptionResult>
+    where
+        F: FnMut(&ChunkTranscription),
+    {
+        // 整段重采样在阻塞线程上执行，不占用异步执行线程
+        let audio_data = audio_data.clone();
+        let quality = self.config().resample_quality;
+        let samples =
+            run_audio_job(move || Self::prepare_audio_samples(&audio_data, quality)).await?;
+        let source = SampleSource::Memory {
+            samples,
+            position: 0,
+        };
+        self.transcribe_chunks(source, config, on_chunk).await
+    }
+
+    /// 分块转录长音频文件
+    ///
+    /// 文件先转换为 Whisper 兼容的 WAV，再按块读取，适合数小时的会议录音。
+    pub async fn transcribe_file_long<P, F>(
+        &self,
+        audio_path: P,
+        config: &LongFormConfig,
+        on_chunk: F,
+    ) -> SttResult<TranscriptionResult>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&ChunkTranscription),
+    {
+        let audio_path = audio_path.as_ref();
+        info!("开始分块转录文件: {}", audio_path.display());
+
+        // FFmpeg 转换与打开文件在阻塞线程上执行
+        let audio_path = audio_path.to_path_buf();
+        let quality = self.config().resample_quality;
+        let source = run_audio_job(move || {
+            let converted = convert_for_whisper(&audio_path)?;
+            SampleSource::open_wav(&converted, quality)
+        })
+        .await?;
+        self.transcribe_chunks(source, config, on_chunk).await
+    }
+
+    /// 逐块读取、转录并拼接
+    async fn transcribe_chunks<F>(
+        &self,
+        mut source: SampleSource,
+        config: &LongFormConfig,
+        mut on_chunk: F,
+    ) -> SttResult<TranscriptionResult>
+    where
+        F: FnMut(&ChunkTranscription),
+    {
+        config.validate()?;
+
+        let start = std::time::Instant::now();
+        let chunk_len = ms_to_samples(config.chunk_duration_ms);
+        let search_len = ms_to_samples(config.split_search_ms);
+        let overlap_ms = config.effective_overlap_ms();
+        let overlap_len = ms_to_samples(overlap_ms);
+        let vad = SimpleVad::new(self.config().vad_threshold);
+
+        let mut result = TranscriptionResult {
+            text: String::new(),
+            language: None,
+            language_detection: None,
+            segments: Vec::new(),
+            processing_time: 0,
+            audio_duration: 0,
+            decisions: Vec::new(),
+            skipped_duration: 0,
+        };
+        let mut buffer: Vec<f32> = Vec::with_capacity(chunk_len + 1);
+        // 第一块识别出语言后使用的转录器
+        let mut fixed_language: Option<WhisperTranscriber> = None;
+        let mut buffer_start = 0;
+        let mut keep_from_ms = 0;
+        let mut previous_text = String::new();
+
+        for index in 0.. {
+            // 多读一个样本，用于判断是否为最后一块；文件读取在阻塞线程上执行
+            let exhausted;
+            (source, buffer, exhausted) = source.fill_blocking(buffer, chunk_len + 1).await?;
+            if buffer.is_empty() {
+                break;
+            }
+            let is_last = exhausted && buffer.len() <= chunk_len;
+
+            let cut = if is_last {
+                buffer.len()
+            } else {
+                match config.strategy {
+                    ChunkStrategy::FixedWindow => chunk_len,
+                    ChunkStrategy::VadSilence => {
+                        vad.quietest_point(&buffer, chunk_len - search_len..chunk_len)
+                    }
+                }
+            };
+
+            let chunk_start_ms = samples_to_ms(buffer_start);
+            let chunk_end_ms = samples_to_ms(buffer_start + cut);
+            // 重叠区域以中点为界：中点之前的段归上一块，之后的归下一块
+            let keep_until_ms = if is_last {
+                u64::MAX
+            } else {
+                chunk_end_ms - overlap_ms / 2
+            };
+
+            debug!(
+                "转录第{}块: {}ms - {}ms",
+                index + 1,
+                chunk_start_ms,
+                chunk_end_ms
+            );
+
+            let audio = AudioData::new(buffer[..cut].to_vec(), AudioConfig::whisper_optimized());
+            let context = (config.carry_context && !previous_text.is_empty())
+                .then_some(previous_text.as_str());
+            let chunk_result = fixed_language
+                .as_ref()
+                .unwrap_or(self)
+                .transcribe_audio_data_with_context(&audio, context)
+                .await?;
+
+            let segments = stitch_chunk(
+                chunk_result.segments,
+                chunk_start_ms,
+                keep_from_ms..keep_until_ms,
+                // 只与上一块的文本比较，避免每块重新切分整段转录
+                &previous_text,
+                overlap_ms,
+            );
+
+            // 重叠区域可能被两块各跳过一次，这里按块累加作为近似值
+            result.skipped_duration += chunk_result.skipped_duration;
+            if result.language.is_none() {
+                // 语言只在第一块识别，之后各块固定使用该语言，避免静音或短块被误判为其他语言
+                if let Some(language) = &chunk_result.language {
+                    if self.config().fixed_language().is_none() {
+                        fixed_language = Some(self.with_language(language));
+                    }
+                }
+                result.language = chunk_result.language;
+                result.language_detection = chunk_result.language_detection;
+            }
+            result
+                .decisions
+                .extend(chunk_result.decisions.into_iter().map(|mut decision| {
+                    decision.start_time += chunk_start_ms;
+                    decision.end_time += chunk_start_ms;
+                    decision
+                }));
+
+            let chunk = ChunkTranscription {
+                index,
+                start_time: chunk_start_ms,
+                end_time: chunk_end_ms,
+                segments,
+            };
+            on_chunk(&chunk);
+
+            previous_text = chunk
+                .segments
+                .iter()
+                .map(|s| s.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            for segment in chunk.segments {
+                if !result.text.is_empty() {
+                    result.text.push(' ');
+                }
+                result.text.push_str(&segment.text);
+                result.segments.push(segment);
+            }
+            result.audio_duration = chunk_end_ms;
+
+            if is_last {
+                break;
+            }
+
+            let advance = cut.saturating_sub(overlap_len).max(1);
+            buffer.drain(..advance);
+            buffer_start += advance;
+            keep_from_ms = keep_until_ms;
+        }
+
+        result.processing_time = start.elapsed().as_millis() as u64;
+        info!(
+            "分块转录完成: {}个段，实时因子: {:.2}x",
+            result.segments.len(),
+            result.real_time_factor()
+        );
+
+        Ok(result)
+    }
+}
+
+/// 将块内段转换为绝对时间，只保留中点落在 `keep` 内的段，并去除与已有文本重复的开头
+///
+/// 只有相邻块的音频存在重叠（`overlap_ms > 0`）时才做文本去重；没有重叠时块边界处的重复是真实语音。
+fn stitch_chunk(
+    segments: Vec<TranscriptionSegment>,
+    chunk_start_ms: u64,
+    keep: Range<u64>,
+    previous_text: &str,
+    overlap_ms: u64,
+) -> Vec<TranscriptionSegment> {
+    let mut kept = Vec::new();
+
+    for mut segment in segments {
+        segment.offset_by(chunk_start_ms);
+
+        let midpoint = (segment.start_time + segment.end_time) / 2;
+        if !keep.contains(&midpoint) {
+            continue;
+        }
+
+        // 只对本块开头的段做文本去重
+        if kept.is_empty() && overlap_ms > 0 {
+            if let Some((text, removed_end)) = strip_overlap(previous_text, &segment.text) {
+                if text.is_empty() {
+                    continue;
+                }
+                let removed = words_before(&segment.text, &segment.words, removed_end);
+                segment.text = text;
+                segment.words.drain(..removed);
+                if let Some(first) = segment.words.first() {
+                    segment.start_time = first.start_time;
+                }
+            }
+        }
+
+        kept.push(segment);
+    }
+
+    kept
+}
+
+/// 去除 `next` 开头与 `previous` 结尾重复的部分
+///
+/// 按词（中日韩文字按字）比较，忽略大小写与标点；返回去重后的文本与 `next` 中被去掉部分的
+/// 结束字节位置，无重复时返回 None。
+pub(crate) fn strip_overlap(previous: &str, next: &str) -> Option<(String, usize)> {
+    let previous_units = text_units(previous);
+    let next_units = text_units(next);
+
+    let tail = &previous_units[previous_units.len().saturating_sub(MAX_OVERLAP_UNITS)..];
+    let max = tail.len().min(next_units.len()).min(MAX_OVERLAP_UNITS);
+
+    (MIN_OVERLAP_UNITS..=max).rev().find_map(|k| {
+        let matches = tail[tail.len() - k..]
+            .iter()
+            .zip(&next_units[..k])
+            .all(|((a, _), (b, _))| a == b);
+        matches.then(|| {
+            let rest = next[next_units[k - 1].1.end..]
+                .trim_start_matches(|c: char| !c.is_alphanumeric() && !is_cjk(c));
+            (rest.to_string(), next_units[k - 1].1.end)
+        })
+    })
+}
+
+/// 统计 `words` 中起点落在 `text` 前 `end` 个字节内的词数
+///
+/// 按词文本在段文本中依次定位，一个词可能包含多个比较单元（如 "Wi-Fi"）；
+/// 定位不到的词及其后的词都保留。
+fn words_before(text: &str, words: &[TranscriptionWord], end: usize) -> usize {
+    let mut cursor = 0;
+    for (index, word) in words.iter().enumerate() {
+        let word_text = word.text.trim();
+        if word_text.is_empty() {
+            continue;
+        }
+        let Some(position) = text[cursor..].find(word_text) else {
+            return index;
+        };
+        let start = cursor + position;
+        if start >= end {
+            return index;
+        }
+        cursor = start + word_text.len();
+    }
+    words.len()
+}
+
+/// 切分为比较单元（小写词或单个中日韩字）及其在原文中的字节范围
+fn text_units(text: &str) -> Vec<(String, Range<usize>)> {
+    let mut units = Vec::new();
+    let mut word_start = None;
+
+    for (index, c) in text.char_indices() {
+        let is_word_char = (c.is_alphanumeric() || c == '\'') && !is_cjk(c);
+        if !is_word_char {
+            if let Some(start) = word_start.take() {
+                units.push((text[start..index].to_lowercase(), start..index));
+            }
+        }
+        if is_cjk(c) {
+            units.push((c.to_string(), index..index + c.len_utf8()));
+        } else if is_word_char && word_start.is_none() {
+            word_start = Some(index);
+        }
+    }
+    if let Some(start) = word_start {
+        units.push((text[start..].to_lowercase(), start..text.len()));
+    }
+
+    units
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start_time: u64, end_time: u64, text: &str) -> TranscriptionSegment {
+        TranscriptionSegment {
+            start_time,
+            end_time,
+            text: text.to_string(),
+            confidence: 0.9,
+            words: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(LongFormConfig::default().validate().is_ok());
+
+        let config = LongFormConfig::default().with_chunk_duration_ms(500);
+        assert!(matches!(config.validate(), Err(SttError::ConfigError(_))));
+
+        let config = LongFormConfig::default()
+            .with_strategy(ChunkStrategy::FixedWindow)
+            .with_chunk_duration_ms(10_000)
+            .with_overlap_ms(5_000);
+        assert!(matches!(config.validate(), Err(SttError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_strip_overlap_words() {
+        let (text, removed) = strip_overlap(
+            "ask not what your country can do",
+            "Your country can do for you.",
+        )
+        .unwrap();
+        assert_eq!(text, "for you.");
+        assert_eq!(removed, "Your country can do".len());
+
+        // 单词偶然重复不算重叠
+        assert_eq!(strip_overlap("we can do it", "it works"), None);
+        assert_eq!(strip_overlap("", "hello world"), None);
+    }
+
+    #[test]
+    fn test_strip_overlap_cjk() {
+        let (text, removed) =
+            strip_overlap("欢迎致电招商银行", "招商银行，请问有什么可以帮您").unwrap();
+        assert_eq!(text, "请问有什么可以帮您");
+        assert_eq!(removed, "招商银行".len());
+
+        // 完全重复时返回空文本
+        let (text, _) = strip_overlap("你好世界", "世界。").unwrap();
+        assert!(text.is_empty());
+    }
+
+    #[test]
+    fn test_stitch_chunk() {
+        let segments = vec![
+            segment(0, 1_500, "can do for you"),
+            segment(1_500, 4_000, "ask what you can do"),
+            segment(28_500, 30_000, "for your country"),
+        ];
+
+        // 块从 28s 开始，上一块保留到 28.5s
+        let stitched = stitch_chunk(
+            segments,
+            28_000,
+            28_500..57_000,
+            "ask not what your country can do",
+            1_000,
+        );
+
+        assert_eq!(stitched.len(), 2);
+        assert_eq!(stitched[0].text, "for you");
+        assert_eq!(stitched[0].start_time, 28_000);
+        assert_eq!(stitched[1].start_time, 29_500);
+        assert_eq!(stitched[1].text, "ask what you can do");
+    }
+
+    #[test]
+    fn test_stitch_chunk_maps_removed_text_to_words() {
+        let word = |start_time, end_time, text: &str| TranscriptionWord {
+            start_time,
+            end_time,
+            text: text.to_string(),
+            probability: 0.9,
+        };
+        let mut seg = segment(0, 2_000, "Wi-Fi password is ready");
+        seg.words = vec![
+            word(0, 500, "Wi-Fi"),
+            word(500, 1_200, " password"),
+            word(1_200, 1_500, " is"),
+            word(1_500, 2_000, " ready"),
+        ];
+
+        // "Wi-Fi" 是两个比较单元，但只对应一个词
+        let stitched = stitch_chunk(
+            vec![seg],
+            28_000,
+            28_000..57_000,
+            "please connect to the Wi-Fi",
+            1_000,
+        );
+
+        assert_eq!(stitched[0].text, "password is ready");
+        assert_eq!(stitched[0].words.len(), 3);
+        assert_eq!(stitched[0].words[0].text, " password");
+        assert_eq!(stitched[0].start_time, 28_500);
+    }
+
+    #[test]
+    fn test_stitch_chunk_without_overlap_keeps_repeats() {
+        // 按静音切分时块之间没有重叠，边界处的重复是真实语音
+        let segments = vec![segment(0, 1_000, "谢谢，再见")];
+        let stitched = stitch_chunk(segments, 30_000, 30_000..u64::MAX, "好的，谢谢", 0);
+        assert_eq!(stitched.len(), 1);
+        assert_eq!(stitched[0].text, "谢谢，再见");
+
+        let segments = vec![segment(0, 1_000, "no no I mean yes")];
+        let stitched = stitch_chunk(segments, 30_000, 30_000..u64::MAX, "oh no no", 0);
+        assert_eq!(stitched[0].text, "no no I mean yes");
+        assert_eq!(stitched[0].start_time, 30_000);
+    }
+
+    #[test]
+    fn test_sample_source_fill() {
+        let mut source = SampleSource::Memory {
+            samples: vec![0.5; 10],
+            position: 0,
+        };
+        let mut buffer = Vec::new();
+
+        assert!(!source.fill(&mut buffer, 6).unwrap());
+        assert_eq!(buffer.len(), 6);
+
+        buffer.drain(..4);
+        assert!(source.fill(&mut buffer, 11).unwrap());
+        assert_eq!(buffer.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_wav_source_fill_blocking() {
+        let path = std::env::temp_dir().join(format!("long_form_fill_{}.wav", std::process::id()));
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: WHISPER_SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..10 {
+            writer.write_sample(i16::MAX / 2).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let source = SampleSource::open_wav(&path, ResampleQuality::default()).unwrap();
+        let (source, buffer, exhausted) = source.fill_blocking(Vec::new(), 6).await.unwrap();
+        assert!(!exhausted);
+        assert_eq!(buffer.len(), 6);
+        assert!((buffer[0] - 0.5).abs() < 1e-3);
+
+        let (_, buffer, exhausted) = source.fill_blocking(buffer, 11).await.unwrap();
+        assert!(exhausted);
+        assert_eq!(buffer.len(), 10);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
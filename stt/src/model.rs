@@ -0,0 +1,513 @@
+//! ggml 模型文件解析
+//!
+//! 读取 whisper.cpp ggml 模型的文件头（超参数、梅尔滤波器、词表）并逐个跳过张量数据，
+//! 得到模型类型、层数、量化类型等元数据。截断或非 ggml 文件会在交给 whisper.cpp 之前
+//! 返回明确的 [`SttError::ModelLoadError`]。整个过程只读取张量头，不会把模型载入内存。
+
+use crate::error::{SttError, SttResult};
+use crate::whisper::ComputeBackend;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// ggml 文件魔数（"ggml"）
+const GGML_MAGIC: u32 = 0x6767_6d6c;
+
+/// 量化版本因子：ftype 中千位以上为量化版本号
+const QNT_VERSION_FACTOR: i32 = 1000;
+
+/// 多语言模型的最小词表大小
+const MULTILINGUAL_VOCAB: i32 = 51865;
+
+/// 模型类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModelType {
+    Tiny,
+    Base,
+    Small,
+    Medium,
+    /// large / large-v1 / large-v2（文件头无法区分）
+    Large,
+    LargeV3,
+    LargeV3Turbo,
+    /// 非标准结构
+    Unknown,
+}
+
+impl ModelType {
+    /// 根据超参数推断模型类型
+    fn detect(n_audio_layer: i32, n_text_layer: i32, n_mels: i32) -> Self {
+        match (n_audio_layer, n_mels) {
+            (4, _) => ModelType::Tiny,
+            (6, _) => ModelType::Base,
+            (12, _) => ModelType::Small,
+            (24, _) => ModelType::Medium,
+            (32, 128) if n_text_layer == 4 => ModelType::LargeV3Turbo,
+            (32, 128) => ModelType::LargeV3,
+            (32, _) => ModelType::Large,
+            _ => ModelType::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for ModelType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ModelType::Tiny => "tiny",
+            ModelType::Base => "base",
+            ModelType::Small => "small",
+            ModelType::Medium => "medium",
+            ModelType::Large => "large",
+            ModelType::LargeV3 => "large-v3",
+            ModelType::LargeV3Turbo => "large-v3-turbo",
+            ModelType::Unknown => "unknown",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 模型权重的量化类型（ggml ftype）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuantizationType {
+    F32,
+    F16,
+    Q4_0,
+    Q4_1,
+    Q5_0,
+    Q5_1,
+    Q8_0,
+    Q2K,
+    Q3K,
+    Q4K,
+    Q5K,
+    Q6K,
+    /// 未识别的 ftype
+    Other(i32),
+}
+
+impl QuantizationType {
+    fn from_ftype(ftype: i32) -> Self {
+        match ftype {
+            0 => QuantizationType::F32,
+            1 => QuantizationType::F16,
+            2 => QuantizationType::Q4_0,
+            3 => QuantizationType::Q4_1,
+            7 => QuantizationType::Q8_0,
+            8 => QuantizationType::Q5_0,
+            9 => QuantizationType::Q5_1,
+            10 => QuantizationType::Q2K,
+            11 => QuantizationType::Q3K,
+            12 => QuantizationType::Q4K,
+            13 => QuantizationType::Q5K,
+            14 => QuantizationType::Q6K,
+            other => QuantizationType::Other(other),
+        }
+    }
+}
+
+/// 模型元数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    /// 模型文件路径
+    pub path: PathBuf,
+    /// 文件大小（字节）
+    pub file_size: u64,
+    /// 模型类型
+    pub model_type: ModelType,
+    /// 是否为多语言模型
+    pub multilingual: bool,
+    /// 词表大小
+    pub n_vocab: i32,
+    /// 编码器层数
+    pub n_audio_layer: i32,
+    /// 编码器隐藏维度
+    pub n_audio_state: i32,
+    /// 编码器注意力头数
+    pub n_audio_head: i32,
+    /// 解码器层数
+    pub n_text_layer: i32,
+    /// 解码器隐藏维度
+    pub n_text_state: i32,
+    /// 解码器注意力头数
+    pub n_text_head: i32,
+    /// 梅尔频带数
+    pub n_mels: i32,
+    /// 量化类型
+    pub quantization: QuantizationType,
+    /// 张量数量
+    pub n_tensors: usize,
+    /// 实际使用的推理后端（仅对已加载的模型有值）
+    #[serde(default)]
+    pub backend: Option<ComputeBackend>,
+}
+
+impl fmt::Display for ModelInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Whisper模型: {} ({}{}，{:?}，{:.1} MB，编码器 {} 层，解码器 {} 层，词表 {})",
+            self.path.display(),
+            self.model_type,
+            if self.multilingual { "" } else { ".en" },
+            self.quantization,
+            self.file_size as f64 / (1024.0 * 1024.0),
+            self.n_audio_layer,
+            self.n_text_layer,
+            self.n_vocab
+        )?;
+        if let Some(backend) = self.backend {
+            write!(f, "，后端: {backend}")?;
+        }
+        Ok(())
+    }
+}
+
+/// 读取并校验 ggml 模型文件
+pub fn read_model_info<P: AsRef<Path>>(path: P) -> SttResult<ModelInfo> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| {
+        SttError::ModelLoadError(format!("无法打开模型文件 {}: {e}", path.display()))
+    })?;
+    let file_size = file
+        .metadata()
+        .map_err(|e| SttError::ModelLoadError(format!("无法读取模型文件信息: {e}")))?
+        .len();
+
+    let mut reader = ModelReader {
+        inner: BufReader::new(file),
+        file_size,
+    };
+    reader.read_info(path)
+}
+
+/// 按 whisper.cpp 的加载顺序读取模型文件
+struct ModelReader<R> {
+    inner: R,
+    file_size: u64,
+}
+
+impl<R: Read + Seek> ModelReader<R> {
+    fn read_info(&mut self, path: &Path) -> SttResult<ModelInfo> {
+        let magic = self.read_u32("文件魔数")?;
+        if magic != GGML_MAGIC {
+            return Err(invalid(format!("不是 ggml 模型文件（魔数 {magic:#010x}）")));
+        }
+
+        let n_vocab = self.read_i32("n_vocab")?;
+        let _n_audio_ctx = self.read_i32("n_audio_ctx")?;
+        let n_audio_state = self.read_i32("n_audio_state")?;
+        let n_audio_head = self.read_i32("n_audio_head")?;
+        let n_audio_layer = self.read_i32("n_audio_layer")?;
+        let _n_text_ctx = self.read_i32("n_text_ctx")?;
+        let n_text_state = self.read_i32("n_text_state")?;
+        let n_text_head = self.read_i32("n_text_head")?;
+        let n_text_layer = self.read_i32("n_text_layer")?;
+        let n_mels = self.read_i32("n_mels")?;
+        let ftype = self.read_i32("ftype")?;
+
+        check_range("n_vocab", n_vocab, 1, 1 << 20)?;
+        check_range("n_audio_state", n_audio_state, 1, 1 << 16)?;
+        check_range("n_audio_head", n_audio_head, 1, 1024)?;
+        check_range("n_audio_layer", n_audio_layer, 1, 1024)?;
+        check_range("n_text_state", n_text_state, 1, 1 << 16)?;
+        check_range("n_text_head", n_text_head, 1, 1024)?;
+        check_range("n_text_layer", n_text_layer, 1, 1024)?;
+        check_range("n_mels", n_mels, 1, 1024)?;
+
+        // 梅尔滤波器
+        let filter_mels = self.read_i32("梅尔滤波器频带数")?;
+        let filter_fft = self.read_i32("梅尔滤波器 FFT 点数")?;
+        check_range("梅尔滤波器频带数", filter_mels, 1, 1024)?;
+        check_range("梅尔滤波器 FFT 点数", filter_fft, 1, 1 << 16)?;
+        self.skip(filter_mels as u64 * filter_fft as u64 * 4, "梅尔滤波器")?;
+
+        // 词表
+        let vocab_len = self.read_i32("词表长度")?;
+        check_range("词表长度", vocab_len, 0, n_vocab)?;
+        for _ in 0..vocab_len {
+            let len = self.read_u32("词条长度")?;
+            if len > 1024 {
+                return Err(invalid(format!("词条长度异常: {len}")));
+            }
+            self.skip(len as u64, "词条")?;
+        }
+
+        // 张量：逐个读取张量头并跳过数据，确认文件完整
+        let expected_tensors = 11 + 15 * n_audio_layer as usize + 24 * n_text_layer as usize;
+        let mut n_tensors = 0;
+        while self.position()? < self.file_size {
+            self.skip_tensor()?;
+            n_tensors += 1;
+        }
+        if n_tensors != expected_tensors {
+            return Err(invalid(format!(
+                "张量数量不符：应为 {expected_tensors}，实际 {n_tensors}（文件可能被截断）"
+            )));
+        }
+
+        Ok(ModelInfo {
+            path: path.to_path_buf(),
+            file_size: self.file_size,
+            model_type: ModelType::detect(n_audio_layer, n_text_layer, n_mels),
+            multilingual: n_vocab >= MULTILINGUAL_VOCAB,
+            n_vocab,
+            n_audio_layer,
+            n_audio_state,
+            n_audio_head,
+            n_text_layer,
+            n_text_state,
+            n_text_head,
+            n_mels,
+            quantization: QuantizationType::from_ftype(ftype % QNT_VERSION_FACTOR),
+            n_tensors,
+            backend: None,
+        })
+    }
+
+    /// 读取一个张量头并跳过其数据
+    fn skip_tensor(&mut self) -> SttResult<()> {
+        let n_dims = self.read_i32("张量维数")?;
+        let name_len = self.read_i32("张量名长度")?;
+        let ttype = self.read_i32("张量类型")?;
+        check_range("张量维数", n_dims, 1, 4)?;
+        check_range("张量名长度", name_len, 1, 1024)?;
+
+        let mut n_elements: u64 = 1;
+        for _ in 0..n_dims {
+            let ne = self.read_i32("张量形状")?;
+            check_range("张量形状", ne, 1, i32::MAX)?;
+            n_elements = n_elements.saturating_mul(ne as u64);
+        }
+
+        let mut name = vec![0u8; name_len as usize];
+        self.read_exact(&mut name, "张量名")?;
+        let name = String::from_utf8_lossy(&name);
+
+        let (block_size, block_bytes) = tensor_block(ttype)
+            .ok_or_else(|| invalid(format!("张量 {name} 的类型未知: {ttype}")))?;
+        if n_elements % block_size != 0 {
+            return Err(invalid(format!(
+                "张量 {name} 的元素数 {n_elements} 不是量化块大小 {block_size} 的整数倍"
+            )));
+        }
+
+        let n_bytes = n_elements / block_size * block_bytes;
+        let end = self.position()?.saturating_add(n_bytes);
+        if end > self.file_size {
+            return Err(invalid(format!(
+                "张量 {name} 数据不完整：需要 {n_bytes} 字节，文件在 {} 字节处结束（文件可能被截断）",
+                self.file_size
+            )));
+        }
+        self.inner
+            .seek(SeekFrom::Start(end))
+            .map_err(|e| invalid(format!("跳过张量 {name} 失败: {e}")))?;
+
+        Ok(())
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8], what: &str) -> SttResult<()> {
+        self.inner
+            .read_exact(buf)
+            .map_err(|_| invalid(format!("读取{what}时文件意外结束（文件可能被截断）")))
+    }
+
+    fn read_u32(&mut self, what: &str) -> SttResult<u32> {
+        let mut buf = [0u8; 4];
+        self.read_exact(&mut buf, what)?;
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn read_i32(&mut self, what: &str) -> SttResult<i32> {
+        let mut buf = [0u8; 4];
+        self.read_exact(&mut buf, what)?;
+        Ok(i32::from_le_bytes(buf))
+    }
+
+    fn skip(&mut self, n_bytes: u64, what: &str) -> SttResult<()> {
+        let end = self.position()?.saturating_add(n_bytes);
+        if end > self.file_size {
+            return Err(invalid(format!(
+                "读取{what}时文件意外结束（文件可能被截断）"
+            )));
+        }
+        self.inner
+            .seek(SeekFrom::Start(end))
+            .map_err(|e| invalid(format!("跳过{what}失败: {e}")))?;
+        Ok(())
+    }
+
+    fn position(&mut self) -> SttResult<u64> {
+        self.inner
+            .stream_position()
+            .map_err(|e| invalid(format!("读取文件位置失败: {e}")))
+    }
+}
+
+/// ggml 张量类型的 (块大小, 每块字节数)
+fn tensor_block(ttype: i32) -> Option<(u64, u64)> {
+    let block = match ttype {
+        0 => (1, 4),      // F32
+        1 => (1, 2),      // F16
+        2 => (32, 18),    // Q4_0
+        3 => (32, 20),    // Q4_1
+        6 => (32, 22),    // Q5_0
+        7 => (32, 24),    // Q5_1
+        8 => (32, 34),    // Q8_0
+        9 => (32, 36),    // Q8_1
+        10 => (256, 84),  // Q2_K
+        11 => (256, 110), // Q3_K
+        12 => (256, 144), // Q4_K
+        13 => (256, 176), // Q5_K
+        14 => (256, 210), // Q6_K
+        15 => (256, 292), // Q8_K
+        _ => return None,
+    };
+    Some(block)
+}
+
+fn check_range(what: &str, value: i32, min: i32, max: i32) -> SttResult<()> {
+    if (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(invalid(format!("{what} 超出合理范围: {value}")))
+    }
+}
+
+fn invalid(message: String) -> SttError {
+    SttError::ModelLoadError(format!("模型文件无效: {message}"))
+}
+
+/// 写入一个结构完整的最小 ggml 模型，供测试使用
+#[cfg(test)]
+pub(crate) fn write_test_model(path: &Path, n_vocab: i32, n_audio_layer: i32, n_text_layer: i32) {
+    let mut bytes = Vec::new();
+    let mut push = |value: i32| bytes.extend_from_slice(&value.to_le_bytes());
+
+    // 超参数
+    for value in [
+        GGML_MAGIC as i32,
+        n_vocab,
+        1500,
+        8,
+        2,
+        n_audio_layer,
+        448,
+        8,
+        2,
+        n_text_layer,
+        80,
+        1,
+    ] {
+        push(value);
+    }
+    // 梅尔滤波器 1x2
+    for value in [1, 2, 0, 0] {
+        push(value);
+    }
+    // 词表：一个词条 "a"
+    push(1);
+    push(1);
+    bytes.push(b'a');
+
+    // 张量：每个张量为 1 维、2 个 F16 元素
+    let n_tensors = 11 + 15 * n_audio_layer + 24 * n_text_layer;
+    for _ in 0..n_tensors {
+        for value in [1i32, 1, 1, 2] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.push(b't');
+        bytes.extend_from_slice(&[0u8; 4]);
+    }
+
+    std::fs::write(path, bytes).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("stt_model_{}_{name}", std::process::id()))
+    }
+
+    #[test]
+    fn test_read_model_info() {
+        let path = temp_path("tiny.bin");
+        write_test_model(&path, 51865, 4, 4);
+
+        let info = read_model_info(&path).unwrap();
+        assert_eq!(info.model_type, ModelType::Tiny);
+        assert!(info.multilingual);
+        assert_eq!(info.n_audio_layer, 4);
+        assert_eq!(info.n_text_layer, 4);
+        assert_eq!(info.n_tensors, 167);
+        assert_eq!(info.quantization, QuantizationType::F16);
+        assert_eq!(info.file_size, std::fs::metadata(&path).unwrap().len());
+        assert!(info.to_string().contains("tiny"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_english_only_model() {
+        let path = temp_path("base_en.bin");
+        write_test_model(&path, 51864, 6, 6);
+
+        let info = read_model_info(&path).unwrap();
+        assert_eq!(info.model_type, ModelType::Base);
+        assert!(!info.multilingual);
+        assert!(info.to_string().contains("base.en"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_reject_non_ggml() {
+        let err = read_model_info(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")).unwrap_err();
+        match err {
+            SttError::ModelLoadError(msg) => assert!(msg.contains("不是 ggml 模型文件")),
+            other => panic!("应返回模型加载错误: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_reject_truncated() {
+        let path = temp_path("truncated.bin");
+        write_test_model(&path, 51865, 1, 1);
+        let bytes = std::fs::read(&path).unwrap();
+
+        // 截断在张量数据中间
+        std::fs::write(&path, &bytes[..bytes.len() - 2]).unwrap();
+        match read_model_info(&path) {
+            Err(SttError::ModelLoadError(msg)) => assert!(msg.contains("截断"), "{msg}"),
+            other => panic!("应返回截断错误: {other:?}"),
+        }
+
+        // 截断在张量边界上：张量数量不符
+        std::fs::write(&path, &bytes[..bytes.len() - 21]).unwrap();
+        match read_model_info(&path) {
+            Err(SttError::ModelLoadError(msg)) => assert!(msg.contains("张量数量不符"), "{msg}"),
+            other => panic!("应返回张量数量错误: {other:?}"),
+        }
+
+        // 截断在文件头
+        std::fs::write(&path, &bytes[..10]).unwrap();
+        assert!(matches!(
+            read_model_info(&path),
+            Err(SttError::ModelLoadError(_))
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_model_type_detection() {
+        assert_eq!(ModelType::detect(32, 32, 80), ModelType::Large);
+        assert_eq!(ModelType::detect(32, 32, 128), ModelType::LargeV3);
+        assert_eq!(ModelType::detect(32, 4, 128), ModelType::LargeV3Turbo);
+        assert_eq!(ModelType::detect(3, 3, 80), ModelType::Unknown);
+    }
+}
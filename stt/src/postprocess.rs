@@ -0,0 +1,1187 @@
+//! 转录文本后处理
+//!
+//! 对 [`TranscriptionResult`] 的每个段落做逆文本规范化（ITN，中英文的数字、金额、日期、时间、百分比）、
+//! 标点与大小写整理以及全角/半角规范化。每条规则都可以单独开关；只改写段落文本，
+//! 段落与词级时间戳保持不变，完整文本由处理后的段落重新拼接。句末标点与句首大写只在
+//! 整个转录的句子边界上处理，不会把每个段落都当成一句话。
+
+use crate::text::{is_cjk, is_cjk_or_fullwidth, is_han};
+use crate::whisper::TranscriptionResult;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// 后处理规则开关
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostProcessConfig {
+    /// 数字：三百五十 → 350，twenty five → 25（英文 10 以下的单个数词保持原样）
+    pub numbers: bool,
+    /// 金额：三块五 → 3.5元，five dollars → $5
+    pub currency: bool,
+    /// 日期：二零二四年三月五日 → 2024年3月5日，march fifth → March 5
+    pub dates: bool,
+    /// 时间：三点十五分 → 3:15，three thirty pm → 3:30 PM
+    pub times: bool,
+    /// 百分比：百分之五十 → 50%，fifty percent → 50%
+    pub percentages: bool,
+    /// 标点：中文补全句末标点、停顿空格改为逗号；英文去掉标点前的空格
+    pub punctuation: bool,
+    /// 大小写：英文句首大写，单独的 i 改为 I
+    pub casing: bool,
+    /// 全角/半角：全角字母数字转半角，纯英文文本中的全角标点转半角
+    pub width: bool,
+}
+
+impl Default for PostProcessConfig {
+    fn default() -> Self {
+        Self {
+            numbers: true,
+            currency: true,
+            dates: true,
+            times: true,
+            percentages: true,
+            punctuation: true,
+            casing: true,
+            width: true,
+        }
+    }
+}
+
+impl PostProcessConfig {
+    /// 所有规则都关闭，可在此基础上逐条开启
+    pub fn disabled() -> Self {
+        Self {
+            numbers: false,
+            currency: false,
+            dates: false,
+            times: false,
+            percentages: false,
+            punctuation: false,
+            casing: false,
+            width: false,
+        }
+    }
+
+    /// 设置数字规则
+    pub fn with_numbers(mut self, enabled: bool) -> Self {
+        self.numbers = enabled;
+        self
+    }
+
+    /// 设置金额规则
+    pub fn with_currency(mut self, enabled: bool) -> Self {
+        self.currency = enabled;
+        self
+    }
+
+    /// 设置日期规则
+    pub fn with_dates(mut self, enabled: bool) -> Self {
+        self.dates = enabled;
+        self
+    }
+
+    /// 设置时间规则
+    pub fn with_times(mut self, enabled: bool) -> Self {
+        self.times = enabled;
+        self
+    }
+
+    /// 设置百分比规则
+    pub fn with_percentages(mut self, enabled: bool) -> Self {
+        self.percentages = enabled;
+        self
+    }
+
+    /// 设置标点规则
+    pub fn with_punctuation(mut self, enabled: bool) -> Self {
+        self.punctuation = enabled;
+        self
+    }
+
+    /// 设置大小写规则
+    pub fn with_casing(mut self, enabled: bool) -> Self {
+        self.casing = enabled;
+        self
+    }
+
+    /// 设置全角/半角规则
+    pub fn with_width(mut self, enabled: bool) -> Self {
+        self.width = enabled;
+        self
+    }
+
+    /// 处理转录结果：逐段改写文本并重新拼接完整文本，时间戳保持不变
+    ///
+    /// 段落常在句子中间切开，因此只在真正的句子边界上补句末标点与句首大写：
+    /// 句末标点只补在最后一段，句首大写只用于第一段及上一段以句末标点结尾的段。
+    pub fn apply(&self, result: &mut TranscriptionResult) {
+        if result.segments.is_empty() {
+            result.text = self.process_text(&result.text);
+            return;
+        }
+        let last = result.segments.len() - 1;
+        let mut sentence_start = true;
+        for (index, segment) in result.segments.iter_mut().enumerate() {
+            segment.text = self.process(&segment.text, sentence_start, index == last);
+            if !segment.text.is_empty() {
+                sentence_start = segment.text.ends_with(is_sentence_end);
+            }
+        }
+        result.text = join_segments(result.segments.iter().map(|s| s.text.as_str()));
+    }
+
+    /// 处理一段完整的文本（视为以句首开始、以句末结束）
+    pub fn process_text(&self, text: &str) -> String {
+        self.process(text, true, true)
+    }
+
+    /// 处理文本；`sentence_start`/`sentence_end` 表示文本开头、结尾是否为句子边界
+    fn process(&self, text: &str, sentence_start: bool, sentence_end: bool) -> String {
+        let mut text = text.trim().to_string();
+        if text.is_empty() {
+            return text;
+        }
+
+        if self.width {
+            text = normalize_width(&text);
+        }
+
+        // 中文
+        if self.percentages {
+            text = zh_percentages(&text);
+        }
+        if self.dates {
+            text = zh_dates(&text);
+        }
+        if self.times {
+            text = zh_times(&text);
+        }
+        if self.currency {
+            text = zh_currency(&text);
+        }
+        if self.numbers {
+            text = zh_numbers(&text);
+        }
+
+        // 英文
+        if self.dates {
+            text = en_dates(&text);
+        }
+        if self.times {
+            text = en_times(&text);
+        }
+        if self.percentages {
+            text = en_percentages(&text);
+        }
+        if self.currency {
+            text = en_currency(&text);
+        }
+        if self.numbers {
+            text = en_numbers(&text);
+        }
+
+        if self.punctuation {
+            text = if text.chars().any(is_han) {
+                punctuate_chinese(&text, sentence_end)
+            } else {
+                clean_english_punctuation(&text)
+            };
+        }
+        if self.casing {
+            text = fix_casing(&text, sentence_start);
+        }
+
+        text
+    }
+}
+
+/// 拼接段落：中文之间不加空格，其余以空格分隔
+pub(crate) fn join_segments<'a>(texts: impl Iterator<Item = &'a str>) -> String {
+    let mut joined = String::new();
+    for text in texts.filter(|t| !t.is_empty()) {
+        let cjk_boundary = joined.chars().last().is_some_and(is_cjk_or_fullwidth)
+            && text.chars().next().is_some_and(is_cjk_or_fullwidth);
+        if !joined.is_empty() && !cjk_boundary {
+            joined.push(' ');
+        }
+        joined.push_str(text);
+    }
+    joined
+}
+
+fn contains_cjk(text: &str) -> bool {
+    text.chars().any(is_cjk)
+}
+
+// ---------------------------------------------------------------------------
+// 全角/半角
+
+fn normalize_width(text: &str) -> String {
+    let cjk = contains_cjk(text);
+    text.chars()
+        .map(|c| match c {
+            '\u{3000}' => ' ',
+            '\u{FF10}'..='\u{FF19}' | '\u{FF21}'..='\u{FF3A}' | '\u{FF41}'..='\u{FF5A}' => {
+                char::from_u32(c as u32 - 0xFEE0).unwrap_or(c)
+            }
+            // 纯英文文本中的全角标点
+            '\u{FF01}'..='\u{FF5E}' if !cjk => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// 中文数字
+
+/// 中文数字字符（含单位）
+const ZH_NUM: &str = "[零〇一二两三四五六七八九十百千万亿]+";
+/// 中文数字或阿拉伯数字，可带小数
+const ZH_NUM_DEC: &str =
+    r"(?:[零〇一二两三四五六七八九十百千万亿]+(?:点[零〇一二三四五六七八九]+)?|\d+(?:\.\d+)?)";
+
+fn zh_digit(c: char) -> Option<u64> {
+    let digit = match c {
+        '零' | '〇' => 0,
+        '一' => 1,
+        '二' | '两' => 2,
+        '三' => 3,
+        '四' => 4,
+        '五' => 5,
+        '六' => 6,
+        '七' => 7,
+        '八' => 8,
+        '九' => 9,
+        _ => return None,
+    };
+    Some(digit)
+}
+
+/// 解析中文整数；纯数字序列（如 二零二四）逐位读出
+fn parse_zh_integer(text: &str) -> Option<String> {
+    if text.is_empty() {
+        return None;
+    }
+    if text.chars().all(|c| zh_digit(c).is_some()) {
+        return Some(
+            text.chars()
+                .filter_map(zh_digit)
+                .map(|d| d.to_string())
+                .collect(),
+        );
+    }
+    parse_zh_units(text).map(|value| value.to_string())
+}
+
+/// 解析带单位的中文数字，支持口语省略（三百五 = 350，一万二 = 12000）
+fn parse_zh_units(text: &str) -> Option<u64> {
+    let mut total: u64 = 0; // 亿以上
+    let mut section: u64 = 0; // 万以上、亿以下
+    let mut current: u64 = 0; // 万以下
+    let mut pending: Option<u64> = None;
+    let mut small_limit: u64 = 10_000;
+    let mut last_unit: u64 = 0;
+    let mut zero_seen = false;
+    let mut seen_wan = false;
+
+    for c in text.chars() {
+        if let Some(digit) = zh_digit(c) {
+            if digit == 0 {
+                zero_seen = true;
+                last_unit = 0;
+                continue;
+            }
+            if pending.is_some() {
+                return None;
+            }
+            pending = Some(digit);
+            continue;
+        }
+
+        let unit = match c {
+            '十' => 10,
+            '百' => 100,
+            '千' => 1_000,
+            '万' => 10_000,
+            '亿' => 100_000_000,
+            _ => return None,
+        };
+        match unit {
+            10 | 100 | 1_000 => {
+                if unit >= small_limit {
+                    return None;
+                }
+                let digit = match pending.take() {
+                    Some(digit) => digit,
+                    None if unit == 10 => 1,
+                    None => return None,
+                };
+                current += digit * unit;
+                small_limit = unit;
+            }
+            10_000 => {
+                let value = current + pending.take().unwrap_or(0);
+                if value == 0 || seen_wan {
+                    return None;
+                }
+                section += value * 10_000;
+                current = 0;
+                small_limit = 10_000;
+                seen_wan = true;
+            }
+            _ => {
+                let value = section + current + pending.take().unwrap_or(0);
+                if value == 0 {
+                    return None;
+                }
+                total = total.checked_add(value.checked_mul(100_000_000)?)?;
+                section = 0;
+                current = 0;
+                small_limit = 10_000;
+                seen_wan = false;
+            }
+        }
+        last_unit = unit;
+        zero_seen = false;
+    }
+
+    if let Some(digit) = pending {
+        // 口语省略：三百五 → 350，一万二 → 12000
+        let scale = if !zero_seen && last_unit >= 100 {
+            last_unit / 10
+        } else {
+            1
+        };
+        current += digit * scale;
+    }
+
+    Some(total + section + current)
+}
+
+/// 解析中文或阿拉伯数字（可带小数）
+fn parse_zh_number(text: &str) -> Option<String> {
+    if text.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return Some(text.to_string());
+    }
+    match text.split_once('点') {
+        Some((integer, fraction)) => {
+            let integer = parse_zh_integer(integer)?;
+            let fraction: String = fraction
+                .chars()
+                .map(|c| zh_digit(c).map(|d| d.to_string()))
+                .collect::<Option<_>>()?;
+            Some(format!("{integer}.{fraction}"))
+        }
+        None => parse_zh_integer(text),
+    }
+}
+
+fn parse_zh_small(text: &str, max: u64) -> Option<u64> {
+    let value: u64 = if text.chars().all(|c| c.is_ascii_digit()) {
+        text.parse().ok()?
+    } else {
+        parse_zh_units(text)?
+    };
+    (value <= max).then_some(value)
+}
+
+fn zh_percentages(text: &str) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(&format!("百分之({ZH_NUM_DEC})")).unwrap());
+    re.replace_all(text, |caps: &Captures| match parse_zh_number(&caps[1]) {
+        Some(number) => format!("{number}%"),
+        None => caps[0].to_string(),
+    })
+    .into_owned()
+}
+
+fn zh_dates(text: &str) -> String {
+    static YEAR: OnceLock<Regex> = OnceLock::new();
+    static MONTH_DAY: OnceLock<Regex> = OnceLock::new();
+    let year = YEAR.get_or_init(|| Regex::new("([零〇一二三四五六七八九]{4})年").unwrap());
+    let month_day = MONTH_DAY.get_or_init(|| {
+        Regex::new("([一二三四五六七八九十]{1,3}|\\d{1,2})月(?:([一二三四五六七八九十]{1,3}|\\d{1,2})(日|号))?")
+            .unwrap()
+    });
+
+    let text = year.replace_all(text, |caps: &Captures| match parse_zh_integer(&caps[1]) {
+        Some(year) => format!("{year}年"),
+        None => caps[0].to_string(),
+    });
+    month_day
+        .replace_all(&text, |caps: &Captures| {
+            let Some(month) = parse_zh_small(&caps[1], 12).filter(|m| *m > 0) else {
+                return caps[0].to_string();
+            };
+            match caps.get(2) {
+                Some(day) => match parse_zh_small(day.as_str(), 31).filter(|d| *d > 0) {
+                    Some(day) => format!("{month}月{day}{}", &caps[3]),
+                    None => format!("{month}月{}{}", day.as_str(), &caps[3]),
+                },
+                None => format!("{month}月"),
+            }
+        })
+        .into_owned()
+}
+
+fn zh_times(text: &str) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(
+            "([零一二两三四五六七八九十]{1,3}|\\d{1,2})点(?:([零一二三四五六七八九十]{1,3}|\\d{1,2})分|(半)|(钟))",
+        )
+        .unwrap()
+    });
+    re.replace_all(text, |caps: &Captures| {
+        let Some(hour) = parse_zh_small(&caps[1], 24) else {
+            return caps[0].to_string();
+        };
+        if let Some(minute) = caps.get(2) {
+            match parse_zh_small(minute.as_str(), 59) {
+                Some(minute) => format!("{hour}:{minute:02}"),
+                None => caps[0].to_string(),
+            }
+        } else if caps.get(3).is_some() {
+            format!("{hour}:30")
+        } else {
+            format!("{hour}点")
+        }
+    })
+    .into_owned()
+}
+
+fn zh_currency(text: &str) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(&format!(
+            "({ZH_NUM_DEC})(块钱|块|元|美元|欧元|英镑|日元)(?:([一二三四五六七八九])(毛|角)?)?"
+        ))
+        .unwrap()
+    });
+    re.replace_all(text, |caps: &Captures| {
+        let Some(amount) = parse_zh_number(&caps[1]) else {
+            return caps[0].to_string();
+        };
+        let unit = &caps[2];
+        let jiao = caps
+            .get(3)
+            .and_then(|c| c.as_str().chars().next())
+            .and_then(zh_digit);
+        // “一块”常作“一起”讲（一块去），不带钱、毛、角时不转换
+        if amount == "1" && unit == "块" && jiao.is_none() {
+            return caps[0].to_string();
+        }
+        let unit = if matches!(unit, "块钱" | "块") {
+            "元"
+        } else {
+            unit
+        };
+        match jiao {
+            Some(jiao) if !amount.contains('.') && matches!(unit, "元") => {
+                format!("{amount}.{jiao}{unit}")
+            }
+            Some(_) => caps[0].to_string(),
+            None => format!("{amount}{unit}"),
+        }
+    })
+    .into_owned()
+}
+
+fn zh_numbers(text: &str) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE
+        .get_or_init(|| Regex::new(&format!("{ZH_NUM}(?:点[零〇一二三四五六七八九]+)?")).unwrap());
+    re.replace_all(text, |caps: &Captures| {
+        let matched = &caps[0];
+        let integer = matched.split('点').next().unwrap_or(matched);
+        let has_unit = integer.chars().any(|c| "十百千万亿".contains(c));
+        let starts_with_unit = integer
+            .chars()
+            .next()
+            .is_some_and(|c| "百千万亿".contains(c));
+        let len = integer.chars().count();
+        // 小数总是转换；单字（一样、十分）、以单位开头（千万）、两三位的数字序列（七八个、三三两两）多为普通词语
+        let decimal = matched.contains('点');
+        let convertible = !starts_with_unit
+            && (decimal
+                || (has_unit && len >= 2)
+                || (!has_unit && len >= 3 && !integer.contains('两')));
+        if !convertible {
+            return matched.to_string();
+        }
+        parse_zh_number(matched).unwrap_or_else(|| matched.to_string())
+    })
+    .into_owned()
+}
+
+// ---------------------------------------------------------------------------
+// 英文数字
+
+const EN_NUM_WORD: &str = "zero|one|two|three|four|five|six|seven|eight|nine|ten|eleven|twelve|thirteen|fourteen|fifteen|sixteen|seventeen|eighteen|nineteen|twenty|thirty|forty|fifty|sixty|seventy|eighty|ninety|hundred|thousand|million|billion";
+const EN_DIGIT_WORD: &str = "zero|oh|one|two|three|four|five|six|seven|eight|nine";
+const EN_MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// 英文数词序列（可带 and、连字符与 point 小数）
+fn en_number_pattern() -> String {
+    format!(
+        r"\b(?:{EN_NUM_WORD})(?:(?:\s+|-)(?:and\s+)?(?:{EN_NUM_WORD}))*(?:\s+point(?:\s+(?:{EN_DIGIT_WORD}))+)?\b"
+    )
+}
+
+fn en_small(word: &str) -> Option<u64> {
+    let value = match word {
+        "zero" | "oh" => 0,
+        "one" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "six" => 6,
+        "seven" => 7,
+        "eight" => 8,
+        "nine" => 9,
+        "ten" => 10,
+        "eleven" => 11,
+        "twelve" => 12,
+        "thirteen" => 13,
+        "fourteen" => 14,
+        "fifteen" => 15,
+        "sixteen" => 16,
+        "seventeen" => 17,
+        "eighteen" => 18,
+        "nineteen" => 19,
+        "twenty" => 20,
+        "thirty" => 30,
+        "forty" => 40,
+        "fifty" => 50,
+        "sixty" => 60,
+        "seventy" => 70,
+        "eighty" => 80,
+        "ninety" => 90,
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn en_words(text: &str) -> Vec<String> {
+    text.split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// 解析英文数词，返回数值文本与词数；不合语法的序列（如 twenty twenty）返回 None
+fn parse_en_number(text: &str) -> Option<(String, usize)> {
+    let words = en_words(text);
+    let (integer_words, fraction_words) = match words.iter().position(|w| w == "point") {
+        Some(index) => (&words[..index], Some(&words[index + 1..])),
+        None => (&words[..], None),
+    };
+
+    #[derive(PartialEq)]
+    enum Last {
+        None,
+        Unit,
+        Teen,
+        Tens,
+        Hundred,
+        Scale,
+    }
+
+    let mut total: u64 = 0;
+    let mut current: u64 = 0;
+    let mut last = Last::None;
+    let mut last_scale = u64::MAX;
+    for word in integer_words {
+        match word.as_str() {
+            "and" if matches!(last, Last::Hundred | Last::Scale) => {}
+            "hundred" => {
+                if !matches!(last, Last::Unit | Last::Teen) || current % 100 == 0 {
+                    return None;
+                }
+                current = current / 100 * 100 + current % 100 * 100;
+                last = Last::Hundred;
+            }
+            "thousand" | "million" | "billion" => {
+                let scale = match word.as_str() {
+                    "thousand" => 1_000,
+                    "million" => 1_000_000,
+                    _ => 1_000_000_000,
+                };
+                if current == 0 || scale >= last_scale {
+                    return None;
+                }
+                total += current * scale;
+                current = 0;
+                last_scale = scale;
+                last = Last::Scale;
+            }
+            word => {
+                let value = en_small(word)?;
+                let kind = match value {
+                    0 if word == "oh" => return None,
+                    0..=9 => Last::Unit,
+                    10..=19 => Last::Teen,
+                    _ => Last::Tens,
+                };
+                let allowed = match kind {
+                    Last::Unit => {
+                        matches!(last, Last::None | Last::Tens | Last::Hundred | Last::Scale)
+                    }
+                    _ => matches!(last, Last::None | Last::Hundred | Last::Scale),
+                };
+                if !allowed || (value == 0 && integer_words.len() > 1) {
+                    return None;
+                }
+                current += value;
+                last = kind;
+            }
+        }
+    }
+    if last == Last::None {
+        return None;
+    }
+
+    let mut number = (total + current).to_string();
+    if let Some(fraction_words) = fraction_words {
+        let digits: String = fraction_words
+            .iter()
+            .map(|w| en_small(w).filter(|d| *d < 10).map(|d| d.to_string()))
+            .collect::<Option<_>>()?;
+        if digits.is_empty() {
+            return None;
+        }
+        number = format!("{number}.{digits}");
+    }
+    Some((number, words.len()))
+}
+
+/// 解析数字：阿拉伯数字原样返回，英文数词转为数字
+fn parse_en_amount(text: &str) -> Option<String> {
+    if text.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return Some(text.to_string());
+    }
+    parse_en_number(text).map(|(number, _)| number)
+}
+
+/// 解析年份：twenty twenty four → 2024，nineteen oh five → 1905，two thousand ten → 2010
+fn parse_en_year(text: &str) -> Option<u64> {
+    if let Ok(year) = text.parse::<u64>() {
+        return Some(year);
+    }
+    if let Some((number, _)) = parse_en_number(text) {
+        let year: u64 = number.parse().ok()?;
+        if (1000..3000).contains(&year) {
+            return Some(year);
+        }
+    }
+
+    let words = en_words(text);
+    for split in 1..words.len() {
+        let left = parse_en_number(&words[..split].join(" "))
+            .and_then(|(n, _)| n.parse::<u64>().ok())
+            .filter(|n| (10..100).contains(n));
+        let right_words = &words[split..];
+        let right = match right_words {
+            [h] if h == "hundred" => Some(0),
+            [oh, digit] if oh == "oh" => en_small(digit).filter(|d| (1..10).contains(d)),
+            _ => parse_en_number(&right_words.join(" "))
+                .and_then(|(n, _)| n.parse::<u64>().ok())
+                .filter(|n| (10..100).contains(n)),
+        };
+        if let (Some(left), Some(right)) = (left, right) {
+            return Some(left * 100 + right);
+        }
+    }
+    None
+}
+
+fn parse_en_ordinal(text: &str) -> Option<u64> {
+    if let Some(digits) = text
+        .strip_suffix("st")
+        .or_else(|| text.strip_suffix("nd"))
+        .or_else(|| text.strip_suffix("rd"))
+        .or_else(|| text.strip_suffix("th"))
+        .or(Some(text))
+        .filter(|d| !d.is_empty() && d.chars().all(|c| c.is_ascii_digit()))
+    {
+        return digits.parse().ok();
+    }
+
+    let words = en_words(text);
+    let (tens, last) = match words.as_slice() {
+        [last] => (0, last.as_str()),
+        [tens, last] => (
+            en_small(tens).filter(|t| matches!(t, 20 | 30))?,
+            last.as_str(),
+        ),
+        _ => return None,
+    };
+    let value = match last {
+        "first" => 1,
+        "second" => 2,
+        "third" => 3,
+        "fourth" => 4,
+        "fifth" => 5,
+        "sixth" => 6,
+        "seventh" => 7,
+        "eighth" => 8,
+        "ninth" => 9,
+        "tenth" => 10,
+        "eleventh" => 11,
+        "twelfth" => 12,
+        "thirteenth" => 13,
+        "fourteenth" => 14,
+        "fifteenth" => 15,
+        "sixteenth" => 16,
+        "seventeenth" => 17,
+        "eighteenth" => 18,
+        "nineteenth" => 19,
+        "twentieth" if tens == 0 => 20,
+        "thirtieth" if tens == 0 => 30,
+        _ => return None,
+    };
+    if tens > 0 && value >= 10 {
+        return None;
+    }
+    Some(tens + value)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+fn en_dates(text: &str) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        let ordinal = "(?:(?:twenty|thirty)[\\s-])?(?:first|second|third|fourth|fifth|sixth|seventh|eighth|ninth)|tenth|eleventh|twelfth|thirteenth|fourteenth|fifteenth|sixteenth|seventeenth|eighteenth|nineteenth|twentieth|thirtieth|\\d{1,2}(?:st|nd|rd|th)?";
+        Regex::new(&format!(
+            r"(?i)\b(?P<month>{})\s+(?P<day>{ordinal})\b(?:,?\s+(?P<year>\d{{4}}|{}))?",
+            EN_MONTHS.join("|"),
+            en_number_pattern()
+        ))
+        .unwrap()
+    });
+
+    re.replace_all(text, |caps: &Captures| {
+        let whole = caps.get(0).unwrap();
+        let day_match = caps.name("day").unwrap();
+        let Some(day) =
+            parse_en_ordinal(&day_match.as_str().to_lowercase()).filter(|d| (1..=31).contains(d))
+        else {
+            return whole.as_str().to_string();
+        };
+        let month = capitalize(&caps["month"]);
+        match caps.name("year").and_then(|y| parse_en_year(y.as_str())) {
+            Some(year) => format!("{month} {day}, {year}"),
+            None => {
+                let rest = &whole.as_str()[day_match.end() - whole.start()..];
+                format!("{month} {day}{rest}")
+            }
+        }
+    })
+    .into_owned()
+}
+
+fn en_times(text: &str) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(&format!(
+            r"(?i)\b(?P<hour>one|two|three|four|five|six|seven|eight|nine|ten|eleven|twelve|\d{{1,2}})(?:\s+(?P<minute>oh\s+(?:one|two|three|four|five|six|seven|eight|nine)|{}|\d{{2}}))?(?:\s+(?P<oclock>o'clock)|\s*(?P<ampm>[ap]\.?m\b\.?))",
+            en_number_pattern()
+        ))
+        .unwrap()
+    });
+
+    re.replace_all(text, |caps: &Captures| {
+        let original = caps[0].to_string();
+        let Some(hour) = parse_en_amount(&caps["hour"].to_lowercase())
+            .and_then(|h| h.parse::<u64>().ok())
+            .filter(|h| (1..=12).contains(h))
+        else {
+            return original;
+        };
+        let minute = match caps.name("minute") {
+            Some(minute) => {
+                let words = en_words(minute.as_str());
+                let value = match words.as_slice() {
+                    [oh, digit] if oh == "oh" => en_small(digit),
+                    _ => parse_en_amount(&minute.as_str().to_lowercase())
+                        .and_then(|m| m.parse::<u64>().ok()),
+                };
+                match value.filter(|m| *m < 60) {
+                    Some(value) => value,
+                    None => return original,
+                }
+            }
+            None => 0,
+        };
+        let suffix = match caps.name("ampm") {
+            Some(ampm) if ampm.as_str().to_lowercase().starts_with('a') => " AM",
+            Some(_) => " PM",
+            None => "",
+        };
+        format!("{hour}:{minute:02}{suffix}")
+    })
+    .into_owned()
+}
+
+fn en_percentages(text: &str) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(&format!(
+            r"(?i)(?P<n>{}|\d+(?:\.\d+)?)\s*(?:percent|per cent)\b",
+            en_number_pattern()
+        ))
+        .unwrap()
+    });
+    re.replace_all(text, |caps: &Captures| {
+        match parse_en_amount(&caps["n"].to_lowercase()) {
+            Some(number) => format!("{number}%"),
+            None => caps[0].to_string(),
+        }
+    })
+    .into_owned()
+}
+
+fn en_currency(text: &str) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        let number = en_number_pattern();
+        Regex::new(&format!(
+            r"(?i)(?P<n>{number}|\d+(?:\.\d+)?)\s+(?P<unit>dollars?|bucks|euros?)\b(?:\s+and\s+(?P<cents>{number}|\d{{1,2}})\s+cents?\b)?"
+        ))
+        .unwrap()
+    });
+    re.replace_all(text, |caps: &Captures| {
+        let Some(amount) = parse_en_amount(&caps["n"].to_lowercase()) else {
+            return caps[0].to_string();
+        };
+        let symbol = if caps["unit"].to_lowercase().starts_with("euro") {
+            "€"
+        } else {
+            "$"
+        };
+        let cents = caps
+            .name("cents")
+            .and_then(|c| parse_en_amount(&c.as_str().to_lowercase()))
+            .and_then(|c| c.parse::<u64>().ok())
+            .filter(|c| *c < 100);
+        match cents {
+            Some(cents) if !amount.contains('.') => format!("{symbol}{amount}.{cents:02}"),
+            _ if caps.name("cents").is_some() => caps[0].to_string(),
+            _ => format!("{symbol}{amount}"),
+        }
+    })
+    .into_owned()
+}
+
+fn en_numbers(text: &str) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(&format!("(?i){}", en_number_pattern())).unwrap());
+    re.replace_all(text, |caps: &Captures| {
+        let matched = &caps[0];
+        match parse_en_number(&matched.to_lowercase()) {
+            // 10 以下的单个数词保持原样（one of them）
+            Some((number, words))
+                if words > 1 || number.parse::<f64>().is_ok_and(|n| n >= 10.0) =>
+            {
+                number
+            }
+            _ => matched.to_string(),
+        }
+    })
+    .into_owned()
+}
+
+// ---------------------------------------------------------------------------
+// 标点与大小写
+
+/// 句末标点
+fn is_sentence_end(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | '。' | '！' | '？')
+}
+
+/// 中文标点：ASCII 标点转全角，停顿空格改为逗号；`sentence_end` 时补全句末标点
+fn punctuate_chinese(text: &str, sentence_end: bool) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let prev_cjk = out.chars().last().is_some_and(is_han);
+        if c.is_whitespace() {
+            let mut j = i;
+            while j < chars.len() && chars[j].is_whitespace() {
+                j += 1;
+            }
+            let next_cjk = chars.get(j).is_some_and(|c| is_han(*c));
+            if prev_cjk && next_cjk {
+                out.push('，');
+            } else if !out.is_empty()
+                && j < chars.len()
+                && !is_cjk_or_fullwidth(chars[j])
+                && !out.chars().last().is_some_and(is_cjk_or_fullwidth)
+            {
+                out.push(' ');
+            }
+            i = j;
+            continue;
+        }
+        let next_digit = chars.get(i + 1).is_some_and(|c| c.is_ascii_digit());
+        let fullwidth = match c {
+            ',' => Some('，'),
+            '?' => Some('？'),
+            '!' => Some('！'),
+            ';' => Some('；'),
+            ':' if !next_digit => Some('：'),
+            '.' if !next_digit => Some('。'),
+            _ => None,
+        };
+        match fullwidth {
+            Some(punct) if prev_cjk => out.push(punct),
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+
+    let last = out.chars().last();
+    if sentence_end && last.is_some_and(|c| is_han(c) || c.is_alphanumeric() || c == '%') {
+        let question = last.is_some_and(|c| matches!(c, '吗' | '么'));
+        out.push(if question { '？' } else { '。' });
+    }
+    out
+}
+
+/// 英文标点：去掉标点前的空格、合并多余空格与重复逗号
+fn clean_english_punctuation(text: &str) -> String {
+    static SPACE_BEFORE: OnceLock<Regex> = OnceLock::new();
+    static SPACES: OnceLock<Regex> = OnceLock::new();
+    static COMMAS: OnceLock<Regex> = OnceLock::new();
+    let space_before = SPACE_BEFORE.get_or_init(|| Regex::new(r"\s+([,.!?;:%])").unwrap());
+    let spaces = SPACES.get_or_init(|| Regex::new(r"\s{2,}").unwrap());
+    let commas = COMMAS.get_or_init(|| Regex::new(r",{2,}").unwrap());
+
+    let text = space_before.replace_all(text, "$1");
+    let text = commas.replace_all(&text, ",");
+    spaces.replace_all(&text, " ").trim().to_string()
+}
+
+/// 英文大小写：句首字母大写（`sentence_start` 表示文本开头是否为句首），单独的 i 改为 I
+fn fix_casing(text: &str, sentence_start: bool) -> String {
+    static PRONOUN: OnceLock<Regex> = OnceLock::new();
+    let pronoun = PRONOUN.get_or_init(|| Regex::new(r"\bi\b").unwrap());
+    let text = pronoun.replace_all(text, "I");
+
+    let mut out = String::with_capacity(text.len());
+    let mut sentence_start = sentence_start;
+    for c in text.chars() {
+        if sentence_start && c.is_alphabetic() {
+            out.extend(c.to_uppercase());
+            sentence_start = false;
+            continue;
+        }
+        if is_sentence_end(c) {
+            sentence_start = true;
+        } else if !c.is_whitespace() && !matches!(c, '"' | '\'' | '“' | '‘' | '(') {
+            sentence_start = false;
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::whisper::TranscriptionSegment;
+
+    fn process(text: &str) -> String {
+        PostProcessConfig::default().process_text(text)
+    }
+
+    #[test]
+    fn test_chinese_numbers() {
+        assert_eq!(parse_zh_units("三百五十"), Some(350));
+        assert_eq!(parse_zh_units("三百五"), Some(350));
+        assert_eq!(parse_zh_units("一万二"), Some(12_000));
+        assert_eq!(parse_zh_units("一千零五"), Some(1_005));
+        assert_eq!(parse_zh_units("十五"), Some(15));
+        assert_eq!(parse_zh_units("两亿三千万"), Some(230_000_000));
+        assert_eq!(parse_zh_units("五六十"), None);
+
+        assert_eq!(process("一共三百五十块"), "一共350元。");
+        assert_eq!(process("三块五"), "3.5元。");
+        assert_eq!(process("我们一块去吧"), "我们一块去吧。");
+        assert_eq!(process("十分感谢"), "十分感谢。");
+        assert_eq!(process("来了一百二十个人"), "来了120个人。");
+        assert_eq!(process("七八个人"), "七八个人。");
+        assert_eq!(process("利率是三点五"), "利率是3.5。");
+    }
+
+    #[test]
+    fn test_chinese_dates_times_percentages() {
+        assert_eq!(process("二零二四年三月五日"), "2024年3月5日。");
+        assert_eq!(process("十二月三十一号"), "12月31号。");
+        assert_eq!(process("下午三点十五分开会"), "下午3:15开会。");
+        assert_eq!(process("八点半出发"), "8:30出发。");
+        assert_eq!(process("增长了百分之五十"), "增长了50%。");
+        assert_eq!(process("三个月"), "三个月。");
+    }
+
+    #[test]
+    fn test_chinese_punctuation_and_width() {
+        assert_eq!(process("你好 我想查一下余额"), "你好，我想查一下余额。");
+        assert_eq!(process("可以吗"), "可以吗？");
+        assert_eq!(process("你好,世界"), "你好，世界。");
+        assert_eq!(process("ＡＢＣ１２３元"), "ABC123元。");
+        assert_eq!(process("已经结束了。"), "已经结束了。");
+        // 中文标点规则只作用于含汉字的文本，韩文的词间空格保持不变
+        assert_eq!(process("안녕하세요 반갑습니다"), "안녕하세요 반갑습니다");
+    }
+
+    #[test]
+    fn test_join_segments() {
+        let join = |texts: &[&str]| join_segments(texts.iter().copied());
+        assert_eq!(join(&["你好。", "再见"]), "你好。再见");
+        assert_eq!(join(&["こんにちは", "さようなら"]), "こんにちはさようなら");
+        assert_eq!(join(&["안녕하세요", "반갑습니다"]), "안녕하세요반갑습니다");
+        assert_eq!(join(&["Hello.", "", "你好"]), "Hello. 你好");
+    }
+
+    #[test]
+    fn test_english_itn() {
+        assert_eq!(process("i have twenty five apples"), "I have 25 apples");
+        assert_eq!(process("one of them"), "One of them");
+        assert_eq!(
+            process("it costs five dollars and fifty cents"),
+            "It costs $5.50"
+        );
+        assert_eq!(process("fifty percent of users"), "50% of users");
+        assert_eq!(process("meet at three thirty pm"), "Meet at 3:30 PM");
+        assert_eq!(process("see you at seven o'clock"), "See you at 7:00");
+        assert_eq!(
+            process("born on march fifth, nineteen ninety nine"),
+            "Born on March 5, 1999"
+        );
+        assert_eq!(
+            process("on july fourth twenty twenty four"),
+            "On July 4, 2024"
+        );
+        assert_eq!(process("two point five million"), "2.5 million");
+        assert_eq!(process("three point five"), "3.5");
+        assert_eq!(process("one thousand two hundred and five"), "1205");
+        assert_eq!(
+            process("hello , world .  how are you"),
+            "Hello, world. How are you"
+        );
+        assert_eq!(process("Ｈｅｌｌｏ，ｗｏｒｌｄ"), "Hello,world");
+    }
+
+    #[test]
+    fn test_rules_toggle() {
+        let config = PostProcessConfig::disabled().with_percentages(true);
+        assert_eq!(config.process_text("百分之五十 一百"), "50% 一百");
+        assert_eq!(
+            PostProcessConfig::disabled().process_text("i have twenty apples"),
+            "i have twenty apples"
+        );
+        let config = PostProcessConfig::default()
+            .with_numbers(false)
+            .with_casing(false);
+        assert_eq!(config.process_text("twenty apples"), "twenty apples");
+    }
+
+    #[test]
+    fn test_apply_preserves_timings() {
+        let segment = |start_time, end_time, text: &str| TranscriptionSegment {
+            start_time,
+            end_time,
+            text: text.to_string(),
+            confidence: 0.9,
+            words: Vec::new(),
+        };
+        let mut result = TranscriptionResult {
+            text: String::new(),
+            language: Some("zh".to_string()),
+            language_detection: None,
+            segments: vec![
+                segment(0, 1500, "一共三百五十块"),
+                segment(1500, 3000, "对吗"),
+            ],
+            processing_time: 0,
+            audio_duration: 3000,
+            decisions: Vec::new(),
+            skipped_duration: 0,
+        };
+
+        PostProcessConfig::default().apply(&mut result);
+        assert_eq!(result.segments[0].text, "一共350元");
+        assert_eq!(result.segments[1].text, "对吗？");
+        assert_eq!(
+            (result.segments[1].start_time, result.segments[1].end_time),
+            (1500, 3000)
+        );
+        assert_eq!(result.text, "一共350元对吗？");
+    }
+
+    #[test]
+    fn test_apply_sentence_boundaries_across_segments() {
+        let segment = |start_time, end_time, text: &str| TranscriptionSegment {
+            start_time,
+            end_time,
+            text: text.to_string(),
+            confidence: 0.9,
+            words: Vec::new(),
+        };
+        let result = |segments| TranscriptionResult {
+            text: String::new(),
+            language: None,
+            language_detection: None,
+            segments,
+            processing_time: 0,
+            audio_duration: 3000,
+            decisions: Vec::new(),
+            skipped_duration: 0,
+        };
+
+        // 句子在段落中间切开：不在段尾补句号
+        let mut zh = result(vec![
+            segment(0, 1500, "我想查一下"),
+            segment(1500, 3000, "余额"),
+        ]);
+        PostProcessConfig::default().apply(&mut zh);
+        assert_eq!(zh.text, "我想查一下余额。");
+
+        // 上一段未以句末标点结尾时，下一段开头不大写
+        let mut en = result(vec![
+            segment(0, 1500, "and so my fellow americans,"),
+            segment(1500, 2500, "ask not what your country can do."),
+            segment(2500, 3000, "ask what you can do"),
+        ]);
+        PostProcessConfig::default().apply(&mut en);
+        assert_eq!(en.segments[0].text, "And so my fellow americans,");
+        assert_eq!(en.segments[1].text, "ask not what your country can do.");
+        assert_eq!(en.segments[2].text, "Ask what you can do");
+    }
+}
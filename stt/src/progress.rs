@@ -0,0 +1,246 @@
+//! 转录任务的取消与进度
+//!
+//! [`CancellationToken`] 用于中止正在运行的转录，被取消的任务返回 [`SttError::Cancelled`]；
+//! 进度回调包装了 whisper 的进度回调，报告完成百分比、已用时间与预计剩余时间。
+
+use crate::error::{SttError, SttResult};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+/// 取消令牌
+///
+/// 克隆共享同一个取消状态，可在 UI 线程中取消另一个任务中正在运行的转录。
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// 创建新的取消令牌
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 请求取消
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// 是否已请求取消
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 已请求取消时返回 [`SttError::Cancelled`]
+    pub fn check(&self) -> SttResult<()> {
+        if self.is_cancelled() {
+            Err(SttError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// 转录进度
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptionProgress {
+    /// 完成百分比（0.0-100.0）
+    pub percent: f32,
+    /// 已用时间（毫秒）
+    pub elapsed_ms: u64,
+    /// 预计剩余时间（毫秒，尚无进度时为 None）
+    pub remaining_ms: Option<u64>,
+}
+
+impl TranscriptionProgress {
+    /// 根据完成百分比与已用时间估算剩余时间
+    pub fn estimate(percent: f32, elapsed_ms: u64) -> Self {
+        let percent = percent.clamp(0.0, 100.0);
+        let remaining_ms = (percent > 0.0)
+            .then(|| (elapsed_ms as f64 * (100.0 - percent as f64) / percent as f64) as u64);
+        Self {
+            percent,
+            elapsed_ms,
+            remaining_ms,
+        }
+    }
+}
+
+/// 进度回调
+pub type ProgressCallback = Arc<dyn Fn(TranscriptionProgress) + Send + Sync>;
+
+/// 转录任务选项：取消令牌与进度回调
+#[derive(Clone, Default)]
+pub struct TranscribeOptions {
+    /// 取消令牌
+    pub cancellation: Option<CancellationToken>,
+    /// 进度回调
+    pub on_progress: Option<ProgressCallback>,
+}
+
+impl fmt::Debug for TranscribeOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TranscribeOptions")
+            .field("cancellation", &self.cancellation)
+            .field("on_progress", &self.on_progress.is_some())
+            .finish()
+    }
+}
+
+impl TranscribeOptions {
+    /// 创建空选项（不可取消、不报告进度）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置取消令牌
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// 设置进度回调（在推理线程上调用，应尽快返回）
+    pub fn with_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(TranscriptionProgress) + Send + Sync + 'static,
+    {
+        self.on_progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// 将进度发送到通道，适合在异步任务中消费进度
+    pub fn with_progress_sender(
+        self,
+        sender: mpsc::UnboundedSender<TranscriptionProgress>,
+    ) -> Self {
+        self.with_progress(move |progress| {
+            let _ = sender.send(progress);
+        })
+    }
+
+    /// 已请求取消时返回 [`SttError::Cancelled`]
+    pub(crate) fn check_cancelled(&self) -> SttResult<()> {
+        self.cancellation
+            .as_ref()
+            .map_or(Ok(()), CancellationToken::check)
+    }
+}
+
+/// 把多次解码的进度合并为整体进度
+///
+/// 每次解码覆盖总样本数中的一段，whisper 报告的段内百分比按样本数折算为整体百分比；
+/// 整体进度只增不减。
+#[derive(Clone)]
+pub(crate) struct ProgressTracker {
+    callback: Option<ProgressCallback>,
+    start: Instant,
+    total_samples: usize,
+    last_percent: Arc<Mutex<f32>>,
+}
+
+impl ProgressTracker {
+    pub(crate) fn new(callback: Option<ProgressCallback>, total_samples: usize) -> Self {
+        Self {
+            callback,
+            start: Instant::now(),
+            total_samples: total_samples.max(1),
+            last_percent: Arc::new(Mutex::new(0.0)),
+        }
+    }
+
+    /// 是否设置了回调
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.callback.is_some()
+    }
+
+    /// 报告进度：已完成 `done_samples`，当前解码的 `region_samples` 完成了 `region_percent`%
+    pub(crate) fn report(&self, done_samples: usize, region_samples: usize, region_percent: f32) {
+        let Some(callback) = &self.callback else {
+            return;
+        };
+
+        let done =
+            done_samples as f32 + region_samples as f32 * region_percent.clamp(0.0, 100.0) / 100.0;
+        let percent = (done / self.total_samples as f32 * 100.0).min(100.0);
+        {
+            let Ok(mut last) = self.last_percent.lock() else {
+                return;
+            };
+            if percent < *last {
+                return;
+            }
+            *last = percent;
+        }
+
+        let elapsed_ms = self.start.elapsed().as_millis() as u64;
+        callback(TranscriptionProgress::estimate(percent, elapsed_ms));
+    }
+
+    /// 报告完成
+    pub(crate) fn finish(&self) {
+        self.report(self.total_samples, 0, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancellation_token() {
+        let token = CancellationToken::new();
+        let shared = token.clone();
+        assert!(token.check().is_ok());
+
+        shared.cancel();
+        assert!(token.is_cancelled());
+        assert!(matches!(token.check(), Err(SttError::Cancelled)));
+
+        let options = TranscribeOptions::new().with_cancellation(token);
+        assert!(matches!(
+            options.check_cancelled(),
+            Err(SttError::Cancelled)
+        ));
+        assert!(TranscribeOptions::new().check_cancelled().is_ok());
+    }
+
+    #[test]
+    fn test_progress_estimate() {
+        let progress = TranscriptionProgress::estimate(25.0, 1000);
+        assert_eq!(progress.remaining_ms, Some(3000));
+
+        assert_eq!(
+            TranscriptionProgress::estimate(0.0, 1000).remaining_ms,
+            None
+        );
+        assert_eq!(
+            TranscriptionProgress::estimate(100.0, 1000).remaining_ms,
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_tracker_combines_regions() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let options = TranscribeOptions::new().with_progress_sender(tx);
+        let tracker = ProgressTracker::new(options.on_progress, 400);
+
+        // 第一段 100 个样本，第二段 300 个样本
+        tracker.report(0, 100, 50.0);
+        tracker.report(0, 100, 100.0);
+        tracker.report(100, 300, 50.0);
+        // 进度回退时不报告
+        tracker.report(100, 300, 10.0);
+        tracker.finish();
+
+        let mut percents = Vec::new();
+        while let Ok(progress) = rx.try_recv() {
+            percents.push(progress.percent);
+        }
+        assert_eq!(percents, vec![12.5, 25.0, 62.5, 100.0]);
+    }
+}
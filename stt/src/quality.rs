@@ -0,0 +1,329 @@
+//! 解码质量检查
+//!
+//! 为温度回退提供压缩比、平均对数概率和重复循环检测，
+//! 并定义记录在转录结果上的逐段决策，便于审计某个段为何被丢弃或重试。
+
+use crate::text::is_cjk;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// 计算文本的 zlib 压缩比（原始字节数 / 压缩后字节数）
+///
+/// 重复循环的文本压缩比会明显偏高，空文本返回 0.0。
+pub fn compression_ratio(text: &str) -> f32 {
+    if text.is_empty() {
+        return 0.0;
+    }
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    if encoder.write_all(text.as_bytes()).is_err() {
+        return 0.0;
+    }
+    match encoder.finish() {
+        Ok(compressed) if !compressed.is_empty() => text.len() as f32 / compressed.len() as f32,
+        _ => 0.0,
+    }
+}
+
+/// 检测文本中的重复循环，返回重复的片段
+///
+/// 中日韩文字按字切分，其他文字按词切分（忽略大小写与标点）。
+/// 单个单元需连续出现 5 次，2-3 个单元的片段需连续出现 4 次，
+/// 更长的片段连续出现 3 次即视为重复循环。
+pub fn detect_repetition(text: &str) -> Option<String> {
+    let units = split_units(text);
+
+    for period in 1..=units.len() / 3 {
+        let min_repeats = match period {
+            1 => 5,
+            2 | 3 => 4,
+            _ => 3,
+        };
+        if period * min_repeats > units.len() {
+            continue;
+        }
+
+        for start in 0..=units.len() - period * min_repeats {
+            let pattern = &units[start..start + period];
+            let repeated = (1..min_repeats).all(|k| {
+                let offset = start + k * period;
+                units[offset..offset + period] == *pattern
+            });
+            if repeated {
+                return Some(join_units(pattern));
+            }
+        }
+    }
+
+    None
+}
+
+/// 将文本切分为重复检测单元
+fn split_units(text: &str) -> Vec<String> {
+    let mut units = Vec::new();
+    let mut word = String::new();
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            if !word.is_empty() {
+                units.push(std::mem::take(&mut word));
+            }
+            units.push(c.to_string());
+        } else if c.is_alphanumeric() || c == '\'' {
+            word.extend(c.to_lowercase());
+        } else if !word.is_empty() {
+            units.push(std::mem::take(&mut word));
+        }
+    }
+    if !word.is_empty() {
+        units.push(word);
+    }
+
+    units
+}
+
+/// 拼接检测单元：相邻的中日韩字之间不加空格
+fn join_units(units: &[String]) -> String {
+    let mut joined = String::new();
+    for unit in units {
+        let prev_cjk = joined.chars().last().is_some_and(is_cjk);
+        let cur_cjk = unit.chars().next().is_some_and(is_cjk);
+        if !(joined.is_empty() || prev_cjk && cur_cjk) {
+            joined.push(' ');
+        }
+        joined.push_str(unit);
+    }
+    joined
+}
+
+/// 质量问题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QualityIssue {
+    /// 压缩比超过阈值（通常意味着重复循环）
+    HighCompressionRatio,
+    /// 平均对数概率低于阈值
+    LowLogProb,
+    /// 检测到重复片段
+    Repetition,
+}
+
+/// 一次解码尝试
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodeAttempt {
+    /// 解码温度
+    pub temperature: f32,
+    /// 解码文本
+    pub text: String,
+    /// 文本 token 的平均对数概率
+    pub avg_logprob: f32,
+    /// 文本压缩比
+    pub compression_ratio: f32,
+    /// 无语音概率
+    pub no_speech_prob: f32,
+    /// 未通过的检查项
+    pub issues: Vec<QualityIssue>,
+}
+
+impl DecodeAttempt {
+    /// 是否通过全部质量检查
+    pub fn passed(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// 段处理结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SegmentVerdict {
+    /// 首次解码即通过检查
+    Accepted,
+    /// 首次解码未通过，提高温度重试后通过
+    Fallback,
+    /// 所有温度均未通过，保留平均对数概率最高的一次
+    Unresolved,
+    /// 无语音概率过高，段被丢弃
+    DroppedNoSpeech,
+}
+
+/// 段决策记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentDecision {
+    /// 开始时间（毫秒）
+    pub start_time: u64,
+    /// 结束时间（毫秒）
+    pub end_time: u64,
+    /// 依次进行的解码尝试
+    pub attempts: Vec<DecodeAttempt>,
+    /// 处理结论
+    pub verdict: SegmentVerdict,
+}
+
+impl SegmentDecision {
+    /// 最终采用的解码尝试（段被丢弃时为 None）
+    pub fn chosen(&self) -> Option<&DecodeAttempt> {
+        match self.verdict {
+            SegmentVerdict::DroppedNoSpeech => None,
+            SegmentVerdict::Accepted | SegmentVerdict::Fallback => self.attempts.last(),
+            SegmentVerdict::Unresolved => self
+                .attempts
+                .iter()
+                .max_by(|a, b| a.avg_logprob.total_cmp(&b.avg_logprob)),
+        }
+    }
+}
+
+/// 质量检查阈值
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityThresholds {
+    /// 压缩比上限（None 表示不检查）
+    pub compression_ratio: Option<f32>,
+    /// 平均对数概率下限（None 表示不检查）
+    pub logprob: Option<f32>,
+    /// 无语音概率阈值（None 表示不丢弃）
+    pub no_speech: Option<f32>,
+    /// 是否检测重复循环
+    pub detect_repetition: bool,
+}
+
+impl QualityThresholds {
+    /// 检查一次解码结果，返回未通过的检查项
+    pub fn check(&self, text: &str, avg_logprob: f32, compression_ratio: f32) -> Vec<QualityIssue> {
+        let mut issues = Vec::new();
+
+        if self
+            .compression_ratio
+            .is_some_and(|max| compression_ratio > max)
+        {
+            issues.push(QualityIssue::HighCompressionRatio);
+        }
+        if self.logprob.is_some_and(|min| avg_logprob < min) {
+            issues.push(QualityIssue::LowLogProb);
+        }
+        if self.detect_repetition && detect_repetition(text).is_some() {
+            issues.push(QualityIssue::Repetition);
+        }
+
+        issues
+    }
+
+    /// 是否判定为无语音段
+    ///
+    /// 与 Whisper 原版一致：无语音概率超过阈值且平均对数概率也低于阈值时才丢弃；
+    /// 未设置对数概率阈值时只看无语音概率。
+    pub fn is_no_speech(&self, no_speech_prob: f32, avg_logprob: f32) -> bool {
+        let Some(threshold) = self.no_speech else {
+            return false;
+        };
+        no_speech_prob > threshold && self.logprob.map_or(true, |min| avg_logprob < min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thresholds() -> QualityThresholds {
+        QualityThresholds {
+            compression_ratio: Some(2.4),
+            logprob: Some(-1.0),
+            no_speech: Some(0.6),
+            detect_repetition: true,
+        }
+    }
+
+    #[test]
+    fn test_compression_ratio() {
+        assert_eq!(compression_ratio(""), 0.0);
+
+        let normal = "And so my fellow Americans, ask not what your country can do for you.";
+        assert!(compression_ratio(normal) < 2.4);
+
+        let looped = "Thank you for watching. ".repeat(20);
+        assert!(compression_ratio(&looped) > 2.4);
+    }
+
+    #[test]
+    fn test_detect_repetition() {
+        assert_eq!(
+            detect_repetition("ask not what your country can do for you"),
+            None
+        );
+        assert_eq!(
+            detect_repetition(
+                "Thank you for watching. Thank you for watching! thank you for watching"
+            ),
+            Some("thank you for watching".to_string())
+        );
+        assert_eq!(
+            detect_repetition("the the the the the end"),
+            Some("the".to_string())
+        );
+        // 四次单字重复不算循环
+        assert_eq!(detect_repetition("no no no no"), None);
+
+        assert_eq!(detect_repetition("今天天气很好"), None);
+        assert_eq!(
+            detect_repetition("谢谢观看，谢谢观看，谢谢观看"),
+            Some("谢谢观看".to_string())
+        );
+        assert_eq!(detect_repetition("啊啊啊啊啊"), Some("啊".to_string()));
+    }
+
+    #[test]
+    fn test_thresholds_check() {
+        let thresholds = thresholds();
+        assert!(thresholds.check("hello world", -0.3, 1.2).is_empty());
+        assert_eq!(
+            thresholds.check("hello world", -1.5, 2.6),
+            vec![QualityIssue::HighCompressionRatio, QualityIssue::LowLogProb]
+        );
+
+        let disabled = QualityThresholds {
+            compression_ratio: None,
+            logprob: None,
+            no_speech: None,
+            detect_repetition: false,
+        };
+        assert!(disabled.check("a a a a a a", -5.0, 10.0).is_empty());
+        assert!(!disabled.is_no_speech(0.99, -5.0));
+    }
+
+    #[test]
+    fn test_is_no_speech() {
+        let thresholds = thresholds();
+        assert!(thresholds.is_no_speech(0.8, -1.5));
+        // 对数概率足够高时保留该段
+        assert!(!thresholds.is_no_speech(0.8, -0.2));
+        assert!(!thresholds.is_no_speech(0.3, -1.5));
+
+        let no_logprob = QualityThresholds {
+            logprob: None,
+            ..thresholds
+        };
+        assert!(no_logprob.is_no_speech(0.8, -0.2));
+    }
+
+    #[test]
+    fn test_decision_chosen() {
+        let attempt = |temperature: f32, avg_logprob: f32| DecodeAttempt {
+            temperature,
+            text: format!("t{temperature}"),
+            avg_logprob,
+            compression_ratio: 1.0,
+            no_speech_prob: 0.1,
+            issues: vec![QualityIssue::LowLogProb],
+        };
+
+        let mut decision = SegmentDecision {
+            start_time: 0,
+            end_time: 1000,
+            attempts: vec![attempt(0.0, -1.2), attempt(0.2, -1.1), attempt(0.4, -1.6)],
+            verdict: SegmentVerdict::Unresolved,
+        };
+        assert_eq!(decision.chosen().unwrap().temperature, 0.2);
+
+        decision.verdict = SegmentVerdict::DroppedNoSpeech;
+        assert!(decision.chosen().is_none());
+    }
+}
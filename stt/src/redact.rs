@@ -0,0 +1,792 @@
+//! 转录文本敏感信息遮蔽
+//!
+//! [`Redactor`] 在转录结果中检测银行卡号、电话号码、身份证号与电子邮箱（支持阿拉伯数字、中文数字、
+//! 英文数词以及“艾特/at”“点/dot”等口语形式），也可以加入自定义正则规则。检测在拼接后的完整文本上进行，
+//! 因此跨段落的号码也能识别。结果中的每个 [`RedactionSpan`] 带有词级时间戳（段内有词级结果时）
+//! 或段落时间戳，可直接交给 [`RedactedTranscript::write_redacted_wav`] 对原始音频做静音或提示音处理。
+
+use crate::error::{SttError, SttResult};
+use crate::postprocess::join_segments;
+use crate::whisper::{TranscriptionResult, TranscriptionSegment};
+use audio_utils as audio_lib;
+use audio_utils::{RedactionMode, TimeRange};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// 单个数字：阿拉伯数字（含全角）、中文数字或英文数词
+const DIGIT: &str = r"(?:[0-9０-９零〇一二三四五六七八九幺]|\b(?:zero|oh|one|two|three|four|five|six|seven|eight|nine)\b)";
+
+/// 敏感信息类型
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PiiKind {
+    /// 银行卡号（13-19 位）
+    CardNumber,
+    /// 电话号码（手机、座机、带国际区号的号码）
+    PhoneNumber,
+    /// 居民身份证号（18 位，末位可为 X）
+    IdNumber,
+    /// 电子邮箱
+    Email,
+    /// 自定义规则
+    Custom(String),
+}
+
+impl PiiKind {
+    /// 遮蔽标签
+    pub fn label(&self) -> &str {
+        match self {
+            PiiKind::CardNumber => "CARD_NUMBER",
+            PiiKind::PhoneNumber => "PHONE_NUMBER",
+            PiiKind::IdNumber => "ID_NUMBER",
+            PiiKind::Email => "EMAIL",
+            PiiKind::Custom(name) => name,
+        }
+    }
+
+    /// 内置检测的全部类型
+    pub fn builtin() -> [PiiKind; 4] {
+        [
+            PiiKind::CardNumber,
+            PiiKind::PhoneNumber,
+            PiiKind::IdNumber,
+            PiiKind::Email,
+        ]
+    }
+}
+
+/// 遮蔽后的文本样式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MaskStyle {
+    /// 替换为类型标签，如 `[PHONE_NUMBER]`
+    #[default]
+    Label,
+    /// 把字母、数字逐个替换为指定字符，保留分隔符
+    Char(char),
+}
+
+/// 时间戳精度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpanTiming {
+    /// 来自词级时间戳
+    Word,
+    /// 来自所在段落的时间范围（段内没有词级时间戳）
+    Segment,
+}
+
+/// 一处被遮蔽的敏感信息
+///
+/// 不保存原始文本，序列化遮蔽结果不会泄露敏感信息；需要原文时请使用 [`Redactor::find`]。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedactionSpan {
+    /// 类型
+    pub kind: PiiKind,
+    /// 替换后的文本
+    pub replacement: String,
+    /// 涉及的段落序号
+    pub segment_indices: Vec<usize>,
+    /// 开始时间（毫秒）
+    pub start_time: u64,
+    /// 结束时间（毫秒）
+    pub end_time: u64,
+    /// 时间戳精度
+    pub timing: SpanTiming,
+}
+
+impl RedactionSpan {
+    /// 对应的音频时间区间
+    pub fn time_range(&self) -> TimeRange {
+        TimeRange::new(self.start_time, self.end_time)
+    }
+}
+
+/// 遮蔽后的转录结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactedTranscript {
+    /// 遮蔽后的完整文本
+    pub text: String,
+    /// 遮蔽后的段落（时间戳不变，涉及敏感信息的词级结果被移除）
+    pub segments: Vec<TranscriptionSegment>,
+    /// 被遮蔽的位置
+    pub spans: Vec<RedactionSpan>,
+}
+
+impl RedactedTranscript {
+    /// 需要遮蔽的音频区间：两侧各扩展 `padding_ms`，重叠区间合并
+    pub fn time_ranges(&self, padding_ms: u64) -> Vec<TimeRange> {
+        let ranges: Vec<TimeRange> = self.spans.iter().map(RedactionSpan::time_range).collect();
+        audio_lib::merge_ranges(&ranges, padding_ms)
+    }
+
+    /// 遮蔽原始音频中对应的区间，写入新的 WAV 文件
+    pub fn write_redacted_wav<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        input: P,
+        output: Q,
+        mode: RedactionMode,
+        padding_ms: u64,
+    ) -> SttResult<()> {
+        audio_lib::write_redacted_wav(input, output, &self.time_ranges(padding_ms), mode)
+            .map_err(|e| SttError::AudioProcessingError(format!("写入遮蔽音频失败: {e}")))
+    }
+}
+
+/// 一处检测结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiiMatch {
+    /// 类型
+    pub kind: PiiKind,
+    /// 在文本中的字节范围
+    pub range: Range<usize>,
+}
+
+/// 自定义规则
+#[derive(Debug, Clone)]
+struct CustomRule {
+    name: String,
+    pattern: Regex,
+}
+
+/// 敏感信息遮蔽器
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    builtin: Vec<PiiKind>,
+    rules: Vec<CustomRule>,
+    mask: MaskStyle,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self {
+            builtin: PiiKind::builtin().to_vec(),
+            rules: Vec::new(),
+            mask: MaskStyle::default(),
+        }
+    }
+}
+
+impl Redactor {
+    /// 启用全部内置检测
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 只启用指定的内置检测（传入空列表时只使用自定义规则）
+    pub fn with_builtin<I: IntoIterator<Item = PiiKind>>(mut self, kinds: I) -> Self {
+        self.builtin = kinds
+            .into_iter()
+            .filter(|kind| !matches!(kind, PiiKind::Custom(_)))
+            .collect();
+        self
+    }
+
+    /// 添加自定义正则规则，`name` 作为遮蔽标签
+    pub fn with_rule<S: Into<String>>(mut self, name: S, pattern: &str) -> SttResult<Self> {
+        let name = name.into();
+        let pattern = Regex::new(pattern)
+            .map_err(|e| SttError::ConfigError(format!("遮蔽规则 {name} 无效: {e}")))?;
+        self.rules.push(CustomRule { name, pattern });
+        Ok(self)
+    }
+
+    /// 设置遮蔽样式
+    pub fn with_mask(mut self, mask: MaskStyle) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    /// 检测文本中的敏感信息，按位置排序，重叠时保留先出现且更长的一处
+    pub fn find(&self, text: &str) -> Vec<PiiMatch> {
+        let mut matches = Vec::new();
+
+        for rule in &self.rules {
+            matches.extend(rule.pattern.find_iter(text).map(|m| PiiMatch {
+                kind: PiiKind::Custom(rule.name.clone()),
+                range: m.range(),
+            }));
+        }
+        if self.builtin.contains(&PiiKind::Email) {
+            matches.extend(find_emails(text));
+        }
+        matches.extend(
+            find_numbers(text)
+                .into_iter()
+                .filter(|m| self.builtin.contains(&m.kind)),
+        );
+
+        matches.sort_by_key(|m| (m.range.start, std::cmp::Reverse(m.range.end)));
+        let mut accepted: Vec<PiiMatch> = Vec::with_capacity(matches.len());
+        for m in matches {
+            if m.range.is_empty() {
+                continue;
+            }
+            if accepted
+                .last()
+                .map_or(true, |last| m.range.start >= last.range.end)
+            {
+                accepted.push(m);
+            }
+        }
+        accepted
+    }
+
+    /// 遮蔽一段文本
+    pub fn redact_text(&self, text: &str) -> String {
+        let mut redacted = String::with_capacity(text.len());
+        let mut cursor = 0;
+        for m in self.find(text) {
+            redacted.push_str(&text[cursor..m.range.start]);
+            redacted.push_str(&self.replacement(&m.kind, &text[m.range.clone()]));
+            cursor = m.range.end;
+        }
+        redacted.push_str(&text[cursor..]);
+        redacted
+    }
+
+    /// 遮蔽转录结果，返回遮蔽后的文本、段落以及带时间戳的遮蔽位置
+    pub fn redact(&self, result: &TranscriptionResult) -> RedactedTranscript {
+        let segments: Vec<TranscriptionSegment> = if result.segments.is_empty() {
+            vec![TranscriptionSegment {
+                start_time: 0,
+                end_time: result.audio_duration,
+                text: result.text.clone(),
+                confidence: 0.0,
+                words: Vec::new(),
+            }]
+        } else {
+            result.segments.clone()
+        };
+
+        // 段落以空格拼接后统一检测，跨段落的号码也能识别
+        let mut full = String::new();
+        let mut layout = Vec::with_capacity(segments.len());
+        for segment in &segments {
+            if !full.is_empty() {
+                full.push(' ');
+            }
+            let start = full.len();
+            full.push_str(&segment.text);
+            layout.push(SegmentLayout::new(&full, start..full.len(), segment));
+        }
+
+        let matches = self.find(&full);
+        let spans: Vec<RedactionSpan> = matches
+            .iter()
+            .map(|m| self.span(&full, m, &segments, &layout))
+            .collect();
+
+        if result.segments.is_empty() {
+            return RedactedTranscript {
+                text: self.redact_text(&result.text),
+                segments: Vec::new(),
+                spans,
+            };
+        }
+
+        let redacted_segments: Vec<TranscriptionSegment> = segments
+            .iter()
+            .zip(&layout)
+            .map(|(segment, layout)| {
+                let mut segment = segment.clone();
+                segment.text = self.redact_range(&full, layout.range.clone(), &matches);
+                // 无法在段落文本中定位的词（如文本经后处理改写）可能包含敏感信息：
+                // 段落中有匹配时一律删除
+                let segment_hit = overlaps_any(&layout.range, &matches);
+                segment.words = segment
+                    .words
+                    .iter()
+                    .zip(&layout.words)
+                    .filter(|(_, range)| match range {
+                        Some(range) => !overlaps_any(range, &matches),
+                        None => !segment_hit,
+                    })
+                    .map(|(word, _)| word.clone())
+                    .collect();
+                segment
+            })
+            .collect();
+
+        RedactedTranscript {
+            text: join_segments(redacted_segments.iter().map(|s| s.text.as_str())),
+            segments: redacted_segments,
+            spans,
+        }
+    }
+
+    fn replacement(&self, kind: &PiiKind, original: &str) -> String {
+        match self.mask {
+            MaskStyle::Label => format!("[{}]", kind.label()),
+            MaskStyle::Char(mask) => original
+                .chars()
+                .map(|c| if c.is_alphanumeric() { mask } else { c })
+                .collect(),
+        }
+    }
+
+    /// 遮蔽拼接文本中某个段落的部分：敏感信息在开始的段落中替换，在后续段落中删除
+    fn redact_range(&self, full: &str, range: Range<usize>, matches: &[PiiMatch]) -> String {
+        let mut text = String::new();
+        let mut cursor = range.start;
+        for m in matches {
+            if m.range.end <= range.start || m.range.start >= range.end {
+                continue;
+            }
+            let start = m.range.start.max(range.start);
+            text.push_str(&full[cursor..start]);
+            if m.range.start >= range.start {
+                text.push_str(&self.replacement(&m.kind, &full[m.range.clone()]));
+            }
+            cursor = m.range.end.min(range.end);
+        }
+        text.push_str(&full[cursor..range.end]);
+        text.trim().to_string()
+    }
+
+    fn span(
+        &self,
+        full: &str,
+        m: &PiiMatch,
+        segments: &[TranscriptionSegment],
+        layout: &[SegmentLayout],
+    ) -> RedactionSpan {
+        let indices: Vec<usize> = layout
+            .iter()
+            .enumerate()
+            .filter(|(_, layout)| {
+                layout.range.start < m.range.end && m.range.start < layout.range.end
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        // 所有涉及的段落都有词级结果时使用词的时间范围
+        let mut word_times: Vec<(u64, u64)> = Vec::new();
+        let mut words_complete = true;
+        for &index in &indices {
+            let segment_words: Vec<(u64, u64)> = segments[index]
+                .words
+                .iter()
+                .zip(&layout[index].words)
+                .filter_map(|(word, range)| {
+                    let range = range.as_ref()?;
+                    (range.start < m.range.end && m.range.start < range.end)
+                        .then_some((word.start_time, word.end_time))
+                })
+                .collect();
+            words_complete &= !segment_words.is_empty();
+            word_times.extend(segment_words);
+        }
+
+        let (start_time, end_time, timing) = if words_complete && !word_times.is_empty() {
+            (
+                word_times.iter().map(|t| t.0).min().unwrap_or(0),
+                word_times.iter().map(|t| t.1).max().unwrap_or(0),
+                SpanTiming::Word,
+            )
+        } else {
+            (
+                indices
+                    .iter()
+                    .map(|&i| segments[i].start_time)
+                    .min()
+                    .unwrap_or(0),
+                indices
+                    .iter()
+                    .map(|&i| segments[i].end_time)
+                    .max()
+                    .unwrap_or(0),
+                SpanTiming::Segment,
+            )
+        };
+
+        RedactionSpan {
+            kind: m.kind.clone(),
+            replacement: self.replacement(&m.kind, &full[m.range.clone()]),
+            segment_indices: indices,
+            start_time,
+            end_time,
+            timing,
+        }
+    }
+}
+
+/// 段落在拼接文本中的位置，以及每个词的位置（找不到时为 None）
+struct SegmentLayout {
+    range: Range<usize>,
+    words: Vec<Option<Range<usize>>>,
+}
+
+impl SegmentLayout {
+    fn new(full: &str, range: Range<usize>, segment: &TranscriptionSegment) -> Self {
+        let mut cursor = range.start;
+        let words = segment
+            .words
+            .iter()
+            .map(|word| {
+                let text = word.text.trim();
+                if text.is_empty() {
+                    return None;
+                }
+                let start = cursor + full[cursor..range.end].find(text)?;
+                cursor = start + text.len();
+                Some(start..cursor)
+            })
+            .collect();
+        Self { range, words }
+    }
+}
+
+fn overlaps_any(range: &Range<usize>, matches: &[PiiMatch]) -> bool {
+    matches
+        .iter()
+        .any(|m| range.start < m.range.end && m.range.start < range.end)
+}
+
+/// 检测电子邮箱，包括“艾特/at”“点/dot”的口语形式
+fn find_emails(text: &str) -> Vec<PiiMatch> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)[a-z0-9][a-z0-9._%+-]*\s*(?:@|艾特|\bat\b)\s*[a-z0-9-]+(?:\s*(?:\.|点|\bdot\b)\s*[a-z0-9-]+)*\s*(?:\.|点|\bdot\b)\s*[a-z]{2,}\b",
+        )
+        .unwrap()
+    });
+    re.find_iter(text)
+        .map(|m| PiiMatch {
+            kind: PiiKind::Email,
+            range: m.range(),
+        })
+        .collect()
+}
+
+/// 检测数字串并按长度与格式归类为身份证号、电话号码或银行卡号
+fn find_numbers(text: &str) -> Vec<PiiMatch> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    static DIGIT_RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(&format!(
+            r"(?i)(?P<plus>\+\s*)?\(?{DIGIT}(?:[\s\-()]{{0,3}}{DIGIT})*(?:[\s\-]?(?P<x>x))?"
+        ))
+        .unwrap()
+    });
+    let digit_re = DIGIT_RE.get_or_init(|| Regex::new(&format!("(?i){DIGIT}")).unwrap());
+
+    re.captures_iter(text)
+        .filter_map(|caps| {
+            let whole = caps.get(0)?;
+            let x = caps.name("x").filter(|x| {
+                !text[x.end()..]
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_alphanumeric())
+            });
+            let digits_end = caps.name("x").map_or(whole.end(), |x| x.start());
+            if is_date_shaped(&text[whole.start()..digits_end])
+                || is_decimal_part(text, whole.start(), digits_end)
+            {
+                return None;
+            }
+            let digits: String = digit_re
+                .find_iter(&text[whole.start()..digits_end])
+                .filter_map(|d| normalize_digit(d.as_str()))
+                .collect();
+
+            let kind = classify(&digits, x.is_some(), caps.name("plus").is_some())?;
+            let end = if kind == PiiKind::IdNumber && x.is_some() {
+                whole.end()
+            } else {
+                whole.start()
+                    + text[whole.start()..digits_end]
+                        .trim_end_matches(|c: char| c.is_whitespace() || "-(".contains(c))
+                        .len()
+            };
+            Some(PiiMatch {
+                kind,
+                range: whole.start()..end,
+            })
+        })
+        .collect()
+}
+
+fn normalize_digit(token: &str) -> Option<char> {
+    let digit = match token.to_lowercase().as_str() {
+        "zero" | "oh" | "零" | "〇" => '0',
+        "one" | "一" | "幺" => '1',
+        "two" | "二" => '2',
+        "three" | "三" => '3',
+        "four" | "四" => '4',
+        "five" | "五" => '5',
+        "six" | "六" => '6',
+        "seven" | "七" => '7',
+        "eight" | "八" => '8',
+        "nine" | "九" => '9',
+        other => {
+            let c = other.chars().next()?;
+            match c {
+                '0'..='9' => c,
+                '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32)?,
+                _ => return None,
+            }
+        }
+    };
+    Some(digit)
+}
+
+/// 按数字个数与格式归类
+fn classify(digits: &str, has_x: bool, has_plus: bool) -> Option<PiiKind> {
+    let len = digits.len() + usize::from(has_x);
+    if len == 18 && (!has_x || digits.len() == 17) && plausible_birth_date(digits) {
+        return Some(PiiKind::IdNumber);
+    }
+    let digits_len = digits.len();
+    if has_plus && (7..=15).contains(&digits_len) {
+        return Some(PiiKind::PhoneNumber);
+    }
+    let mobile = digits_len == 11
+        && digits.starts_with('1')
+        && digits[1..2].chars().all(|c| ('3'..='9').contains(&c));
+    if mobile {
+        return Some(PiiKind::PhoneNumber);
+    }
+    match digits_len {
+        13..=19 => Some(PiiKind::CardNumber),
+        7..=12 => Some(PiiKind::PhoneNumber),
+        _ => None,
+    }
+}
+
+/// 数字是否紧邻小数点（如金额 `12345.67` 的整数或小数部分）
+fn is_decimal_part(text: &str, start: usize, end: usize) -> bool {
+    let digit_before = text[..start]
+        .strip_suffix('.')
+        .is_some_and(|rest| rest.ends_with(|c: char| c.is_ascii_digit()));
+    let digit_after = text[end..]
+        .strip_prefix('.')
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()));
+    digit_before || digit_after
+}
+
+/// 是否为 `2024-03-05` 这样按 4-2-2 分组的日期
+fn is_date_shaped(text: &str) -> bool {
+    let groups: Vec<&str> = text
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|group| !group.is_empty())
+        .collect();
+    let [year, month, day] = groups[..] else {
+        return false;
+    };
+    year.len() == 4
+        && month.len() == 2
+        && day.len() == 2
+        && plausible_birth_date(&format!("000000{year}{month}{day}"))
+}
+
+/// 身份证号第 7-14 位是否为合理的出生日期
+fn plausible_birth_date(digits: &str) -> bool {
+    let field = |range: Range<usize>| digits.get(range).and_then(|s| s.parse::<u32>().ok());
+    matches!(
+        (field(6..10), field(10..12), field(12..14)),
+        (Some(1900..=2099), Some(1..=12), Some(1..=31))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::words::TranscriptionWord;
+
+    fn kinds(redactor: &Redactor, text: &str) -> Vec<(PiiKind, String)> {
+        redactor
+            .find(text)
+            .into_iter()
+            .map(|m| (m.kind, text[m.range].to_string()))
+            .collect()
+    }
+
+    fn segment(start_time: u64, end_time: u64, text: &str) -> TranscriptionSegment {
+        TranscriptionSegment {
+            start_time,
+            end_time,
+            text: text.to_string(),
+            confidence: 0.9,
+            words: Vec::new(),
+        }
+    }
+
+    fn result(segments: Vec<TranscriptionSegment>) -> TranscriptionResult {
+        TranscriptionResult {
+            text: segments
+                .iter()
+                .map(|s| s.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            language: Some("zh".to_string()),
+            language_detection: None,
+            segments,
+            processing_time: 0,
+            audio_duration: 10_000,
+            decisions: Vec::new(),
+            skipped_duration: 0,
+        }
+    }
+
+    #[test]
+    fn test_builtin_detectors() {
+        let redactor = Redactor::new();
+        // 日期与带小数的金额不是电话号码
+        assert!(kinds(&redactor, "会议定在2024-03-05上午").is_empty());
+        assert!(kinds(&redactor, "on 2024 03 05 we met").is_empty());
+        assert!(kinds(&redactor, "共计12345.67元").is_empty());
+        assert!(kinds(&redactor, "余额为 1234567.89 元").is_empty());
+        assert_eq!(
+            kinds(
+                &redactor,
+                "我的卡号是6222 0212 3456 7890，手机幺三八一二三四五六七八"
+            ),
+            vec![
+                (PiiKind::CardNumber, "6222 0212 3456 7890".to_string()),
+                (PiiKind::PhoneNumber, "幺三八一二三四五六七八".to_string()),
+            ]
+        );
+        assert_eq!(
+            kinds(&redactor, "身份证号11010519491231002X。"),
+            vec![(PiiKind::IdNumber, "11010519491231002X".to_string())]
+        );
+        assert_eq!(
+            kinds(&redactor, "call me at (415) 555-0123 please"),
+            vec![(PiiKind::PhoneNumber, "(415) 555-0123".to_string())]
+        );
+        assert_eq!(
+            kinds(&redactor, "it's five five five one two three four"),
+            vec![(
+                PiiKind::PhoneNumber,
+                "five five five one two three four".to_string()
+            )]
+        );
+        assert_eq!(
+            kinds(
+                &redactor,
+                "邮箱是 zhang.san@example.com 或者 lisi 艾特 qq 点 com"
+            ),
+            vec![
+                (PiiKind::Email, "zhang.san@example.com".to_string()),
+                (PiiKind::Email, "lisi 艾特 qq 点 com".to_string()),
+            ]
+        );
+        assert_eq!(
+            kinds(&redactor, "write to john at gmail dot com"),
+            vec![(PiiKind::Email, "john at gmail dot com".to_string())]
+        );
+        // 金额、日期等短数字不受影响
+        assert!(redactor.find("余额还有3500元，2024年3月5日到期").is_empty());
+    }
+
+    #[test]
+    fn test_custom_rules_and_mask() {
+        let redactor = Redactor::new()
+            .with_builtin([])
+            .with_rule("POLICY", r"[A-Z]{2}\d{6}")
+            .unwrap()
+            .with_mask(MaskStyle::Char('*'));
+        assert_eq!(
+            redactor.redact_text("保单号AB123456，电话13812345678"),
+            "保单号********，电话13812345678"
+        );
+        assert!(Redactor::new().with_rule("BAD", "(").is_err());
+        assert_eq!(
+            Redactor::new().redact_text("电话13812345678"),
+            "电话[PHONE_NUMBER]"
+        );
+    }
+
+    #[test]
+    fn test_redact_with_word_timestamps() {
+        let mut first = segment(0, 3_000, "请记下我的电话 138 1234 5678");
+        first.words = [
+            (0, 1_500, "请记下我的电话"),
+            (1_600, 2_000, "138"),
+            (2_000, 2_500, "1234"),
+            (2_500, 2_900, "5678"),
+        ]
+        .into_iter()
+        .map(|(start_time, end_time, text)| TranscriptionWord {
+            start_time,
+            end_time,
+            text: text.to_string(),
+            probability: 0.9,
+        })
+        .collect();
+        let result = result(vec![first, segment(3_000, 5_000, "谢谢")]);
+
+        let redacted = Redactor::new().redact(&result);
+        assert_eq!(redacted.text, "请记下我的电话 [PHONE_NUMBER] 谢谢");
+        assert_eq!(redacted.segments[0].words.len(), 1);
+        assert_eq!(redacted.spans.len(), 1);
+        let span = &redacted.spans[0];
+        assert_eq!(span.replacement, "[PHONE_NUMBER]");
+        // 序列化结果中不包含原始号码
+        let json = serde_json::to_string(&redacted).unwrap();
+        assert!(!json.contains("1234"));
+        assert_eq!(span.timing, SpanTiming::Word);
+        assert_eq!((span.start_time, span.end_time), (1_600, 2_900));
+        assert_eq!(
+            redacted.time_ranges(100),
+            vec![TimeRange::new(1_500, 3_000)]
+        );
+    }
+
+    #[test]
+    fn test_redact_after_post_processing() {
+        let spoken = "电话一三八一二三四五六七八";
+        let mut first = segment(0, 3_000, spoken);
+        first.words = spoken
+            .chars()
+            .enumerate()
+            .map(|(i, c)| TranscriptionWord {
+                start_time: i as u64 * 200,
+                end_time: i as u64 * 200 + 200,
+                text: c.to_string(),
+                probability: 0.9,
+            })
+            .collect();
+        let mut result = result(vec![first, segment(3_000, 5_000, "谢谢")]);
+        for word in ["你", "好"] {
+            result.segments[1].words.push(TranscriptionWord {
+                start_time: 3_000,
+                end_time: 4_000,
+                text: word.to_string(),
+                probability: 0.9,
+            });
+        }
+        crate::postprocess::PostProcessConfig::default().apply(&mut result);
+        assert!(result.segments[0].text.contains("13812345678"));
+
+        // 改写后的文本中定位不到逐字的词，有匹配的段落删除这些词，没有匹配的段落保持不变
+        let redacted = Redactor::new().redact(&result);
+        assert!(redacted.segments[0].text.contains("[PHONE_NUMBER]"));
+        assert_eq!(redacted.segments[0].words.len(), 2);
+        assert_eq!(redacted.segments[1].words.len(), 2);
+        let json = serde_json::to_string(&redacted).unwrap();
+        assert!(!json.contains("\"三\""), "{json}");
+    }
+
+    #[test]
+    fn test_redact_across_segments() {
+        let result = result(vec![
+            segment(0, 2_000, "卡号是6222 0212"),
+            segment(2_000, 4_000, "3456 7890 对吧"),
+            segment(4_000, 6_000, "好的"),
+        ]);
+
+        let redacted = Redactor::new().redact(&result);
+        assert_eq!(redacted.segments[0].text, "卡号是[CARD_NUMBER]");
+        assert_eq!(redacted.segments[1].text, "对吧");
+        assert_eq!(redacted.segments[1].start_time, 2_000);
+        let span = &redacted.spans[0];
+        assert_eq!(span.kind, PiiKind::CardNumber);
+        assert_eq!(span.segment_indices, vec![0, 1]);
+        assert_eq!(span.timing, SpanTiming::Segment);
+        assert_eq!((span.start_time, span.end_time), (0, 4_000));
+    }
+}
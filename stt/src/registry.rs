@@ -0,0 +1,514 @@
+//! 本地模型注册表
+//!
+//! 在配置的目录中查找 ggml 模型文件，按内置的校验和表识别已知的 whisper 模型，
+//! 并检测损坏（校验和不符）或不完整（文件头/张量不完整）的文件。
+//! 调用方可以按名称（如 `"small"`、`"medium.en"`、`"large-v3-q5_0"`）获取模型，而不必手动拼接路径。
+//! 注册表只校验和解析磁盘上已有的文件，不负责下载。
+
+use crate::error::{SttError, SttResult};
+use crate::model::{read_model_info, ModelInfo};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// 指定额外模型目录的环境变量，多个目录按系统路径分隔符分隔
+pub const MODEL_DIR_ENV: &str = "WHISPER_MODEL_DIR";
+
+/// 内置的已知模型表：(名称, SHA-1)，与 whisper.cpp 发布的 ggml 模型一致
+///
+/// whisper.cpp 只发布 SHA-1，内置表不含 SHA-256；SHA-256 用于 [`ModelRegistry::with_model`] 登记的模型。
+const KNOWN_MODELS: &[(&str, &str)] = &[
+    ("tiny", "bd577a113a864445d4c299885e0cb97d4ba92b5f"),
+    ("tiny.en", "c78c86eb1a8faa21b369bcd33207cc90d64ae9df"),
+    ("tiny-q5_1", "2827a03e495b1ed3048ef28a6a4620537db4ee51"),
+    ("base", "465707469ff3a37a2b9b8d8f89f2f99de7299dac"),
+    ("base.en", "137c40403d78fd54d454da0f9bd998f78703390c"),
+    ("base-q5_1", "a3733eda680ef76256db5fc5dd9de8629e62c5e7"),
+    ("small", "55356645c2b361a969dfd0ef2c5a50d530afd8d5"),
+    ("small.en", "db8a495a91d927739e50b3fc1cc4c6b8f6c2d022"),
+    ("small-q5_1", "6fe57ddcfdd1c6b07cdcc73aaf620810ce5fc771"),
+    ("medium", "fd9727b6e1217c2f614f9b698455c4ffd82463b4"),
+    ("medium.en", "8c30f0e44ce9560643ebd10bbe50cd20eafd3723"),
+    ("medium-q5_0", "7718d4c1ec62ca96998f058114db417236937323"),
+    ("large-v1", "b1caaf735c4cc1429223d5a74f0f4d0b9b59a299"),
+    ("large-v2", "0f4c8e34f21cf1a914c59d8b3ce882345ad349d6"),
+    ("large-v3", "ad82bf6a9043ceed055076d0fd39f5f186ff8062"),
+    ("large-v3-q5_0", "e6e2ed78495d403bef4b7cff42ef4aaadcfea8de"),
+    ("large-v3-turbo", "4af2b29d7ec73d781377bfd1758ca957a807e941"),
+    (
+        "large-v3-turbo-q5_0",
+        "e050f7970618a659205450ad97eb95a18d69c9ee",
+    ),
+];
+
+/// 模型文件校验和（小写十六进制）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Checksum {
+    Sha1(String),
+    Sha256(String),
+}
+
+impl Checksum {
+    fn matches(&self, hashes: &FileHashes) -> bool {
+        match self {
+            Checksum::Sha1(expected) => expected.eq_ignore_ascii_case(&hashes.sha1),
+            Checksum::Sha256(expected) => hashes
+                .sha256
+                .as_ref()
+                .is_some_and(|actual| expected.eq_ignore_ascii_case(actual)),
+        }
+    }
+
+    fn value(&self) -> &str {
+        match self {
+            Checksum::Sha1(value) | Checksum::Sha256(value) => value,
+        }
+    }
+}
+
+/// 已知模型
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownModel {
+    /// 模型名称，对应文件名 `ggml-<name>.bin`
+    pub name: String,
+    /// 期望的校验和
+    pub checksum: Checksum,
+}
+
+/// 模型文件状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModelStatus {
+    /// 校验和与已知模型一致
+    Verified,
+    /// 文件完整，但不在已知模型表中（如自行微调或量化的模型）
+    Unknown,
+    /// 文件名对应已知模型，但校验和不符
+    Corrupted { expected: String, actual: String },
+    /// 文件头或张量不完整，无法加载
+    Invalid(String),
+}
+
+impl ModelStatus {
+    /// 文件是否可以加载
+    pub fn is_usable(&self) -> bool {
+        matches!(self, ModelStatus::Verified | ModelStatus::Unknown)
+    }
+}
+
+/// 注册表中的一个模型文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelEntry {
+    /// 模型名称：已识别时为已知名称，否则取自文件名
+    pub name: String,
+    /// 文件路径
+    pub path: PathBuf,
+    /// 校验状态
+    pub status: ModelStatus,
+    /// 文件头元数据（文件无效时为 None）
+    pub info: Option<ModelInfo>,
+}
+
+/// 本地模型注册表
+#[derive(Debug, Clone)]
+pub struct ModelRegistry {
+    dirs: Vec<PathBuf>,
+    known: Vec<KnownModel>,
+}
+
+impl Default for ModelRegistry {
+    /// 搜索默认目录（见 [`default_model_dirs`]），使用内置的已知模型表
+    fn default() -> Self {
+        Self::new(default_model_dirs())
+    }
+}
+
+impl ModelRegistry {
+    /// 创建搜索指定目录的注册表，使用内置的已知模型表
+    pub fn new<I, P>(dirs: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        Self {
+            dirs: dirs.into_iter().map(Into::into).collect(),
+            known: KNOWN_MODELS
+                .iter()
+                .map(|(name, sha1)| KnownModel {
+                    name: name.to_string(),
+                    checksum: Checksum::Sha1(sha1.to_string()),
+                })
+                .collect(),
+        }
+    }
+
+    /// 追加搜索目录
+    pub fn with_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.dirs.push(dir.into());
+        self
+    }
+
+    /// 登记自定义模型，如自行量化或微调的模型
+    pub fn with_model<S: Into<String>>(mut self, name: S, checksum: Checksum) -> Self {
+        self.known.push(KnownModel {
+            name: name.into(),
+            checksum,
+        });
+        self
+    }
+
+    /// 搜索目录
+    pub fn dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+
+    /// 已知模型表
+    pub fn known_models(&self) -> &[KnownModel] {
+        &self.known
+    }
+
+    /// 扫描所有目录中的 `.bin` 文件并逐个校验
+    ///
+    /// 需要计算每个文件的校验和，大模型较慢。
+    pub fn scan(&self) -> Vec<ModelEntry> {
+        self.model_files()
+            .iter()
+            .map(|path| self.verify(path))
+            .collect()
+    }
+
+    /// 校验单个模型文件：解析文件头并与已知模型表比对校验和
+    pub fn verify<P: AsRef<Path>>(&self, path: P) -> ModelEntry {
+        let path = path.as_ref();
+        let file_name = name_from_file(path);
+        let entry = |name: String, status, info| ModelEntry {
+            name,
+            path: path.to_path_buf(),
+            status,
+            info,
+        };
+
+        let info = match read_model_info(path) {
+            Ok(info) => info,
+            Err(e) => return entry(file_name, ModelStatus::Invalid(e.to_string()), None),
+        };
+
+        let with_sha256 = self
+            .known
+            .iter()
+            .any(|model| matches!(model.checksum, Checksum::Sha256(_)));
+        let hashes = match FileHashes::compute(path, with_sha256) {
+            Ok(hashes) => hashes,
+            Err(e) => {
+                let status = ModelStatus::Invalid(format!("读取模型文件失败: {e}"));
+                return entry(file_name, status, Some(info));
+            }
+        };
+
+        if let Some(model) = self
+            .known
+            .iter()
+            .find(|model| model.checksum.matches(&hashes))
+        {
+            return entry(model.name.clone(), ModelStatus::Verified, Some(info));
+        }
+
+        let status = match self.known.iter().find(|model| model.name == file_name) {
+            Some(model) => {
+                let actual = match model.checksum {
+                    Checksum::Sha1(_) => hashes.sha1,
+                    Checksum::Sha256(_) => hashes.sha256.unwrap_or_default(),
+                };
+                ModelStatus::Corrupted {
+                    expected: model.checksum.value().to_string(),
+                    actual,
+                }
+            }
+            None => ModelStatus::Unknown,
+        };
+        entry(file_name, status, Some(info))
+    }
+
+    /// 按文件名查找模型（`ggml-<name>.bin` 或 `<name>.bin`），不计算校验和
+    pub fn find(&self, name: &str) -> Option<PathBuf> {
+        self.candidates(name).into_iter().next()
+    }
+
+    /// 按名称获取经过校验的模型文件路径
+    ///
+    /// 先按文件名查找并校验；没有可用文件时扫描所有目录，按校验和找出被重命名的已知模型。
+    /// 文件损坏或不完整时返回 [`SttError::ModelLoadError`]。
+    pub fn resolve(&self, name: &str) -> SttResult<PathBuf> {
+        let mut last_error = None;
+        for path in self.candidates(name) {
+            let entry = self.verify(&path);
+            match entry.status {
+                ModelStatus::Verified | ModelStatus::Unknown => return Ok(entry.path),
+                ModelStatus::Corrupted { expected, actual } => {
+                    warn!("模型文件校验和不符: {}", path.display());
+                    last_error = Some(format!(
+                        "模型文件已损坏: {}（期望校验和 {expected}，实际 {actual}）",
+                        path.display()
+                    ));
+                }
+                ModelStatus::Invalid(reason) => {
+                    warn!("模型文件无效: {}", path.display());
+                    last_error = Some(reason);
+                }
+            }
+        }
+
+        if self.known.iter().any(|model| model.name == name) {
+            info!("按校验和扫描模型目录以查找 {name}");
+            if let Some(entry) = self
+                .scan()
+                .into_iter()
+                .find(|entry| entry.status == ModelStatus::Verified && entry.name == name)
+            {
+                return Ok(entry.path);
+            }
+        }
+
+        Err(SttError::ModelLoadError(
+            last_error.unwrap_or_else(|| self.not_found(name)),
+        ))
+    }
+
+    /// 解析模型路径或名称：名称按文件名查找，并检查文件头与文件大小（不计算校验和），
+    /// 其他路径原样返回
+    fn resolve_path(&self, model: &Path) -> SttResult<PathBuf> {
+        if model.exists() || !is_model_name(model) {
+            return Ok(model.to_path_buf());
+        }
+        let Some(name) = model.to_str() else {
+            return Ok(model.to_path_buf());
+        };
+
+        let mut last_error = None;
+        for path in self.candidates(name) {
+            match read_model_info(&path) {
+                Ok(_) => return Ok(path),
+                Err(e) => {
+                    warn!("模型文件无效: {}", path.display());
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| SttError::ModelLoadError(self.not_found(name))))
+    }
+
+    fn not_found(&self, name: &str) -> String {
+        let dirs: Vec<String> = self.dirs.iter().map(|d| d.display().to_string()).collect();
+        format!("未找到模型 {name}（搜索目录: {}）", dirs.join(", "))
+    }
+
+    /// 按文件名匹配的候选文件
+    fn candidates(&self, name: &str) -> Vec<PathBuf> {
+        let file_names = [format!("ggml-{name}.bin"), format!("{name}.bin")];
+        self.dirs
+            .iter()
+            .flat_map(|dir| file_names.iter().map(move |file| dir.join(file)))
+            .filter(|path| path.is_file())
+            .collect()
+    }
+
+    /// 所有目录中的 `.bin` 文件，按路径排序
+    fn model_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self
+            .dirs
+            .iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "bin"))
+            .collect();
+        files.sort();
+        files
+    }
+}
+
+/// 默认的模型搜索目录：环境变量 `WHISPER_MODEL_DIR` 中的目录，然后是 `models` 与 `fixtures/models`
+pub fn default_model_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::env::var_os(MODEL_DIR_ENV)
+        .map(|value| std::env::split_paths(&value).collect())
+        .unwrap_or_default();
+    dirs.push(PathBuf::from("models"));
+    dirs.push(PathBuf::from("fixtures/models"));
+    dirs
+}
+
+/// 把模型名称解析为默认目录中经过校验的文件路径；已存在的路径与不像名称的路径原样返回
+///
+/// 加载时只检查文件头与文件大小，不完整或找不到时返回 [`SttError::ModelLoadError`]；
+/// 大模型的校验和需要数秒，只在显式调用 [`ModelRegistry::resolve`]、`verify` 或 `scan` 时计算。
+pub(crate) fn resolve_model_path(model: &Path) -> SttResult<PathBuf> {
+    ModelRegistry::default().resolve_path(model)
+}
+
+/// 是否像模型名称（单个路径分量且不以 `.bin` 结尾）
+fn is_model_name(model: &Path) -> bool {
+    model.components().count() == 1 && model.extension() != Some(OsStr::new("bin"))
+}
+
+/// 从文件名推断模型名称：去掉 `ggml-` 前缀与 `.bin` 扩展名
+fn name_from_file(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    stem.strip_prefix("ggml-")
+        .map(str::to_string)
+        .unwrap_or(stem)
+}
+
+/// 文件的校验和
+struct FileHashes {
+    sha1: String,
+    sha256: Option<String>,
+}
+
+impl FileHashes {
+    /// 流式计算校验和，不把文件读入内存
+    fn compute(path: &Path, with_sha256: bool) -> std::io::Result<Self> {
+        let mut file = File::open(path)?;
+        let mut sha1 = Sha1::new();
+        let mut sha256 = with_sha256.then(Sha256::new);
+        let mut buf = vec![0u8; 1 << 20];
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            sha1.update(&buf[..n]);
+            if let Some(sha256) = &mut sha256 {
+                sha256.update(&buf[..n]);
+            }
+        }
+
+        Ok(Self {
+            sha1: format!("{:x}", sha1.finalize()),
+            sha256: sha256.map(|hasher| format!("{:x}", hasher.finalize())),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::write_test_model;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("stt_registry_{}_{name}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn hashes(path: &Path) -> FileHashes {
+        FileHashes::compute(path, true).unwrap()
+    }
+
+    #[test]
+    fn test_scan_statuses() {
+        let dir = temp_dir("scan");
+        // 自定义模型：以 SHA-256 登记
+        write_test_model(&dir.join("ggml-custom.bin"), 51865, 1, 1);
+        // 名称对应内置模型但内容不同：校验和不符
+        write_test_model(&dir.join("ggml-tiny.bin"), 51865, 4, 4);
+        // 未登记的模型
+        write_test_model(&dir.join("ggml-mine.bin"), 51864, 1, 1);
+        // 下载中断的文件
+        let bytes = std::fs::read(dir.join("ggml-custom.bin")).unwrap();
+        std::fs::write(dir.join("ggml-base.bin"), &bytes[..bytes.len() / 2]).unwrap();
+        std::fs::write(dir.join("notes.txt"), b"").unwrap();
+
+        let sha256 = hashes(&dir.join("ggml-custom.bin")).sha256.unwrap();
+        let registry = ModelRegistry::new([&dir]).with_model("custom", Checksum::Sha256(sha256));
+        let entries = registry.scan();
+        assert_eq!(entries.len(), 4);
+
+        let status = |name: &str| {
+            entries
+                .iter()
+                .find(|entry| entry.name == name)
+                .map(|entry| entry.status.clone())
+                .unwrap()
+        };
+        assert_eq!(status("custom"), ModelStatus::Verified);
+        assert_eq!(status("mine"), ModelStatus::Unknown);
+        assert!(matches!(status("base"), ModelStatus::Invalid(_)));
+        match status("tiny") {
+            ModelStatus::Corrupted { expected, .. } => assert_eq!(expected, KNOWN_MODELS[0].1),
+            other => panic!("应为校验和不符: {other:?}"),
+        }
+        assert!(!status("tiny").is_usable());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_by_name() {
+        let dir = temp_dir("resolve");
+        write_test_model(&dir.join("renamed.bin"), 51865, 1, 1);
+        write_test_model(&dir.join("ggml-small.bin"), 51865, 2, 2);
+        write_test_model(&dir.join("ggml-mine.bin"), 51865, 3, 3);
+
+        let sha1 = hashes(&dir.join("renamed.bin")).sha1;
+        let registry = ModelRegistry::new([&dir]).with_model("custom", Checksum::Sha1(sha1));
+
+        // 文件名不符时按校验和找到被重命名的模型
+        assert_eq!(registry.resolve("custom").unwrap(), dir.join("renamed.bin"));
+        assert_eq!(registry.resolve("mine").unwrap(), dir.join("ggml-mine.bin"));
+        assert_eq!(registry.find("small"), Some(dir.join("ggml-small.bin")));
+
+        match registry.resolve("small") {
+            Err(SttError::ModelLoadError(msg)) => assert!(msg.contains("已损坏"), "{msg}"),
+            other => panic!("应返回模型损坏错误: {other:?}"),
+        }
+        // 加载时按名称解析只检查文件头与大小，不计算校验和
+        assert_eq!(
+            registry.resolve_path(Path::new("small")).unwrap(),
+            dir.join("ggml-small.bin")
+        );
+        assert_eq!(
+            registry.resolve_path(Path::new("mine")).unwrap(),
+            dir.join("ggml-mine.bin")
+        );
+        let bytes = std::fs::read(dir.join("ggml-mine.bin")).unwrap();
+        std::fs::write(dir.join("ggml-partial.bin"), &bytes[..bytes.len() / 2]).unwrap();
+        assert!(matches!(
+            registry.resolve_path(Path::new("partial")),
+            Err(SttError::ModelLoadError(_))
+        ));
+
+        match registry.resolve("medium.en") {
+            Err(SttError::ModelLoadError(msg)) => assert!(msg.contains("未找到模型"), "{msg}"),
+            other => panic!("应返回未找到错误: {other:?}"),
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_model_name_detection() {
+        assert!(is_model_name(Path::new("large-v3-q5_0")));
+        assert!(is_model_name(Path::new("medium.en")));
+        assert!(!is_model_name(Path::new("ggml-small.bin")));
+        assert!(!is_model_name(Path::new("models/small")));
+        assert_eq!(
+            name_from_file(Path::new("m/ggml-medium.en.bin")),
+            "medium.en"
+        );
+
+        // 路径原样返回，找不到的名称返回错误
+        let path = PathBuf::from("/tmp/fake_model.bin");
+        assert_eq!(resolve_model_path(&path).unwrap(), path);
+        assert!(matches!(
+            resolve_model_path(Path::new("___no_such_model___")),
+            Err(SttError::ModelLoadError(_))
+        ));
+    }
+}
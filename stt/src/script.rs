@@ -0,0 +1,201 @@
+//! 简繁中文输出控制
+//!
+//! 即使指定 `language = "zh"`，Whisper 仍可能对普通话音频输出繁体字。[`OutputScript`] 通过两种方式统一输出字形：
+//! 解码时加入对应字形的提示词引导模型，解码后再按内置字表（`data/zh_variants.txt`，离线可用）逐字转换段落文本。
+//!
+//! 转换是逐字进行的，不处理词组层面的一简对多繁（如 发/發/髮）；这类字在简转繁时使用最常见的写法或保持原样。
+
+use crate::whisper::TranscriptionResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// 内置繁简字表
+const VARIANTS_TABLE: &str = include_str!("../data/zh_variants.txt");
+
+/// 中文输出字形
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutputScript {
+    /// 保持模型原始输出
+    #[default]
+    AsIs,
+    /// 简体中文
+    Simplified,
+    /// 繁体中文
+    Traditional,
+}
+
+impl OutputScript {
+    /// 按目标字形转换文本
+    pub fn convert(self, text: &str) -> String {
+        match self {
+            OutputScript::AsIs => text.to_string(),
+            OutputScript::Simplified => to_simplified(text),
+            OutputScript::Traditional => to_traditional(text),
+        }
+    }
+
+    /// 引导模型输出目标字形的提示词
+    pub fn prompt(self) -> Option<&'static str> {
+        match self {
+            OutputScript::AsIs => None,
+            OutputScript::Simplified => Some("以下是普通话的句子。"),
+            OutputScript::Traditional => Some("以下是普通話的句子。"),
+        }
+    }
+
+    /// 转换转录结果中的完整文本、段落与词级文本；识别为非中文的结果保持不变
+    pub fn apply(self, result: &mut TranscriptionResult) {
+        if self == OutputScript::AsIs {
+            return;
+        }
+        if result
+            .language
+            .as_deref()
+            .is_some_and(|language| !is_chinese_language(language))
+        {
+            return;
+        }
+
+        result.text = self.convert(&result.text);
+        for segment in &mut result.segments {
+            segment.text = self.convert(&segment.text);
+            for word in &mut segment.words {
+                word.text = self.convert(&word.text);
+            }
+        }
+    }
+}
+
+/// 语言代码是否为中文（普通话或粤语）
+pub(crate) fn is_chinese_language(language: &str) -> bool {
+    matches!(language, "zh" | "yue")
+}
+
+/// 繁体转简体
+pub fn to_simplified(text: &str) -> String {
+    let tables = tables();
+    text.chars()
+        .map(|c| tables.t2s.get(&c).copied().unwrap_or(c))
+        .collect()
+}
+
+/// 简体转繁体
+pub fn to_traditional(text: &str) -> String {
+    let tables = tables();
+    text.chars()
+        .map(|c| tables.s2t.get(&c).copied().unwrap_or(c))
+        .collect()
+}
+
+/// 解析后的转换表
+struct Tables {
+    t2s: HashMap<char, char>,
+    s2t: HashMap<char, char>,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| parse_table(VARIANTS_TABLE))
+}
+
+/// 解析字表：`[双向]` 段落的字对同时用于两个方向，`[仅繁转简]` 段落只用于繁转简
+fn parse_table(content: &str) -> Tables {
+    let mut tables = Tables {
+        t2s: HashMap::new(),
+        s2t: HashMap::new(),
+    };
+    let mut bidirectional = true;
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            bidirectional = line == "[双向]";
+            continue;
+        }
+        for pair in line.split_whitespace() {
+            let mut chars = pair.chars();
+            let (Some(traditional), Some(simplified), None) =
+                (chars.next(), chars.next(), chars.next())
+            else {
+                continue;
+            };
+            tables.t2s.insert(traditional, simplified);
+            if bidirectional {
+                tables.s2t.entry(simplified).or_insert(traditional);
+            }
+        }
+    }
+
+    tables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::whisper::TranscriptionSegment;
+    use crate::words::TranscriptionWord;
+
+    #[test]
+    fn test_table_is_consistent() {
+        let tables = tables();
+        assert!(tables.t2s.len() > 1000);
+        // 简转繁再转回简体应得到原字
+        for (simplified, traditional) in &tables.s2t {
+            assert_eq!(tables.t2s.get(traditional), Some(simplified));
+        }
+    }
+
+    #[test]
+    fn test_convert() {
+        assert_eq!(
+            to_simplified("我們這裡的銀行帳戶餘額"),
+            "我们这里的银行帐户余额"
+        );
+        assert_eq!(to_simplified("頭髮乾了"), "头发干了");
+        assert_eq!(to_traditional("我们这里的银行"), "我們這裡的銀行");
+        assert_eq!(to_traditional("发票"), "發票");
+        // 一简对多繁且无首选写法的字保持原样
+        assert_eq!(to_traditional("干净"), "干淨");
+        assert_eq!(OutputScript::AsIs.convert("這裡"), "這裡");
+        assert_eq!(OutputScript::Simplified.convert("Hello 世界"), "Hello 世界");
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut result = TranscriptionResult {
+            text: "您好這裡是銀行".to_string(),
+            language: Some("zh".to_string()),
+            language_detection: None,
+            segments: vec![TranscriptionSegment {
+                start_time: 0,
+                end_time: 2000,
+                text: "您好這裡是銀行".to_string(),
+                confidence: 0.9,
+                words: vec![TranscriptionWord {
+                    start_time: 500,
+                    end_time: 800,
+                    text: "這裡".to_string(),
+                    probability: 0.8,
+                }],
+            }],
+            processing_time: 0,
+            audio_duration: 2000,
+            decisions: Vec::new(),
+            skipped_duration: 0,
+        };
+
+        let mut japanese = result.clone();
+        japanese.language = Some("ja".to_string());
+        OutputScript::Simplified.apply(&mut japanese);
+        assert_eq!(japanese.text, "您好這裡是銀行");
+
+        OutputScript::Simplified.apply(&mut result);
+        assert_eq!(result.text, "您好这里是银行");
+        assert_eq!(result.segments[0].text, "您好这里是银行");
+        assert_eq!(result.segments[0].words[0].text, "这里");
+        assert_eq!(result.segments[0].words[0].start_time, 500);
+    }
+}
@@ -9,16 +9,21 @@
 use crate::{
     audio::{AudioConfig, AudioData},
     error::{SttError, SttResult},
+    long_form::strip_overlap,
     vad::SimpleVad,
-    whisper::{TranscriptionResult, WhisperConfig, WhisperTranscriber},
+    whisper::{TranscriptionResult, TranscriptionSegment, WhisperConfig, WhisperTranscriber},
 };
+use audio_utils::StreamingResampler;
 use std::{
     collections::VecDeque,
-    sync::{Arc, Mutex},
-    time::{Duration, Instant},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    time::{Duration, Instant, SystemTime},
 };
 use tokio::{
-    sync::{mpsc, mpsc::error::TryRecvError},
+    sync::{mpsc, Notify},
     time::sleep,
 };
 
@@ -41,6 +46,26 @@ pub struct StreamingConfig {
     pub silence_timeout: Duration,
     /// LocalAgreement 窗口大小 n（至少 2）
     pub local_agreement_n: usize,
+    /// 音频流开始时的墙上时间，设置后可把流时间换算为墙上时间
+    pub stream_start: Option<SystemTime>,
+    /// 音频输入队列容量（时长），超出时按 `overflow_policy` 处理
+    pub audio_queue_duration: Duration,
+    /// 事件队列容量（条），消费者处理不及时时转录任务会等待
+    pub event_queue_capacity: usize,
+    /// 音频输入队列已满时的处理策略
+    pub overflow_policy: OverflowPolicy,
+}
+
+/// 音频输入队列已满时的处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// 等待队列腾出空间：`push_audio` 阻塞当前线程，`push_audio_async` 异步等待
+    Block,
+    /// 丢弃最早的音频，并通过 [`StreamingEvent::AudioDropped`] 报告
+    #[default]
+    DropOldest,
+    /// 拒绝写入，`push_audio` 返回 `SttError::StreamError`
+    Error,
 }
 
 impl Default for StreamingConfig {
@@ -54,15 +79,68 @@ impl Default for StreamingConfig {
             vad_threshold: 0.005,
             silence_timeout: Duration::from_secs(2),
             local_agreement_n: 3,
+            stream_start: None,
+            audio_queue_duration: Duration::from_secs(10),
+            event_queue_capacity: 64,
+            overflow_policy: OverflowPolicy::default(),
         }
     }
 }
 
+/// 一条语句的当前转录假设
+///
+/// 同一语句的部分结果共享 `utterance_id`，`revision` 单调递增；界面可以按编号原地替换旧的部分结果。
+///
+/// 部分结果只携带音频缓冲区内的文本：已移出缓冲区的已确认文本不再重复发送
+/// （已随 `Transcription` 事件发出，字符数见 `committed_offset`），最终结果携带整条语句。
+#[derive(Debug, Clone)]
+pub struct StreamingHypothesis {
+    /// 语句编号（从 0 开始，每次语句结束后递增）
+    pub utterance_id: u64,
+    /// 同一语句内的修订号（从 0 开始递增）
+    pub revision: u64,
+    /// 本语句中位于 `committed` 之前、已移出缓冲区的已确认文本的字符数（最终结果为 0）
+    pub committed_offset: usize,
+    /// 已确认、不会再变化的文本
+    pub committed: String,
+    /// 尚未确认、后续转录中可能变化的文本
+    pub unconfirmed: String,
+    /// 检测到的语言
+    pub language: Option<String>,
+    /// 段落，时间为相对音频流开始的毫秒数：部分结果只含尚未确认的段落，最终结果含整条语句的段落
+    pub segments: Vec<TranscriptionSegment>,
+    /// 语句开始时的墙上时间（需设置 [`StreamingConfig::stream_start`]）
+    pub wall_clock: Option<SystemTime>,
+    /// 产生本结果的解码音频结尾相对音频流开始的时间（毫秒）
+    pub stream_end_ms: u64,
+}
+
+impl StreamingHypothesis {
+    /// 本结果携带的文本（已确认部分加未确认部分）
+    pub fn text(&self) -> String {
+        format!("{}{}", self.committed, self.unconfirmed)
+    }
+}
+
 /// 流式转录事件
 #[derive(Debug, Clone)]
 pub enum StreamingEvent {
-    /// 转录结果
+    /// 新确认的文本（只包含本次新增的部分）
+    ///
+    /// 段落时间相对音频流开始；`audio_duration` 与 `processing_time` 对应本次解码的音频窗口，
+    /// 可直接用于 `real_time_factor`。
     Transcription(TranscriptionResult),
+    /// 部分结果：已确认文本与当前未确认的尾部，每次转录后发送
+    Partial(StreamingHypothesis),
+    /// 最终结果：语句在语音结束时关闭，全部文本视为已确认
+    Final(StreamingHypothesis),
+    /// 音频输入队列溢出，最早的音频被丢弃（丢弃部分以静音计入流时间）
+    AudioDropped {
+        /// 本次丢弃的样本数
+        samples: u64,
+        /// 开始以来累计丢弃的样本数
+        total: u64,
+    },
     /// 语音开始
     SpeechStart,
     /// 语音结束
@@ -82,6 +160,10 @@ struct AudioBuffer {
     config: AudioConfig,
     /// 最大样本数量
     max_samples: usize,
+    /// 缓冲区第一个样本在整个音频流中的位置（样本数）
+    start_sample: u64,
+    /// 音频流开始以来写入的样本总数
+    total_samples: u64,
 }
 
 impl AudioBuffer {
@@ -91,16 +173,27 @@ impl AudioBuffer {
             samples: VecDeque::with_capacity(max_samples),
             config,
             max_samples,
+            start_sample: 0,
+            total_samples: 0,
         }
     }
 
+    /// 开始新的音频流：清空样本并重置流位置
+    fn reset_stream(&mut self) {
+        self.samples.clear();
+        self.start_sample = 0;
+        self.total_samples = 0;
+    }
+
     fn push_samples(&mut self, new_samples: &[f32]) {
         for &sample in new_samples {
             if self.samples.len() >= self.max_samples {
                 self.samples.pop_front();
+                self.start_sample += 1;
             }
             self.samples.push_back(sample);
         }
+        self.total_samples += new_samples.len() as u64;
     }
 
     #[allow(dead_code)]
@@ -126,8 +219,42 @@ impl AudioBuffer {
     }
 
     fn clear(&mut self) {
+        self.start_sample += self.samples.len() as u64;
         self.samples.clear();
     }
+
+    /// 以静音填补输入队列丢弃的样本，保持流位置与实际时间对齐
+    fn push_gap(&mut self, count: u64) {
+        let kept = count.min(self.max_samples as u64);
+        let skipped = count - kept;
+        if skipped > 0 {
+            self.start_sample += self.samples.len() as u64 + skipped;
+            self.total_samples += skipped;
+            self.samples.clear();
+        }
+        self.push_samples(&vec![0.0; kept as usize]);
+    }
+
+    /// 丢弃流位置 `sample` 之前的样本
+    fn trim_to(&mut self, sample: u64) {
+        let count = (sample.saturating_sub(self.start_sample) as usize).min(self.samples.len());
+        self.samples.drain(..count);
+        self.start_sample += count as u64;
+    }
+
+    /// 复制缓冲区中的全部样本及其在流中的位置
+    fn snapshot(&self) -> BufferSnapshot {
+        BufferSnapshot {
+            samples: self.get_all_samples(),
+            start_sample: self.start_sample,
+            offset_ms: self.start_ms(),
+        }
+    }
+
+    /// 缓冲区开头在整个音频流中的时间（毫秒）
+    fn start_ms(&self) -> u64 {
+        self.start_sample * 1000 / self.config.sample_rate.max(1) as u64
+    }
 }
 
 // SimpleVad 现在从 vad 模块导入
@@ -164,7 +291,9 @@ impl StreamingAggregator {
 
         let lcp = Self::longest_common_prefix(self.last_texts.iter().map(|s| s.as_str()));
         let lcp_trimmed = lcp.trim();
-        if lcp_trimmed.len() > self.confirmed_prefix.len() {
+        if lcp_trimmed.len() > self.confirmed_prefix.len()
+            && lcp_trimmed.starts_with(self.confirmed_prefix.as_str())
+        {
             let addition = &lcp_trimmed[self.confirmed_prefix.len()..];
             self.confirmed_prefix = lcp_trimmed.to_string();
             return if addition.is_empty() {
@@ -178,42 +307,711 @@ impl StreamingAggregator {
 
     fn longest_common_prefix<'a, I: Iterator<Item = &'a str>>(mut it: I) -> String {
         if let Some(first) = it.next() {
-            let mut prefix = first.as_bytes().to_vec();
+            let mut prefix = first;
             for s in it {
-                let bytes = s.as_bytes();
-                let mut i = 0;
-                while i < prefix.len() && i < bytes.len() && prefix[i] == bytes[i] {
-                    i += 1;
-                }
-                prefix.truncate(i);
+                // 按字符比较，避免在多字节字符（如中文）中间截断
+                let end = prefix
+                    .char_indices()
+                    .zip(s.chars())
+                    .find(|((_, a), b)| a != b)
+                    .map(|((i, _), _)| i)
+                    .unwrap_or_else(|| prefix.len().min(s.len()));
+                prefix = &prefix[..end];
                 if prefix.is_empty() {
                     break;
                 }
             }
-            return String::from_utf8(prefix).unwrap_or_default();
+            return prefix.to_string();
         }
         String::new()
     }
 }
 
+/// 某一时刻缓冲区内容的副本
+struct BufferSnapshot {
+    samples: Vec<f32>,
+    /// 第一个样本在流中的位置（样本数）
+    start_sample: u64,
+    /// 第一个样本在流中的时间（毫秒）
+    offset_ms: u64,
+}
+
+/// 输入音频格式：采样率与声道数，多声道样本交错排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputFormat {
+    /// 采样率 (Hz)
+    pub sample_rate: u32,
+    /// 声道数
+    pub channels: u16,
+}
+
+impl InputFormat {
+    /// 创建输入格式
+    pub fn new(sample_rate: u32, channels: u16) -> Self {
+        Self {
+            sample_rate,
+            channels,
+        }
+    }
+}
+
+impl From<&AudioConfig> for InputFormat {
+    fn from(config: &AudioConfig) -> Self {
+        Self::new(config.sample_rate, config.channels)
+    }
+}
+
+/// 一块交错排列的输入样本
+#[derive(Debug, Clone, Copy)]
+pub enum InputSamples<'a> {
+    /// 32 位浮点，范围 -1.0..=1.0
+    F32(&'a [f32]),
+    /// 16 位有符号整数
+    I16(&'a [i16]),
+    /// 8 位无符号整数，128 为零点
+    U8(&'a [u8]),
+    /// G.711 μ-law 编码
+    MuLaw(&'a [u8]),
+}
+
+impl InputSamples<'_> {
+    fn len(&self) -> usize {
+        match self {
+            InputSamples::F32(samples) => samples.len(),
+            InputSamples::I16(samples) => samples.len(),
+            InputSamples::U8(samples) | InputSamples::MuLaw(samples) => samples.len(),
+        }
+    }
+
+    /// 解码为 -1.0..=1.0 的浮点样本
+    fn to_f32(self) -> Vec<f32> {
+        match self {
+            InputSamples::F32(samples) => samples.to_vec(),
+            InputSamples::I16(samples) => samples.iter().map(|&x| x as f32 / 32768.0).collect(),
+            InputSamples::U8(samples) => samples
+                .iter()
+                .map(|&x| (x as f32 - 128.0) / 128.0)
+                .collect(),
+            InputSamples::MuLaw(samples) => samples.iter().map(|&x| mulaw_to_f32(x)).collect(),
+        }
+    }
+}
+
+/// G.711 μ-law 解码
+fn mulaw_to_f32(byte: u8) -> f32 {
+    const BIAS: i32 = 0x84;
+    let byte = !byte;
+    let exponent = (byte >> 4) & 0x07;
+    let mantissa = (byte & 0x0F) as i32;
+    let magnitude = (((mantissa << 3) + BIAS) << exponent) - BIAS;
+    let sample = if byte & 0x80 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    };
+    sample as f32 / 32768.0
+}
+
+/// 输入格式转换：下混为单声道并流式重采样到目标采样率
+struct InputConverter {
+    format: InputFormat,
+    target_rate: u32,
+    resampler: StreamingResampler,
+    /// 已输入的帧数
+    input_frames: u64,
+    /// 已输出的样本数
+    output_samples: u64,
+}
+
+impl InputConverter {
+    fn new(format: InputFormat, target_rate: u32) -> SttResult<Self> {
+        if format.channels == 0 {
+            return Err(SttError::config_error("输入声道数不能为 0"));
+        }
+        let resampler = StreamingResampler::new(format.sample_rate, target_rate)
+            .map_err(|e| SttError::ResamplingError(e.to_string()))?;
+        Ok(Self {
+            format,
+            target_rate,
+            resampler,
+            input_frames: 0,
+            output_samples: 0,
+        })
+    }
+
+    fn process(&mut self, samples: InputSamples<'_>) -> SttResult<Vec<f32>> {
+        let channels = self.format.channels as usize;
+        if samples.len() % channels != 0 {
+            return Err(SttError::AudioProcessingError(format!(
+                "样本数 {} 不是声道数 {} 的整数倍",
+                samples.len(),
+                channels
+            )));
+        }
+
+        let samples = samples.to_f32();
+        let mono: Vec<f32> = if channels == 1 {
+            samples
+        } else {
+            samples
+                .chunks_exact(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+                .collect()
+        };
+        self.input_frames += mono.len() as u64;
+
+        let output = self
+            .resampler
+            .process_chunk(&mono)
+            .map_err(|e| SttError::ResamplingError(e.to_string()))?;
+        self.output_samples += output.len() as u64;
+        Ok(output)
+    }
+
+    /// 输出重采样器中剩余的样本，并校正总长度使其与输入时长一致
+    fn flush(&mut self) -> SttResult<Vec<f32>> {
+        let mut output = self
+            .resampler
+            .finalize()
+            .map_err(|e| SttError::ResamplingError(e.to_string()))?;
+        let expected = (self.input_frames as u128 * self.target_rate as u128
+            / self.format.sample_rate as u128) as u64;
+        let remaining = expected.saturating_sub(self.output_samples) as usize;
+        output.resize(remaining, 0.0);
+        self.output_samples += output.len() as u64;
+        Ok(output)
+    }
+}
+
+/// 有界音频输入队列：按样本数限制容量，满时按溢出策略处理
+#[derive(Debug)]
+struct AudioQueue {
+    state: Mutex<QueueState>,
+    /// 容量（样本数）
+    capacity: usize,
+    policy: OverflowPolicy,
+    /// 有数据写入或队列关闭时唤醒消费者
+    data_ready: Notify,
+    /// 有空间释放时唤醒异步写入者
+    space_ready: Notify,
+    /// 有空间释放时唤醒同步写入者
+    space_available: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    chunks: VecDeque<Vec<f32>>,
+    /// 队列中的样本数
+    queued: usize,
+    /// 尚未报告的丢弃样本数
+    dropped: u64,
+    closed: bool,
+}
+
+impl AudioQueue {
+    fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            state: Mutex::new(QueueState::default()),
+            capacity: capacity.max(1),
+            policy,
+            data_ready: Notify::new(),
+            space_ready: Notify::new(),
+            space_available: Condvar::new(),
+        }
+    }
+
+    /// 在持有锁的状态下写入；`Block` 策略下空间不足时原样返回数据
+    fn push_locked(&self, state: &mut QueueState, chunk: Vec<f32>) -> SttResult<Option<Vec<f32>>> {
+        if state.closed {
+            return Err(SttError::other("转录器未运行"));
+        }
+        // 队列为空时总是接受，避免单块超过容量时永远无法写入
+        if state.queued + chunk.len() > self.capacity && !state.chunks.is_empty() {
+            match self.policy {
+                OverflowPolicy::Block => return Ok(Some(chunk)),
+                OverflowPolicy::Error => {
+                    return Err(SttError::StreamError(format!(
+                        "音频输入队列已满: 容量 {} 个样本",
+                        self.capacity
+                    )))
+                }
+                OverflowPolicy::DropOldest => {
+                    while state.queued + chunk.len() > self.capacity {
+                        let Some(oldest) = state.chunks.pop_front() else {
+                            break;
+                        };
+                        state.queued -= oldest.len();
+                        state.dropped += oldest.len() as u64;
+                    }
+                }
+            }
+        }
+        state.queued += chunk.len();
+        state.chunks.push_back(chunk);
+        Ok(None)
+    }
+
+    /// 同步写入，`Block` 策略下阻塞当前线程直到有空间
+    fn push_blocking(&self, mut chunk: Vec<f32>) -> SttResult<()> {
+        let mut state = self.state.lock().unwrap();
+        while let Some(pending) = self.push_locked(&mut state, chunk)? {
+            chunk = pending;
+            state = self.space_available.wait(state).unwrap();
+        }
+        drop(state);
+        self.data_ready.notify_one();
+        Ok(())
+    }
+
+    /// 异步写入，`Block` 策略下等待到有空间
+    async fn push(&self, mut chunk: Vec<f32>) -> SttResult<()> {
+        loop {
+            let space = self.space_ready.notified();
+            let pending = {
+                let mut state = self.state.lock().unwrap();
+                self.push_locked(&mut state, chunk)?
+            };
+            match pending {
+                Some(pending) => {
+                    chunk = pending;
+                    space.await;
+                }
+                None => break,
+            }
+        }
+        self.data_ready.notify_one();
+        Ok(())
+    }
+
+    /// 等待并取出队列中的全部样本与未报告的丢弃数；队列关闭且已取空时返回 `None`
+    async fn pop_all(&self) -> Option<(Vec<f32>, u64)> {
+        loop {
+            let ready = self.data_ready.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                if !state.chunks.is_empty() || state.dropped > 0 {
+                    let samples: Vec<f32> = state.chunks.drain(..).flatten().collect();
+                    state.queued = 0;
+                    let dropped = std::mem::take(&mut state.dropped);
+                    drop(state);
+                    self.space_available.notify_all();
+                    self.space_ready.notify_waiters();
+                    return Some((samples, dropped));
+                }
+                if state.closed {
+                    return None;
+                }
+            }
+            ready.await;
+        }
+    }
+
+    /// 关闭队列，唤醒所有等待者
+    fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.data_ready.notify_one();
+        self.space_available.notify_all();
+        self.space_ready.notify_waiters();
+    }
+}
+
+/// 作为下一次解码提示的已确认文本的最大字符数
+const PROMPT_CONTEXT_CHARS: usize = 200;
+
+/// 一次转录中可以从缓冲区裁掉的前部
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TrimPoint {
+    /// 裁剪位置相对本次转录音频开头的时间（毫秒）
+    end_ms: u64,
+    /// 裁剪位置在本次转录文本中的字节位置
+    text_end: usize,
+}
+
+/// 语句跟踪：为转录结果分配语句编号与修订号，生成部分结果与最终结果
+///
+/// 聚合器只处理缓冲区内音频对应的文本（窗口文本）；缓冲区前部被裁掉后，
+/// 对应的已确认文本移入 `archived`，窗口文本随之缩短。部分结果只携带窗口文本与未确认段落，
+/// 大小不随语句变长而增长；整条语句只在结束时随 `Final` 发送一次。
+#[derive(Debug)]
+struct UtteranceTracker {
+    aggregator: StreamingAggregator,
+    local_agreement_n: usize,
+    utterance_id: u64,
+    revision: u64,
+    /// 当前语句中已移出缓冲区的已确认文本
+    archived: String,
+    /// `archived` 的字符数
+    archived_chars: usize,
+    /// 当前语句第一个已确认段落的开始时间（流时间，毫秒）
+    utterance_start_ms: Option<u64>,
+    /// 上一条语句的最终文本，用作新语句的解码提示
+    previous: String,
+    /// 当前窗口最近一次转录的文本
+    latest: Option<String>,
+    /// 最近一次转录的段落（流时间）及其在窗口文本中的结束位置
+    latest_segments: Vec<(usize, TranscriptionSegment)>,
+    /// 窗口文本中已随确认文本发送过段落的位置
+    emitted_len: usize,
+    /// 当前语句已确认的段落（流时间）
+    committed_segments: Vec<TranscriptionSegment>,
+    /// 最近一次转录的音频窗口时长（毫秒）
+    window_ms: u64,
+    /// 最近一次转录的音频结尾在整个音频流中的时间（毫秒）
+    stream_end_ms: u64,
+    /// 音频流开始时的墙上时间
+    stream_start: Option<SystemTime>,
+    /// 下一次转录的音频预期开始于流中的时间（毫秒），更晚说明缓冲区溢出丢弃了前部音频
+    window_start_ms: Option<u64>,
+    /// 溢出后窗口开头可能仍包含已归档的文本，需要去除重复
+    dedupe_archived: bool,
+    language: Option<String>,
+}
+
+impl UtteranceTracker {
+    fn new(local_agreement_n: usize, stream_start: Option<SystemTime>) -> Self {
+        Self {
+            aggregator: StreamingAggregator::new(local_agreement_n),
+            local_agreement_n,
+            utterance_id: 0,
+            revision: 0,
+            archived: String::new(),
+            archived_chars: 0,
+            utterance_start_ms: None,
+            previous: String::new(),
+            latest: None,
+            latest_segments: Vec::new(),
+            emitted_len: 0,
+            committed_segments: Vec::new(),
+            window_ms: 0,
+            stream_end_ms: 0,
+            stream_start,
+            window_start_ms: None,
+            dedupe_archived: false,
+            language: None,
+        }
+    }
+
+    /// 处理一次转录结果，返回需要发送的事件与可裁剪的缓冲区位置
+    ///
+    /// `offset_ms` 是本次转录音频开头在整个音频流中的时间，用于把段落时间换算为流时间。
+    fn on_result(
+        &mut self,
+        result: &TranscriptionResult,
+        offset_ms: u64,
+    ) -> (Vec<StreamingEvent>, Option<TrimPoint>) {
+        // 缓冲区溢出从前部丢弃了音频：新窗口不再以已确认文本开头，需重新开始一致性确认
+        if self.window_start_ms.is_some_and(|start| offset_ms > start) {
+            self.archive_confirmed();
+        }
+        self.window_start_ms = Some(offset_ms);
+
+        let raw = result.text.trim();
+        // 溢出后窗口开头可能与已归档文本重复，去掉重复部分
+        let (text, removed) = match self
+            .dedupe_archived
+            .then(|| strip_overlap(&self.archived, raw))
+            .flatten()
+        {
+            Some((rest, _)) => {
+                let removed = raw.len() - rest.len();
+                (rest, removed)
+            }
+            None => (raw.to_string(), 0),
+        };
+        let text = text.as_str();
+        if text.is_empty() {
+            return (Vec::new(), None);
+        }
+        self.latest = Some(text.to_string());
+        self.language = result.language.clone();
+        self.window_ms = result.audio_duration;
+        self.stream_end_ms = offset_ms + result.audio_duration;
+        // 段落在窗口文本中的结束位置（相对本次转录的时间），整段落在重复部分内的段落丢弃
+        let located: Vec<(usize, &TranscriptionSegment)> = segment_ends(raw, &result.segments)
+            .map(|ends| {
+                ends.into_iter()
+                    .zip(&result.segments)
+                    .filter(|(end, _)| *end > removed)
+                    .map(|(end, segment)| (end - removed, segment))
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.latest_segments = located
+            .iter()
+            .map(|&(end, segment)| {
+                let mut segment = segment.clone();
+                segment.offset_by(offset_ms);
+                (end, segment)
+            })
+            .collect();
+
+        let mut events = Vec::new();
+        let addition = if self.local_agreement_n <= 1 {
+            // 不做一致性确认，每次结果直接视为已确认
+            self.aggregator.confirmed_prefix = text.to_string();
+            Some(text.to_string())
+        } else {
+            self.aggregator.push_and_confirm(text)
+        };
+        if let Some(addition) = addition.filter(|a| !a.trim().is_empty()) {
+            let segments = self.take_confirmed_segments(self.aggregator.confirmed_prefix.len());
+            events.push(StreamingEvent::Transcription(TranscriptionResult {
+                text: addition.trim().to_string(),
+                language: result.language.clone(),
+                language_detection: result.language_detection.clone(),
+                segments,
+                processing_time: result.processing_time,
+                audio_duration: result.audio_duration,
+                decisions: Vec::new(),
+                skipped_duration: result.skipped_duration,
+            }));
+        }
+
+        events.push(StreamingEvent::Partial(self.hypothesis(text)));
+        self.revision += 1;
+
+        // 裁剪到完整确认的最后一段结尾；窗口只有一段时，该段完整确认后也可裁剪
+        let confirmed_len = self.aggregator.confirmed_prefix.len();
+        let trim = located
+            .iter()
+            .rev()
+            .find(|(end, _)| *end > 0 && *end <= confirmed_len)
+            .map(|(end, segment)| TrimPoint {
+                end_ms: segment.end_time,
+                text_end: *end,
+            });
+        (events, trim)
+    }
+
+    /// 缓冲区溢出丢弃了前部音频：已确认文本移入已归档文本，重新开始一致性确认
+    ///
+    /// 未确认的尾部仍在缓冲区中，之后的转录会重新给出；窗口开头与归档文本重复的部分会被去掉。
+    fn archive_confirmed(&mut self) {
+        let confirmed = std::mem::take(&mut self.aggregator.confirmed_prefix);
+        if !confirmed.trim().is_empty() {
+            let archived_len = self.archived.len();
+            self.archived = join_text(&self.archived, confirmed.trim());
+            self.archived_chars += self.archived[archived_len..].chars().count();
+            self.dedupe_archived = true;
+        }
+        self.aggregator = StreamingAggregator::new(self.local_agreement_n);
+        self.latest = None;
+        self.latest_segments.clear();
+        self.emitted_len = 0;
+    }
+
+    /// 缓冲区已按 `trim` 裁掉前部音频，将窗口文本的对应部分移入已归档文本
+    fn trim(&mut self, trim: &TrimPoint) {
+        if let Some(start) = self.window_start_ms.as_mut() {
+            *start += trim.end_ms;
+        }
+        // 裁剪位置在已确认段落的结尾，之后的窗口不再包含已归档的文本
+        self.dedupe_archived = false;
+        let text_end = trim.text_end;
+        let Some(latest) = self.latest.clone() else {
+            return;
+        };
+        let Some(consumed) = latest.get(..text_end) else {
+            return;
+        };
+        let archived_len = self.archived.len();
+        self.archived = join_text(&self.archived, consumed.trim());
+        self.archived_chars += self.archived[archived_len..].chars().count();
+
+        let strip = |text: &str| text.strip_prefix(consumed).map(|t| t.trim().to_string());
+        let confirmed = &self.aggregator.confirmed_prefix;
+        self.aggregator.confirmed_prefix = strip(confirmed).unwrap_or_default();
+        self.aggregator.last_texts = self
+            .aggregator
+            .last_texts
+            .iter()
+            .filter_map(|text| strip(text))
+            .collect();
+        self.latest = strip(&latest);
+
+        // 剩余文本去掉了开头的空白，段落位置需按实际缩短的长度平移
+        let removed = latest.len() - self.latest.as_deref().map_or(0, str::len);
+        self.latest_segments = std::mem::take(&mut self.latest_segments)
+            .into_iter()
+            .filter(|(end, _)| *end > removed)
+            .map(|(end, segment)| (end - removed, segment))
+            .collect();
+        self.emitted_len = self.emitted_len.saturating_sub(removed);
+    }
+
+    /// 下一次解码使用的提示文本：缓冲区之外的已确认文本
+    fn context(&self) -> Option<String> {
+        let context = if self.archived.is_empty() {
+            &self.previous
+        } else {
+            &self.archived
+        };
+        let skip = context.chars().count().saturating_sub(PROMPT_CONTEXT_CHARS);
+        let context: String = context.chars().skip(skip).collect();
+        (!context.is_empty()).then_some(context)
+    }
+
+    /// 语句结束：未确认的尾部一并确认，返回需要发送的事件并开始新语句
+    fn finish(&mut self) -> Vec<StreamingEvent> {
+        let latest = self.latest.take().unwrap_or_default();
+        if latest.is_empty() && self.archived.is_empty() {
+            self.reset();
+            return Vec::new();
+        }
+
+        let tail = self.hypothesis(&latest).unconfirmed.trim().to_string();
+        let tail_segments = self.take_confirmed_segments(latest.len());
+        let text = join_text(&self.archived, &latest);
+        let segments = std::mem::take(&mut self.committed_segments);
+        let wall_clock = self.wall_clock(&segments);
+        self.reset();
+
+        let mut events = Vec::new();
+        if !tail.is_empty() {
+            events.push(StreamingEvent::Transcription(TranscriptionResult {
+                text: tail,
+                language: self.language.clone(),
+                language_detection: None,
+                segments: tail_segments,
+                processing_time: 0,
+                audio_duration: self.window_ms,
+                decisions: Vec::new(),
+                skipped_duration: 0,
+            }));
+        }
+        events.push(StreamingEvent::Final(StreamingHypothesis {
+            utterance_id: self.utterance_id,
+            revision: self.revision,
+            committed_offset: 0,
+            committed: text.clone(),
+            unconfirmed: String::new(),
+            language: self.language.clone(),
+            wall_clock,
+            segments,
+            stream_end_ms: self.stream_end_ms,
+        }));
+
+        self.previous = text;
+        self.utterance_id += 1;
+        self.revision = 0;
+        events
+    }
+
+    fn reset(&mut self) {
+        self.aggregator = StreamingAggregator::new(self.local_agreement_n);
+        self.committed_segments.clear();
+        self.archived.clear();
+        self.archived_chars = 0;
+        self.utterance_start_ms = None;
+        self.latest = None;
+        self.latest_segments.clear();
+        self.emitted_len = 0;
+        self.window_start_ms = None;
+        self.dedupe_archived = false;
+    }
+
+    /// 取出窗口文本中结束位置不超过 `confirmed_len` 且尚未发送的段落
+    fn take_confirmed_segments(&mut self, confirmed_len: usize) -> Vec<TranscriptionSegment> {
+        let segments: Vec<TranscriptionSegment> = self
+            .latest_segments
+            .iter()
+            .filter(|(end, _)| *end > self.emitted_len && *end <= confirmed_len)
+            .map(|(_, segment)| segment.clone())
+            .collect();
+        if self.utterance_start_ms.is_none() {
+            self.utterance_start_ms = segments.first().map(|segment| segment.start_time);
+        }
+        self.committed_segments.extend(segments.iter().cloned());
+        if let Some((end, _)) = self
+            .latest_segments
+            .iter()
+            .rev()
+            .find(|(end, _)| *end <= confirmed_len)
+        {
+            self.emitted_len = self.emitted_len.max(*end);
+        }
+        segments
+    }
+
+    /// 以窗口最新文本构造当前假设：已确认前缀之后的部分为未确认尾部
+    fn hypothesis(&self, latest: &str) -> StreamingHypothesis {
+        let confirmed = self.aggregator.confirmed_prefix.as_str();
+        let committed = if latest.starts_with(confirmed) {
+            confirmed
+        } else {
+            ""
+        };
+        // 已确认的段落已随 Transcription 事件发送，这里只携带尚未确认的段落
+        let segments: Vec<TranscriptionSegment> = self
+            .latest_segments
+            .iter()
+            .filter(|(end, _)| *end > self.emitted_len)
+            .map(|(_, segment)| segment.clone())
+            .collect();
+        StreamingHypothesis {
+            utterance_id: self.utterance_id,
+            revision: self.revision,
+            committed_offset: self.archived_chars,
+            unconfirmed: latest[committed.len()..].to_string(),
+            committed: committed.to_string(),
+            language: self.language.clone(),
+            wall_clock: self.wall_clock(&segments),
+            segments,
+            stream_end_ms: self.stream_end_ms,
+        }
+    }
+
+    /// 语句第一个段落开始时的墙上时间：优先使用第一个已确认段落，尚无确认段落时使用 `segments`
+    fn wall_clock(&self, segments: &[TranscriptionSegment]) -> Option<SystemTime> {
+        let start = self.stream_start?;
+        let first = self
+            .utterance_start_ms
+            .or_else(|| segments.first().map(|segment| segment.start_time))?;
+        Some(start + Duration::from_millis(first))
+    }
+}
+
+/// 拼接两段文本，中日韩字符之间不加空格
+fn join_text(head: &str, tail: &str) -> String {
+    crate::postprocess::join_segments([head, tail].into_iter())
+}
+
+/// 计算每个段落在转录文本中的结束字节位置；段落文本无法按顺序定位时返回 `None`
+fn segment_ends(text: &str, segments: &[TranscriptionSegment]) -> Option<Vec<usize>> {
+    let mut cursor = 0;
+    segments
+        .iter()
+        .map(|segment| {
+            let segment_text = segment.text.trim();
+            let start = cursor + text[cursor..].find(segment_text)?;
+            cursor = start + segment_text.len();
+            Some(cursor)
+        })
+        .collect()
+}
+
 /// 实时语音转录器
 pub struct StreamingTranscriber {
     /// Whisper转录器实例
-    transcriber: Arc<WhisperTranscriber>,
+    transcriber: WhisperTranscriber,
     /// 流式转录配置
     config: StreamingConfig,
-    /// 音频配置
+    /// 内部音频配置（16kHz 单声道）
     audio_config: AudioConfig,
+    /// 默认输入格式
+    input_format: InputFormat,
+    /// 输入格式转换器
+    converter: Mutex<Option<InputConverter>>,
     /// 音频缓冲区（线程安全）
     buffer: Arc<Mutex<AudioBuffer>>,
     /// 语音活动检测器（可选）
     vad: Option<SimpleVad>,
     /// 事件发送器（用于输出转录结果和状态）
-    event_sender: Option<mpsc::UnboundedSender<StreamingEvent>>,
+    event_sender: Option<mpsc::Sender<StreamingEvent>>,
     /// 运行状态标志（线程安全）
     is_running: Arc<Mutex<bool>>,
-    /// 音频数据发送器
-    audio_sender: Option<mpsc::UnboundedSender<Vec<f32>>>,
+    /// 音频输入队列
+    audio_queue: Option<Arc<AudioQueue>>,
     /// 音频处理任务句柄
     audio_task_handle: Option<tokio::task::JoinHandle<()>>,
     /// 转录处理任务句柄
@@ -222,12 +1020,17 @@ pub struct StreamingTranscriber {
 
 impl StreamingTranscriber {
     /// 创建新的流式转录器
+    ///
+    /// `audio_config` 声明 `push_audio` 输入样本的采样率与声道数；输入会在写入时
+    /// 下混为单声道并重采样到 16kHz，缓冲区、VAD 与转录都在 16kHz 单声道上进行。
     pub fn new(
         whisper_config: WhisperConfig,
         streaming_config: StreamingConfig,
         audio_config: AudioConfig,
     ) -> SttResult<Self> {
-        let transcriber = Arc::new(WhisperTranscriber::new(whisper_config)?);
+        let transcriber = WhisperTranscriber::new(whisper_config)?;
+        let input_format = InputFormat::from(&audio_config);
+        let audio_config = AudioConfig::whisper_optimized();
         let buffer = Arc::new(Mutex::new(AudioBuffer::new(
             audio_config.clone(),
             streaming_config.buffer_duration,
@@ -246,11 +1049,13 @@ impl StreamingTranscriber {
             transcriber,
             config: streaming_config,
             audio_config,
+            input_format,
+            converter: Mutex::new(None),
             buffer,
             vad,
             event_sender: None,
             is_running: Arc::new(Mutex::new(false)),
-            audio_sender: None,
+            audio_queue: None,
             audio_task_handle: None,
             transcription_task_handle: None,
         })
@@ -283,54 +1088,58 @@ impl StreamingTranscriber {
     }
 
     /// 开始流式转录
-    pub async fn start_streaming(&mut self) -> SttResult<mpsc::UnboundedReceiver<StreamingEvent>> {
-        let (tx, rx) = mpsc::unbounded_channel();
+    ///
+    /// 返回的事件接收端容量为 `event_queue_capacity`，消费者处理不及时时转录任务会等待。
+    pub async fn start_streaming(&mut self) -> SttResult<mpsc::Receiver<StreamingEvent>> {
+        let (tx, rx) = mpsc::channel(self.config.event_queue_capacity.max(1));
         self.event_sender = Some(tx.clone());
-        let (audio_tx, mut audio_rx) = mpsc::unbounded_channel::<Vec<f32>>();
-        self.audio_sender = Some(audio_tx);
+        let capacity = (self.audio_config.sample_rate as f64
+            * self.audio_config.channels.max(1) as f64
+            * self.config.audio_queue_duration.as_secs_f64()) as usize;
+        let audio_queue = Arc::new(AudioQueue::new(capacity, self.config.overflow_policy));
+        self.audio_queue = Some(Arc::clone(&audio_queue));
+        // 缓冲区有新数据时唤醒转录任务
+        let data_arrived = Arc::new(Notify::new());
+        // 输入队列已关闭且全部写入缓冲区
+        let input_closed = Arc::new(AtomicBool::new(false));
 
         *self.is_running.lock().unwrap() = true;
+        self.buffer.lock().unwrap().reset_stream();
+        *self.converter.lock().unwrap() = None;
 
-        // 启动音频处理任务（异步解耦）
+        // 启动音频处理任务：队列有数据时被唤醒，批量写入缓冲区
         let buffer = Arc::clone(&self.buffer);
-        let is_running_audio = Arc::clone(&self.is_running);
+        let audio_events = tx.clone();
+        let audio_arrived = Arc::clone(&data_arrived);
+        let audio_closed = Arc::clone(&input_closed);
         self.audio_task_handle = Some(tokio::spawn(async move {
-            while *is_running_audio.lock().unwrap() {
-                // 批量处理音频数据，减少锁竞争
-                let mut batch = Vec::new();
-                let mut batch_size = 0;
-                const MAX_BATCH_SIZE: usize = 8192; // 约0.17秒@48kHz
-
-                // 收集一批音频数据
-                loop {
-                    match audio_rx.try_recv() {
-                        Ok(chunk) => {
-                            batch_size += chunk.len();
-                            batch.extend_from_slice(&chunk);
-                            if batch_size >= MAX_BATCH_SIZE {
-                                break;
-                            }
-                        }
-                        Err(TryRecvError::Empty) => break,
-                        Err(TryRecvError::Disconnected) => return,
-                    }
+            let mut total_dropped = 0u64;
+            while let Some((samples, dropped)) = audio_queue.pop_all().await {
+                if dropped > 0 {
+                    total_dropped += dropped;
+                    log::warn!("音频输入队列溢出，丢弃 {dropped} 个样本");
+                    buffer.lock().unwrap().push_gap(dropped);
+                    let _ = audio_events
+                        .send(StreamingEvent::AudioDropped {
+                            samples: dropped,
+                            total: total_dropped,
+                        })
+                        .await;
                 }
-
-                // 批量写入缓冲区
-                if !batch.is_empty() {
-                    buffer.lock().unwrap().push_samples(&batch);
+                if !samples.is_empty() {
+                    buffer.lock().unwrap().push_samples(&samples);
                 }
-
-                // 短暂休眠避免CPU占用过高
-                sleep(Duration::from_millis(10)).await;
+                audio_arrived.notify_one();
             }
+            // 队列关闭：唤醒转录任务处理剩余音频后退出
+            audio_closed.store(true, Ordering::Release);
+            audio_arrived.notify_one();
         }));
 
         // 启动转录任务
-        let transcriber = Arc::clone(&self.transcriber);
+        let transcriber = self.transcriber.clone();
         let buffer_clone = Arc::clone(&self.buffer);
         let config = self.config.clone();
-        let audio_config = self.audio_config.clone();
         let is_running = Arc::clone(&self.is_running);
         let vad = self.vad.clone();
 
@@ -338,100 +1147,89 @@ impl StreamingTranscriber {
             let mut last_transcription = Instant::now();
             let mut speech_detected = false;
             let mut last_speech_time = Instant::now();
-            let mut aggregator = StreamingAggregator::new(config.local_agreement_n);
+            let mut tracker = UtteranceTracker::new(config.local_agreement_n, config.stream_start);
+
+            loop {
+                // 等待新数据到达，并保证两次转录之间至少间隔 transcription_interval
+                data_arrived.notified().await;
+                let closed = input_closed.load(Ordering::Acquire);
+                let since_last = last_transcription.elapsed();
+                if !closed && since_last < config.transcription_interval {
+                    sleep(config.transcription_interval - since_last).await;
+                }
+                if !*is_running.lock().unwrap() {
+                    break;
+                }
 
-            while *is_running.lock().unwrap() {
-                sleep(Duration::from_millis(50)).await; // 更频繁的检查
+                if closed {
+                    // 输入已结束：转录剩余音频，关闭当前语句后退出
+                    let snapshot = buffer_clone.lock().unwrap().snapshot();
+                    let has_speech = !snapshot.samples.is_empty()
+                        && (speech_detected
+                            || vad
+                                .as_ref()
+                                .map_or(true, |vad| vad.detect_speech(&snapshot.samples)));
+                    if has_speech {
+                        decode_window(&transcriber, &buffer_clone, &mut tracker, &tx, snapshot)
+                            .await;
+                    }
+                    for event in tracker.finish() {
+                        let _ = tx.send(event).await;
+                    }
+                    if speech_detected {
+                        let _ = tx.send(StreamingEvent::SpeechEnd).await;
+                    }
+                    break;
+                }
 
                 let now = Instant::now();
-                let should_transcribe =
-                    now.duration_since(last_transcription) >= config.transcription_interval;
-
-                if should_transcribe {
-                    let samples = {
-                        let buffer_guard = buffer_clone.lock().unwrap();
-                        if buffer_guard.duration() < config.min_audio_length {
-                            continue;
-                        }
-                        buffer_guard.get_all_samples()
-                    };
-
-                    if samples.is_empty() {
+                let snapshot = {
+                    let buffer_guard = buffer_clone.lock().unwrap();
+                    if buffer_guard.duration() < config.min_audio_length {
                         continue;
                     }
+                    buffer_guard.snapshot()
+                };
 
-                    // VAD检测
-                    if let Some(ref vad_detector) = vad {
-                        let has_speech = vad_detector.detect_speech(&samples);
+                if snapshot.samples.is_empty() {
+                    continue;
+                }
 
-                        if has_speech {
-                            if !speech_detected {
-                                let _ = tx.send(StreamingEvent::SpeechStart);
-                                speech_detected = true;
-                            }
-                            last_speech_time = now;
-                        } else if speech_detected {
-                            // 检查静音超时
-                            if now.duration_since(last_speech_time) >= config.silence_timeout {
-                                let _ = tx.send(StreamingEvent::SpeechEnd);
-                                let _ = tx.send(StreamingEvent::Silence);
-                                speech_detected = false;
-
-                                // 清空缓冲区
-                                buffer_clone.lock().unwrap().clear();
-                                continue;
-                            }
+                // VAD检测
+                if let Some(ref vad_detector) = vad {
+                    let has_speech = vad_detector.detect_speech(&snapshot.samples);
+
+                    if has_speech {
+                        if !speech_detected {
+                            let _ = tx.send(StreamingEvent::SpeechStart).await;
+                            speech_detected = true;
                         }
+                        last_speech_time = now;
+                    } else if speech_detected {
+                        // 检查静音超时
+                        if now.duration_since(last_speech_time) >= config.silence_timeout {
+                            for event in tracker.finish() {
+                                let _ = tx.send(event).await;
+                            }
+                            let _ = tx.send(StreamingEvent::SpeechEnd).await;
+                            let _ = tx.send(StreamingEvent::Silence).await;
+                            speech_detected = false;
 
-                        // 如果没有检测到语音，跳过转录
-                        if !has_speech && !speech_detected {
+                            // 清空缓冲区
+                            buffer_clone.lock().unwrap().clear();
                             continue;
                         }
                     }
 
-                    // 执行转录
-                    let audio_data = AudioData::new(samples, audio_config.clone());
-                    match transcriber.transcribe_audio_data(&audio_data).await {
-                        Ok(result) => {
-                            let text = result.text.trim().to_string();
-                            if text.is_empty() {
-                                // 跳过空结果
-                            } else {
-                                // 尝试确认文本
-                                if let Some(confirmed_add) = aggregator.push_and_confirm(&text) {
-                                    if !confirmed_add.trim().is_empty() {
-                                        let confirmed = TranscriptionResult {
-                                            text: confirmed_add,
-                                            language: result.language.clone(),
-                                            segments: Vec::new(),
-                                            processing_time: result.processing_time,
-                                            audio_duration: result.audio_duration,
-                                        };
-                                        let _ = tx.send(StreamingEvent::Transcription(confirmed));
-                                    }
-                                } else {
-                                    // 对于单次转录，直接发送结果
-                                    if config.local_agreement_n <= 1 {
-                                        let direct_result = TranscriptionResult {
-                                            text: text.clone(),
-                                            language: result.language.clone(),
-                                            segments: Vec::new(),
-                                            processing_time: result.processing_time,
-                                            audio_duration: result.audio_duration,
-                                        };
-                                        let _ =
-                                            tx.send(StreamingEvent::Transcription(direct_result));
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            let _ = tx.send(StreamingEvent::Error(e.to_string()));
-                        }
+                    // 如果没有检测到语音，跳过转录
+                    if !has_speech && !speech_detected {
+                        continue;
                     }
-
-                    last_transcription = now;
                 }
+
+                // 执行转录
+                decode_window(&transcriber, &buffer_clone, &mut tracker, &tx, snapshot).await;
+                last_transcription = now;
             }
         }));
 
@@ -442,7 +1240,9 @@ impl StreamingTranscriber {
     pub fn stop_streaming(&mut self) {
         *self.is_running.lock().unwrap() = false;
         self.event_sender = None;
-        self.audio_sender = None;
+        if let Some(queue) = self.audio_queue.take() {
+            queue.close();
+        }
 
         // 取消任务（非阻塞）
         if let Some(handle) = self.audio_task_handle.take() {
@@ -454,10 +1254,22 @@ impl StreamingTranscriber {
     }
 
     /// 异步停止流式转录并等待任务完成
+    ///
+    /// 重采样器中尚未输出的样本与队列中的音频会先写入缓冲区并做最后一次转录，
+    /// 当前语句以 `Final` 事件关闭后任务才退出。事件接收端需要继续读取直到该方法返回。
     pub async fn stop_streaming_async(&mut self) {
-        *self.is_running.lock().unwrap() = false;
+        let remaining = self.flush_converter();
+        if let (Some(queue), Ok(remaining)) = (self.audio_queue.as_deref(), remaining) {
+            if !remaining.is_empty() {
+                let _ = queue.push(remaining).await;
+            }
+        }
+
+        // 关闭输入队列：音频任务写完剩余数据后退出，转录任务随后做最后一次转录
         self.event_sender = None;
-        self.audio_sender = None;
+        if let Some(queue) = self.audio_queue.take() {
+            queue.close();
+        }
 
         // 等待任务完成
         if let Some(handle) = self.audio_task_handle.take() {
@@ -466,22 +1278,105 @@ impl StreamingTranscriber {
         if let Some(handle) = self.transcription_task_handle.take() {
             let _ = handle.await;
         }
+        *self.is_running.lock().unwrap() = false;
+    }
+
+    /// 设置默认输入格式（`push_audio` 等未指定格式的输入使用该格式）
+    pub fn set_input_format(&mut self, format: InputFormat) {
+        self.input_format = format;
     }
 
-    /// 添加音频数据
+    /// 获取默认输入格式
+    pub fn input_format(&self) -> InputFormat {
+        self.input_format
+    }
+
+    /// 添加音频数据（默认输入格式）
+    ///
+    /// 输入队列已满时按 `overflow_policy` 处理；`Block` 策略会阻塞当前线程，
+    /// 在异步上下文中请使用 [`push_audio_async`](Self::push_audio_async)。
     pub fn push_audio(&self, samples: &[f32]) -> SttResult<()> {
-        if let Some(tx) = &self.audio_sender {
-            let _ = tx.send(samples.to_vec());
-            Ok(())
-        } else {
-            Err(SttError::other("转录器未运行"))
-        }
+        self.push_input(InputSamples::F32(samples), None)
     }
 
-    /// 添加音频数据（i16格式）
+    /// 添加音频数据（i16格式，默认输入格式）
     pub fn push_audio_i16(&self, samples: &[i16]) -> SttResult<()> {
-        let f32_samples: Vec<f32> = samples.iter().map(|&x| x as f32 / 32768.0).collect();
-        self.push_audio(&f32_samples)
+        self.push_input(InputSamples::I16(samples), None)
+    }
+
+    /// 添加音频数据（异步，默认输入格式），`Block` 策略下队列已满时异步等待
+    pub async fn push_audio_async(&self, samples: &[f32]) -> SttResult<()> {
+        self.push_input_async(InputSamples::F32(samples), None)
+            .await
+    }
+
+    /// 添加任意格式的音频数据
+    ///
+    /// `format` 为 `None` 时使用默认输入格式；输入会被下混为单声道并重采样到 16kHz。
+    /// 格式变化时先输出上一格式在重采样器中剩余的样本。
+    pub fn push_input(
+        &self,
+        samples: InputSamples<'_>,
+        format: Option<InputFormat>,
+    ) -> SttResult<()> {
+        let queue = self.running_queue()?;
+        let converted = self.convert_input(samples, format)?;
+        if converted.is_empty() {
+            return Ok(());
+        }
+        queue.push_blocking(converted)
+    }
+
+    /// 添加任意格式的音频数据（异步），`Block` 策略下队列已满时异步等待
+    pub async fn push_input_async(
+        &self,
+        samples: InputSamples<'_>,
+        format: Option<InputFormat>,
+    ) -> SttResult<()> {
+        let queue = self.running_queue()?;
+        let converted = self.convert_input(samples, format)?;
+        if converted.is_empty() {
+            return Ok(());
+        }
+        queue.push(converted).await
+    }
+
+    fn convert_input(
+        &self,
+        samples: InputSamples<'_>,
+        format: Option<InputFormat>,
+    ) -> SttResult<Vec<f32>> {
+        let format = format.unwrap_or(self.input_format);
+        let mut converter = self.converter.lock().unwrap();
+
+        let mut output = Vec::new();
+        match converter.as_mut() {
+            Some(current) if current.format == format => {}
+            _ => {
+                if let Some(previous) = converter.as_mut() {
+                    output = previous.flush()?;
+                }
+                *converter = Some(InputConverter::new(format, self.audio_config.sample_rate)?);
+            }
+        }
+        if let Some(current) = converter.as_mut() {
+            output.extend(current.process(samples)?);
+        }
+        Ok(output)
+    }
+
+    /// 输出转换器中剩余的样本
+    fn flush_converter(&self) -> SttResult<Vec<f32>> {
+        match self.converter.lock().unwrap().as_mut() {
+            Some(converter) => converter.flush(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn running_queue(&self) -> SttResult<&AudioQueue> {
+        self.audio_queue
+            .as_deref()
+            .ok_or_else(|| SttError::other("转录器未运行"))
     }
 
     /// 获取当前缓冲区状态
@@ -490,6 +1385,21 @@ impl StreamingTranscriber {
         (buffer_guard.duration(), buffer_guard.samples.len())
     }
 
+    /// 自 `start_streaming` 以来写入的音频总时长
+    pub fn stream_position(&self) -> Duration {
+        let buffer_guard = self.buffer.lock().unwrap();
+        Duration::from_secs_f64(
+            buffer_guard.total_samples as f64 / buffer_guard.config.sample_rate.max(1) as f64,
+        )
+    }
+
+    /// 将相对音频流开始的时间（毫秒）换算为墙上时间；未设置 `stream_start` 时返回 `None`
+    pub fn wall_clock_time(&self, stream_ms: u64) -> Option<SystemTime> {
+        self.config
+            .stream_start
+            .map(|start| start + Duration::from_millis(stream_ms))
+    }
+
     /// 检查是否正在运行
     pub fn is_running(&self) -> bool {
         *self.is_running.lock().unwrap()
@@ -501,11 +1411,47 @@ impl StreamingTranscriber {
     }
 }
 
+/// 转录缓冲区快照，发送事件并按确认结果裁剪缓冲区
+async fn decode_window(
+    transcriber: &WhisperTranscriber,
+    buffer: &Mutex<AudioBuffer>,
+    tracker: &mut UtteranceTracker,
+    tx: &mpsc::Sender<StreamingEvent>,
+    snapshot: BufferSnapshot,
+) {
+    let audio_config = buffer.lock().unwrap().config.clone();
+    let sample_rate = audio_config.sample_rate as u64;
+    let audio_data = AudioData::new(snapshot.samples, audio_config);
+    let context = tracker.context();
+    match transcriber
+        .transcribe_audio_data_with_context(&audio_data, context.as_deref())
+        .await
+    {
+        Ok(result) => {
+            let (events, trim) = tracker.on_result(&result, snapshot.offset_ms);
+            for event in events {
+                let _ = tx.send(event).await;
+            }
+            // 已确认的段落不再重复解码：裁掉缓冲区中对应的音频
+            if let Some(trim) = trim {
+                let trim_sample = snapshot.start_sample + trim.end_ms * sample_rate / 1000;
+                buffer.lock().unwrap().trim_to(trim_sample);
+                tracker.trim(&trim);
+            }
+        }
+        Err(e) => {
+            let _ = tx.send(StreamingEvent::Error(e.to_string())).await;
+        }
+    }
+}
+
 /// 便捷函数：创建默认的流式转录器
+///
+/// `model` 可以是模型文件路径或模型名称（如 `"base"`）。
 pub fn create_streaming_transcriber(
-    model_path: impl Into<std::path::PathBuf>,
+    model: impl Into<std::path::PathBuf>,
 ) -> SttResult<StreamingTranscriber> {
-    let whisper_config = WhisperConfig::new(model_path);
+    let whisper_config = WhisperConfig::new(model);
     let streaming_config = StreamingConfig::default();
     let audio_config = AudioConfig::whisper_optimized();
 
@@ -513,12 +1459,14 @@ pub fn create_streaming_transcriber(
 }
 
 /// 便捷函数：创建自定义配置的流式转录器
+///
+/// `model` 可以是模型文件路径或模型名称。
 pub fn create_custom_streaming_transcriber(
-    model_path: impl Into<std::path::PathBuf>,
+    model: impl Into<std::path::PathBuf>,
     streaming_config: StreamingConfig,
     audio_config: AudioConfig,
 ) -> SttResult<StreamingTranscriber> {
-    let whisper_config = WhisperConfig::new(model_path);
+    let whisper_config = WhisperConfig::new(model);
     StreamingTranscriber::new(whisper_config, streaming_config, audio_config)
 }
 
@@ -590,6 +1538,430 @@ mod tests {
         assert_eq!(agg2.push_and_confirm("hello there"), None); // 公共前缀"hello"已经被包含在之前确认的"hello world"中
     }
 
+    #[test]
+    fn test_streaming_aggregator_multibyte() {
+        assert_eq!(
+            StreamingAggregator::longest_common_prefix(["你好世界", "你好朋友"].into_iter()),
+            "你好"
+        );
+        assert_eq!(
+            StreamingAggregator::longest_common_prefix(["中文", "中"].into_iter()),
+            "中"
+        );
+    }
+
+    fn result_with_segments(segments: &[(u64, u64, &str)]) -> TranscriptionResult {
+        let segments: Vec<TranscriptionSegment> = segments
+            .iter()
+            .map(|&(start_time, end_time, text)| TranscriptionSegment {
+                start_time,
+                end_time,
+                text: text.to_string(),
+                confidence: 0.9,
+                words: Vec::new(),
+            })
+            .collect();
+        TranscriptionResult {
+            text: segments
+                .iter()
+                .map(|s| s.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            segments,
+            ..result_with_text("")
+        }
+    }
+
+    fn result_with_text(text: &str) -> TranscriptionResult {
+        TranscriptionResult {
+            text: text.to_string(),
+            language: Some("en".to_string()),
+            language_detection: None,
+            segments: Vec::new(),
+            processing_time: 0,
+            audio_duration: 0,
+            decisions: Vec::new(),
+            skipped_duration: 0,
+        }
+    }
+
+    fn partials(events: &[StreamingEvent]) -> Vec<StreamingHypothesis> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                StreamingEvent::Partial(hypothesis) => Some(hypothesis.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_utterance_tracker_partials() {
+        let mut tracker = UtteranceTracker::new(2, None);
+
+        let events = tracker.on_result(&result_with_text("hello wor"), 0).0;
+        let partial = &partials(&events)[0];
+        assert_eq!((partial.utterance_id, partial.revision), (0, 0));
+        assert_eq!(partial.committed, "");
+        assert_eq!(partial.unconfirmed, "hello wor");
+
+        let events = tracker.on_result(&result_with_text("hello world and"), 0).0;
+        assert!(matches!(
+            &events[0],
+            StreamingEvent::Transcription(result) if result.text == "hello wor"
+        ));
+        let partial = &partials(&events)[0];
+        assert_eq!(partial.revision, 1);
+        assert_eq!(partial.committed, "hello wor");
+        assert_eq!(partial.unconfirmed, "ld and");
+        assert_eq!(partial.text(), "hello world and");
+
+        // 空结果不产生事件，也不消耗修订号
+        assert!(tracker.on_result(&result_with_text("  "), 0).0.is_empty());
+    }
+
+    #[test]
+    fn test_utterance_tracker_final() {
+        let mut tracker = UtteranceTracker::new(2, None);
+        tracker.on_result(&result_with_text("你好"), 0);
+        tracker.on_result(&result_with_text("你好世界"), 0);
+
+        let events = tracker.finish();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[0],
+            StreamingEvent::Transcription(result) if result.text == "世界"
+        ));
+        match &events[1] {
+            StreamingEvent::Final(hypothesis) => {
+                assert_eq!(hypothesis.utterance_id, 0);
+                assert_eq!(hypothesis.revision, 2);
+                assert_eq!(hypothesis.committed, "你好世界");
+                assert!(hypothesis.unconfirmed.is_empty());
+            }
+            other => panic!("期望 Final 事件，实际为 {other:?}"),
+        }
+
+        // 新语句使用新的编号，确认状态重新开始
+        let events = tracker.on_result(&result_with_text("再见"), 0).0;
+        let partial = &partials(&events)[0];
+        assert_eq!((partial.utterance_id, partial.revision), (1, 0));
+        assert_eq!(partial.unconfirmed, "再见");
+
+        // 没有任何转录结果的语句不产生 Final
+        let mut idle = UtteranceTracker::new(2, None);
+        assert!(idle.finish().is_empty());
+    }
+
+    #[test]
+    fn test_utterance_tracker_trim() {
+        let mut tracker = UtteranceTracker::new(2, None);
+        let first = result_with_segments(&[(0, 2000, "Hello there."), (2000, 3000, "How")]);
+        let (_, trim) = tracker.on_result(&first, 1000);
+        assert_eq!(trim, None);
+
+        let second = result_with_segments(&[(0, 2000, "Hello there."), (2000, 3500, "How are")]);
+        let (events, trim) = tracker.on_result(&second, 1000);
+        // 确认了 "Hello there. How"，完整确认的第一段以流时间发送
+        match &events[0] {
+            StreamingEvent::Transcription(result) => {
+                assert_eq!(result.text, "Hello there. How");
+                assert_eq!(result.segments.len(), 1);
+                assert_eq!(result.segments[0].start_time, 1000);
+                assert_eq!(result.segments[0].end_time, 3000);
+            }
+            other => panic!("期望 Transcription 事件，实际为 {other:?}"),
+        }
+        let trim = trim.expect("应在第一段结尾裁剪");
+        assert_eq!(trim.end_ms, 2000);
+
+        tracker.trim(&trim);
+        assert_eq!(tracker.context().as_deref(), Some("Hello there."));
+
+        // 裁剪后的缓冲区只包含后半句，段落时间相对新的缓冲区开头；
+        // 语句编号不变，部分结果只携带缓冲区内的文本与未确认的段落
+        let third = result_with_segments(&[(0, 1500, "How are you?")]);
+        let (events, _) = tracker.on_result(&third, 3000);
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, StreamingEvent::Final(_))));
+        match events.last() {
+            Some(StreamingEvent::Partial(partial)) => {
+                assert_eq!((partial.utterance_id, partial.revision), (0, 2));
+                assert_eq!(partial.committed_offset, "Hello there.".len());
+                assert_eq!(partial.committed, "How are");
+                assert_eq!(partial.unconfirmed, " you?");
+                assert_eq!(partial.segments.len(), 1);
+                assert_eq!(partial.segments[0].start_time, 3000);
+            }
+            other => panic!("期望 Partial 事件，实际为 {other:?}"),
+        }
+
+        let events = tracker.finish();
+        match &events[0] {
+            StreamingEvent::Transcription(result) => {
+                assert_eq!(result.text, "you?");
+                assert_eq!(result.segments[0].start_time, 3000);
+                assert_eq!(result.segments[0].end_time, 4500);
+            }
+            other => panic!("期望 Transcription 事件，实际为 {other:?}"),
+        }
+        match &events[1] {
+            StreamingEvent::Final(hypothesis) => {
+                // 最终结果携带整条语句
+                assert_eq!(hypothesis.utterance_id, 0);
+                assert_eq!(hypothesis.committed_offset, 0);
+                assert_eq!(hypothesis.committed, "Hello there. How are you?");
+                assert_eq!(hypothesis.segments.len(), 2);
+            }
+            other => panic!("期望 Final 事件，实际为 {other:?}"),
+        }
+        // 新语句以上一条语句的文本作为提示
+        assert_eq!(
+            tracker.context().as_deref(),
+            Some("Hello there. How are you?")
+        );
+    }
+
+    #[test]
+    fn test_utterance_tracker_single_segment_overflow() {
+        let mut tracker = UtteranceTracker::new(2, None);
+        let committed = |events: &[StreamingEvent]| -> Vec<String> {
+            events
+                .iter()
+                .filter_map(|event| match event {
+                    StreamingEvent::Transcription(result) => Some(result.text.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        tracker.on_result(&result_with_segments(&[(0, 3000, "Hello there how")]), 0);
+        let (events, trim) = tracker.on_result(
+            &result_with_segments(&[(0, 4000, "Hello there how are")]),
+            0,
+        );
+        assert_eq!(committed(&events), vec!["Hello there how"]);
+        assert_eq!(trim, None);
+
+        // 缓冲区溢出丢弃了前 1 秒：窗口不再以已确认文本开头，已确认文本归档，重复部分去掉
+        let window = |text| result_with_segments(&[(0, 4000, text)]);
+        let (events, _) = tracker.on_result(&window("there how are you"), 1000);
+        let partial = &partials(&events)[0];
+        assert_eq!(partial.committed_offset, "Hello there how".len());
+        assert_eq!(partial.committed, "");
+        assert_eq!(partial.unconfirmed, "are you");
+
+        // 之后的转录继续确认新文本
+        let (events, trim) = tracker.on_result(&window("there how are you doing"), 1000);
+        assert_eq!(committed(&events), vec!["are you"]);
+        assert_eq!(trim, None);
+
+        // 窗口中唯一的段完整确认后在其结尾裁剪
+        let (events, trim) = tracker.on_result(&window("there how are you doing"), 1000);
+        assert_eq!(committed(&events), vec!["doing"]);
+        let trim = trim.expect("完整确认的唯一段落应可裁剪");
+        assert_eq!(trim.end_ms, 4000);
+        tracker.trim(&trim);
+
+        // 裁剪后的窗口从裁剪位置开始，不视为溢出
+        let (events, _) = tracker.on_result(&result_with_text("Fine"), 5000);
+        assert_eq!(partials(&events)[0].unconfirmed, "Fine");
+        match tracker.finish().last() {
+            Some(StreamingEvent::Final(hypothesis)) => {
+                assert_eq!(hypothesis.committed, "Hello there how are you doing Fine");
+            }
+            other => panic!("期望 Final 事件，实际为 {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_audio_buffer_offset() {
+        let config = AudioConfig::new(1000, 1, 16);
+        let mut buffer = AudioBuffer::new(config, Duration::from_secs(1));
+        buffer.push_samples(&vec![0.0; 1200]);
+        // 溢出丢弃的样本计入流位置
+        assert_eq!(buffer.start_sample, 200);
+        assert_eq!(buffer.start_ms(), 200);
+
+        buffer.trim_to(700);
+        assert_eq!(buffer.samples.len(), 500);
+        assert_eq!(buffer.start_sample, 700);
+
+        // 已丢弃的位置不会再次裁剪
+        buffer.trim_to(100);
+        assert_eq!(buffer.samples.len(), 500);
+
+        buffer.clear();
+        assert_eq!(buffer.start_sample, 1200);
+        assert_eq!(buffer.total_samples, 1200);
+
+        buffer.push_samples(&[0.0; 300]);
+        assert_eq!(buffer.total_samples, 1500);
+        buffer.reset_stream();
+        assert_eq!((buffer.start_sample, buffer.total_samples), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_audio_queue_drop_oldest() {
+        let queue = AudioQueue::new(4, OverflowPolicy::DropOldest);
+        queue.push_blocking(vec![0.1, 0.2]).unwrap();
+        queue.push_blocking(vec![0.3, 0.4]).unwrap();
+        queue.push_blocking(vec![0.5, 0.6, 0.7]).unwrap();
+
+        let (samples, dropped) = queue.pop_all().await.unwrap();
+        assert_eq!(samples, vec![0.5, 0.6, 0.7]);
+        assert_eq!(dropped, 4);
+
+        // 单块超过容量时仍然接受
+        queue.push_blocking(vec![0.0; 10]).unwrap();
+        assert_eq!(queue.pop_all().await.unwrap(), (vec![0.0; 10], 0));
+
+        queue.close();
+        assert!(queue.pop_all().await.is_none());
+        assert!(queue.push_blocking(vec![0.0]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_audio_queue_error_policy() {
+        let queue = AudioQueue::new(2, OverflowPolicy::Error);
+        queue.push_blocking(vec![0.1, 0.2]).unwrap();
+        assert!(matches!(
+            queue.push_blocking(vec![0.3]),
+            Err(SttError::StreamError(_))
+        ));
+        assert_eq!(queue.pop_all().await.unwrap(), (vec![0.1, 0.2], 0));
+    }
+
+    #[tokio::test]
+    async fn test_audio_queue_block_policy() {
+        let queue = Arc::new(AudioQueue::new(2, OverflowPolicy::Block));
+        queue.push(vec![0.1, 0.2]).await.unwrap();
+
+        let writer = {
+            let queue = Arc::clone(&queue);
+            tokio::spawn(async move { queue.push(vec![0.3, 0.4]).await })
+        };
+        tokio::task::yield_now().await;
+        assert!(!writer.is_finished());
+
+        // 消费者取走数据后，等待中的写入者被唤醒
+        assert_eq!(queue.pop_all().await.unwrap(), (vec![0.1, 0.2], 0));
+        writer.await.unwrap().unwrap();
+        assert_eq!(queue.pop_all().await.unwrap(), (vec![0.3, 0.4], 0));
+    }
+
+    #[test]
+    fn test_input_samples_decode() {
+        assert_eq!(
+            InputSamples::U8(&[0, 128, 255]).to_f32(),
+            vec![-1.0, 0.0, 127.0 / 128.0]
+        );
+        assert_eq!(
+            InputSamples::I16(&[-32768, 16384]).to_f32(),
+            vec![-1.0, 0.5]
+        );
+
+        // μ-law：0xFF/0x7F 为零，0x00/0x80 为负/正最大值
+        let decoded = InputSamples::MuLaw(&[0xFF, 0x7F, 0x00, 0x80]).to_f32();
+        assert_eq!(decoded[0], 0.0);
+        assert_eq!(decoded[1], 0.0);
+        assert_eq!(decoded[2], -32124.0 / 32768.0);
+        assert_eq!(decoded[3], 32124.0 / 32768.0);
+    }
+
+    #[test]
+    fn test_input_converter() {
+        // 立体声直通：只下混，不重采样
+        let mut converter = InputConverter::new(InputFormat::new(16_000, 2), 16_000).unwrap();
+        let output = converter
+            .process(InputSamples::I16(&[16384, 0, -16384, -16384]))
+            .unwrap();
+        assert_eq!(output, vec![0.25, -0.5]);
+        assert!(converter
+            .process(InputSamples::F32(&[0.1, 0.2, 0.3]))
+            .is_err());
+
+        // 48kHz 立体声 1 秒 -> 16kHz 单声道 1 秒
+        let mut converter = InputConverter::new(InputFormat::new(48_000, 2), 16_000).unwrap();
+        let stereo: Vec<f32> = (0..48_000)
+            .flat_map(|i| {
+                let v = (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 48_000.0).sin() * 0.5;
+                [v, v]
+            })
+            .collect();
+        let mut total = 0;
+        for chunk in stereo.chunks(960) {
+            total += converter.process(InputSamples::F32(chunk)).unwrap().len();
+        }
+        total += converter.flush().unwrap().len();
+        assert_eq!(total, 16_000);
+
+        assert!(InputConverter::new(InputFormat::new(16_000, 0), 16_000).is_err());
+    }
+
+    #[test]
+    fn test_audio_buffer_gap() {
+        let mut buffer = AudioBuffer::new(AudioConfig::new(1000, 1, 16), Duration::from_secs(1));
+        buffer.push_samples(&[0.5; 100]);
+        buffer.push_gap(50);
+        assert_eq!(buffer.samples.len(), 150);
+        assert_eq!(buffer.total_samples, 150);
+
+        // 超过缓冲区容量的空缺只保留一个缓冲区长度的静音
+        buffer.push_gap(5000);
+        assert_eq!(buffer.samples.len(), 1000);
+        assert_eq!(buffer.total_samples, 5150);
+        assert_eq!(buffer.start_sample, 4150);
+    }
+
+    #[test]
+    fn test_utterance_tracker_stream_time() {
+        let stream_start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut tracker = UtteranceTracker::new(2, Some(stream_start));
+
+        let mut result = result_with_segments(&[(200, 1800, "第一句。"), (1800, 2600, "第二")]);
+        result.audio_duration = 3000;
+        tracker.on_result(&result, 60_000);
+        let mut result = result_with_segments(&[(200, 1800, "第一句。"), (1800, 2800, "第二句")]);
+        result.audio_duration = 3000;
+        let (events, _) = tracker.on_result(&result, 60_000);
+        match &events[0] {
+            StreamingEvent::Transcription(committed) => {
+                // audio_duration 是本次解码的窗口时长，流位置由段落时间给出
+                assert_eq!(committed.audio_duration, 3000);
+                assert_eq!(committed.segments[0].start_time, 60_200);
+            }
+            other => panic!("期望 Transcription 事件，实际为 {other:?}"),
+        }
+        match events.last() {
+            Some(StreamingEvent::Partial(partial)) => {
+                // 已确认的第一段已随 Transcription 发送，部分结果只含未确认的段落
+                assert_eq!(partial.segments.len(), 1);
+                assert_eq!(partial.segments[0].end_time, 62_800);
+                assert_eq!(partial.stream_end_ms, 63_000);
+                assert_eq!(
+                    partial.wall_clock,
+                    Some(stream_start + Duration::from_millis(60_200))
+                );
+            }
+            other => panic!("期望 Partial 事件，实际为 {other:?}"),
+        }
+
+        match tracker.finish().last() {
+            Some(StreamingEvent::Final(hypothesis)) => {
+                assert_eq!(hypothesis.committed, "第一句。 第二句");
+                let times: Vec<_> = hypothesis
+                    .segments
+                    .iter()
+                    .map(|s| (s.start_time, s.end_time))
+                    .collect();
+                assert_eq!(times, vec![(60_200, 61_800), (61_800, 62_800)]);
+            }
+            other => panic!("期望 Final 事件，实际为 {other:?}"),
+        }
+    }
+
     #[test]
     fn test_streaming_transcriber_creation() {
         use crate::whisper::WhisperConfig;
@@ -0,0 +1,47 @@
+//! 文本工具
+//!
+//! 中日韩文字判定与终端显示宽度，供分词、拼接、字幕排版与评估对齐共用。
+
+/// 是否为中日韩文字（按字切分）
+pub(crate) fn is_cjk(c: char) -> bool {
+    is_han(c)
+        || matches!(c as u32,
+            0x3040..=0x30FF       // 平假名、片假名
+            | 0xAC00..=0xD7AF) // 韩文音节
+}
+
+/// 是否为汉字
+pub(crate) fn is_han(c: char) -> bool {
+    matches!(c as u32,
+        0x3400..=0x4DBF       // 扩展 A
+        | 0x4E00..=0x9FFF     // 基本汉字
+        | 0xF900..=0xFAFF     // 兼容汉字
+        | 0x20000..=0x2FA1F) // 扩展 B 及以后
+}
+
+/// 中日韩文字或全角标点
+pub(crate) fn is_cjk_or_fullwidth(c: char) -> bool {
+    is_cjk(c) || matches!(c as u32, 0x3000..=0x303F | 0xFF00..=0xFFEF)
+}
+
+/// 显示宽度：中日韩文字与全角字符计为 2
+pub(crate) fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| if is_cjk_or_fullwidth(c) { 2 } else { 1 })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("你好，world"), 11);
+        assert_eq!(display_width("カタカナ한국"), 12);
+        // 非中日韩的宽码位（如西里尔字母、带重音字母）按 1 计
+        assert_eq!(display_width("привет"), 6);
+        assert_eq!(display_width("café"), 4);
+    }
+}
@@ -9,7 +9,6 @@ pub struct SimpleVad {
     threshold: f32,
     /// 检测窗口大小（样本数）
     window_size: usize,
-    #[allow(dead_code)]
     /// 音频采样率 (Hz)
     sample_rate: u32,
 }
@@ -79,6 +78,63 @@ impl SimpleVad {
 
         segments
     }
+
+    /// 检测语音区域：每个语音段前后各扩展 `padding_ms`，间隔不超过 `merge_gap_ms` 的区域合并
+    ///
+    /// 返回按时间排序、互不重叠的 `(start, end)` 样本区间。
+    pub fn speech_regions(
+        &self,
+        samples: &[f32],
+        padding_ms: u64,
+        merge_gap_ms: u64,
+    ) -> Vec<(usize, usize)> {
+        let padding = self.ms_to_samples(padding_ms);
+        let merge_gap = self.ms_to_samples(merge_gap_ms);
+        let mut regions: Vec<(usize, usize)> = Vec::new();
+
+        for (start, end) in self.detect_speech_segments(samples) {
+            let start = start.saturating_sub(padding);
+            let end = (end + padding).min(samples.len());
+            match regions.last_mut() {
+                Some(last) if start <= last.1 + merge_gap => last.1 = last.1.max(end),
+                _ => regions.push((start, end)),
+            }
+        }
+
+        regions
+    }
+
+    fn ms_to_samples(&self, ms: u64) -> usize {
+        (ms * self.sample_rate as u64 / 1000) as usize
+    }
+
+    /// 在 `range` 范围内寻找能量最低的窗口，返回其中心位置（样本索引）
+    ///
+    /// 常用于在静音处切分长音频；能量相同时取最靠近范围末尾的窗口。
+    pub fn quietest_point(&self, samples: &[f32], range: std::ops::Range<usize>) -> usize {
+        let end = range.end.min(samples.len());
+        let start = range.start.min(end);
+        if end - start < self.window_size {
+            return end;
+        }
+
+        let mut best = end;
+        let mut best_rms = f32::MAX;
+        let mut window_start = end - self.window_size;
+        loop {
+            let rms = self.calculate_rms(&samples[window_start..window_start + self.window_size]);
+            if rms < best_rms {
+                best_rms = rms;
+                best = window_start + self.window_size / 2;
+            }
+            if window_start < start + self.window_size {
+                break;
+            }
+            window_start -= self.window_size;
+        }
+
+        best
+    }
 }
 
 #[cfg(test)]
@@ -129,6 +185,45 @@ mod tests {
         assert_eq!(segments.len(), 2);
     }
 
+    #[test]
+    fn test_speech_regions_padding_and_merge() {
+        let vad = SimpleVad::new(0.05);
+
+        // 1s 静音 - 1s 语音 - 0.3s 停顿 - 1s 语音 - 3s 静音 - 0.5s 语音 - 1s 静音
+        let mut samples = vec![0.0; 16000];
+        samples.extend(vec![0.1; 16000]);
+        samples.extend(vec![0.0; 4800]);
+        samples.extend(vec![0.1; 16000]);
+        samples.extend(vec![0.0; 48000]);
+        samples.extend(vec![0.1; 8000]);
+        samples.extend(vec![0.0; 16000]);
+
+        let regions = vad.speech_regions(&samples, 200, 500);
+        assert_eq!(regions, vec![(12800, 56000), (97600, 112000)]);
+
+        // 不合并、不扩展时保留原始语音段
+        let regions = vad.speech_regions(&samples, 0, 0);
+        assert_eq!(regions.len(), 3);
+
+        assert!(vad.speech_regions(&vec![0.0; 16000], 200, 500).is_empty());
+    }
+
+    #[test]
+    fn test_quietest_point() {
+        let vad = SimpleVad::new(0.01);
+
+        // 语音-静音-语音，静音位于 [3200, 4800)
+        let mut samples = vec![0.2; 3200];
+        samples.extend(vec![0.0; 1600]);
+        samples.extend(vec![0.2; 3200]);
+
+        let point = vad.quietest_point(&samples, 1000..7000);
+        assert!((3200..4800).contains(&point), "切分点应落在静音内: {point}");
+
+        // 范围小于一个窗口时返回范围末尾
+        assert_eq!(vad.quietest_point(&samples, 100..200), 200);
+    }
+
     #[test]
     fn test_calculate_rms() {
         let vad = SimpleVad::new(0.01);
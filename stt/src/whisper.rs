@@ -2,31 +2,204 @@
 //!
 //! 基于 whisper-rs 库实现的语音识别功能
 
-use crate::audio::AudioData;
+use crate::audio::{AdvancedResampler, AudioData, ResampleQuality};
 use crate::error::{SttError, SttResult};
+use crate::model::{read_model_info, ModelInfo};
+use crate::postprocess::PostProcessConfig;
+use crate::progress::{CancellationToken, ProgressTracker, TranscribeOptions};
+use crate::quality::{
+    compression_ratio, detect_repetition, DecodeAttempt, QualityThresholds, SegmentDecision,
+    SegmentVerdict,
+};
+use crate::registry::resolve_model_path;
+use crate::script::{is_chinese_language, OutputScript};
+use crate::words::{merge_tokens, TokenPiece, TranscriptionWord};
 use audio_utils as audio_lib;
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+use sysinfo::System;
+use tokio::sync::Semaphore;
 use whisper_rs::{
-    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+    get_lang_str, DtwMode, DtwModelPreset, DtwParameters, FullParams, SamplingStrategy,
+    WhisperContext, WhisperContextParameters, WhisperSegment, WhisperState, WhisperTokenId,
 };
 
 // 导入 VAD 相关模块
 use crate::vad::SimpleVad;
 
+/// Whisper 模型要求的输入采样率（Hz）
+pub(crate) const WHISPER_SAMPLE_RATE: u32 = 16000;
+
+/// whisper.cpp 可解码的最短输入（1 秒，额外留一帧余量）
+const MIN_DECODE_SAMPLES: usize = WHISPER_SAMPLE_RATE as usize + 160;
+
+/// 解码采样策略
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DecodingStrategy {
+    /// 贪心解码：速度快，适合实时场景
+    Greedy {
+        /// 候选数量（温度回退时生效），至少为 1
+        best_of: i32,
+    },
+    /// 束搜索：更准确但更耗时，适合离线批处理
+    BeamSearch {
+        /// 束宽，至少为 1
+        beam_size: i32,
+        /// 耐心系数（whisper.cpp 目前未实现，-1.0 表示默认）
+        patience: f32,
+    },
+}
+
+impl Default for DecodingStrategy {
+    fn default() -> Self {
+        DecodingStrategy::Greedy { best_of: 1 }
+    }
+}
+
+impl DecodingStrategy {
+    /// 转换为 whisper-rs 的采样策略
+    fn to_sampling_strategy(self) -> SamplingStrategy {
+        match self {
+            DecodingStrategy::Greedy { best_of } => SamplingStrategy::Greedy { best_of },
+            DecodingStrategy::BeamSearch {
+                beam_size,
+                patience,
+            } => SamplingStrategy::BeamSearch {
+                beam_size,
+                patience,
+            },
+        }
+    }
+}
+
+/// DTW 词级时间戳对齐预设
+///
+/// 开启后 whisper 使用交叉注意力做动态时间规整，得到更精确的 token 时间点；
+/// 预设需与所加载的模型一致。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DtwPreset {
+    TinyEn,
+    Tiny,
+    BaseEn,
+    Base,
+    SmallEn,
+    Small,
+    MediumEn,
+    Medium,
+    LargeV1,
+    LargeV2,
+    LargeV3,
+    LargeV3Turbo,
+    /// 使用模型最上面的若干文本层（适用于非标准模型）
+    TopMost {
+        /// 使用的文本层数
+        n_top: i32,
+    },
+}
+
+impl DtwPreset {
+    /// 转换为 whisper-rs 的 DTW 模式
+    fn to_dtw_mode(self) -> DtwMode<'static> {
+        let model_preset = match self {
+            DtwPreset::TopMost { n_top } => return DtwMode::TopMost { n_top },
+            DtwPreset::TinyEn => DtwModelPreset::TinyEn,
+            DtwPreset::Tiny => DtwModelPreset::Tiny,
+            DtwPreset::BaseEn => DtwModelPreset::BaseEn,
+            DtwPreset::Base => DtwModelPreset::Base,
+            DtwPreset::SmallEn => DtwModelPreset::SmallEn,
+            DtwPreset::Small => DtwModelPreset::Small,
+            DtwPreset::MediumEn => DtwModelPreset::MediumEn,
+            DtwPreset::Medium => DtwModelPreset::Medium,
+            DtwPreset::LargeV1 => DtwModelPreset::LargeV1,
+            DtwPreset::LargeV2 => DtwModelPreset::LargeV2,
+            DtwPreset::LargeV3 => DtwModelPreset::LargeV3,
+            DtwPreset::LargeV3Turbo => DtwModelPreset::LargeV3Turbo,
+        };
+        DtwMode::ModelPreset { model_preset }
+    }
+}
+
+/// 推理后端
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ComputeBackend {
+    /// CPU
+    Cpu,
+    /// NVIDIA CUDA
+    Cuda,
+    /// Vulkan
+    Vulkan,
+    /// Apple Metal
+    Metal,
+}
+
+impl ComputeBackend {
+    /// 编译时启用的 GPU 后端（未启用任何 GPU 特性时为 None）
+    pub fn compiled_gpu() -> Option<Self> {
+        if cfg!(feature = "cuda") {
+            Some(ComputeBackend::Cuda)
+        } else if cfg!(feature = "vulkan") {
+            Some(ComputeBackend::Vulkan)
+        } else if cfg!(feature = "metal") {
+            Some(ComputeBackend::Metal)
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for ComputeBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ComputeBackend::Cpu => "CPU",
+            ComputeBackend::Cuda => "CUDA",
+            ComputeBackend::Vulkan => "Vulkan",
+            ComputeBackend::Metal => "Metal",
+        };
+        f.write_str(name)
+    }
+}
+
+/// VAD 模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VadMode {
+    /// 只裁剪第一个语音段之前的静音
+    #[default]
+    TrimLeading,
+    /// 只转录检测到的语音区域，跳过其间的停顿
+    SpeechOnly,
+}
+
 /// Whisper 模型配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WhisperConfig {
     /// 模型文件路径
     pub model_path: PathBuf,
-    /// 语言代码（如 "zh", "en"，None 表示自动检测）
+    /// 语言代码（如 "zh", "en"，None 或 "auto" 表示自动检测）
     pub language: Option<String>,
+    /// 语言检测只使用音频开头的这段时长（秒）
+    #[serde(default = "default_language_detection_seconds")]
+    pub language_detection_seconds: f32,
+    /// 语言检测结果中保留概率最高的前 N 种语言
+    #[serde(default = "default_language_top_n")]
+    pub language_top_n: usize,
     /// 是否翻译为英文
     pub translate: bool,
-    /// 线程数
+    /// 每个推理使用的线程数（默认为物理核心数除以默认并发推理数）
     pub n_threads: i32,
+    /// 是否使用 GPU（需启用 `cuda`/`vulkan`/`metal` 特性，关闭时强制使用 CPU）
+    #[serde(default = "default_use_gpu")]
+    pub use_gpu: bool,
+    /// GPU 设备编号
+    #[serde(default)]
+    pub gpu_device: i32,
+    /// 是否启用 flash attention（与 DTW 不能同时使用）
+    #[serde(default)]
+    pub flash_attn: bool,
+    /// DTW 词级时间戳对齐预设（None 表示关闭）
+    #[serde(default)]
+    pub dtw_preset: Option<DtwPreset>,
     /// 是否输出时间戳
     pub print_timestamps: bool,
     /// 是否输出进度信息
@@ -35,14 +208,120 @@ pub struct WhisperConfig {
     pub print_special: bool,
     /// 温度参数（0.0-1.0）
     pub temperature: f32,
+    /// 解码采样策略
+    #[serde(default)]
+    pub sampling_strategy: DecodingStrategy,
+    /// 温度回退步长：段未通过质量检查时，按此步长提高温度重新解码（0 表示不回退）
+    #[serde(default = "default_temperature_increment")]
+    pub temperature_increment: f32,
+    /// 压缩比阈值：超过时判定为重复循环并触发回退（None 表示不检查）
+    #[serde(default = "default_compression_ratio_threshold")]
+    pub compression_ratio_threshold: Option<f32>,
+    /// 平均对数概率阈值：低于时触发回退（None 表示不检查）
+    #[serde(default = "default_logprob_threshold")]
+    pub logprob_threshold: Option<f32>,
+    /// 无语音概率阈值：超过且平均对数概率低于阈值时丢弃该段（None 表示不丢弃）
+    #[serde(default = "default_no_speech_threshold")]
+    pub no_speech_threshold: Option<f32>,
+    /// 是否检测重复循环并触发回退
+    #[serde(default = "default_detect_repetition")]
+    pub detect_repetition: bool,
     /// 最大段长度（毫秒）
     pub max_segment_length: Option<u32>,
     /// 是否使用初始提示
     pub initial_prompt: Option<String>,
+    /// 是否输出词级时间戳（开启 whisper 的 token 时间戳）
+    #[serde(default)]
+    pub word_timestamps: bool,
+    /// 热词/领域词汇表（如产品名、人名），会渲染进每次解码的提示词
+    #[serde(default)]
+    pub vocabulary: Vec<String>,
     /// 是否启用语音活动检测 (VAD)
     pub enable_vad: bool,
     /// VAD 阈值 (0.0-1.0)，用于检测语音活动
     pub vad_threshold: f32,
+    /// VAD 模式
+    #[serde(default)]
+    pub vad_mode: VadMode,
+    /// 只转录语音时，每个语音区域前后保留的时长（毫秒）
+    #[serde(default = "default_vad_padding_ms")]
+    pub vad_padding_ms: u64,
+    /// 只转录语音时，间隔不超过该时长（毫秒）的语音区域会被合并
+    #[serde(default = "default_vad_merge_gap_ms")]
+    pub vad_merge_gap_ms: u64,
+    /// 输入采样率不是 16kHz 时使用的重采样质量
+    #[serde(default)]
+    pub resample_quality: ResampleQuality,
+    /// 同一个转录器（及其克隆）同时运行的推理数上限
+    #[serde(default = "default_max_concurrent_inference")]
+    pub max_concurrent_inference: usize,
+    /// 中文输出字形（简体、繁体或保持原样）
+    #[serde(default)]
+    pub output_script: OutputScript,
+    /// 转录文本后处理（逆文本规范化、标点等，None 表示保留原始输出）
+    #[serde(default)]
+    pub post_process: Option<PostProcessConfig>,
+}
+
+fn default_language_detection_seconds() -> f32 {
+    30.0
+}
+
+fn default_language_top_n() -> usize {
+    5
+}
+
+fn default_vad_padding_ms() -> u64 {
+    200
+}
+
+fn default_vad_merge_gap_ms() -> u64 {
+    500
+}
+
+/// 物理核心数，无法获取时退回逻辑核心数
+fn physical_cores() -> usize {
+    static CORES: OnceLock<usize> = OnceLock::new();
+    *CORES.get_or_init(|| {
+        System::new()
+            .physical_core_count()
+            .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+            .map_or(4, |n| n.max(1))
+    })
+}
+
+/// 默认线程数：物理核心数平分给默认的并发推理数，避免同时解码时 CPU 超额订阅
+fn default_n_threads() -> i32 {
+    (physical_cores() / default_max_concurrent_inference()).max(1) as i32
+}
+
+fn default_use_gpu() -> bool {
+    ComputeBackend::compiled_gpu().is_some()
+}
+
+/// 默认允许两个推理同时运行（每个推理使用一半物理核心），批量转录默认即可并行两个文件
+fn default_max_concurrent_inference() -> usize {
+    2
+}
+
+fn default_temperature_increment() -> f32 {
+    0.2
+}
+
+fn default_compression_ratio_threshold() -> Option<f32> {
+    Some(2.4)
+}
+
+fn default_logprob_threshold() -> Option<f32> {
+    Some(-1.0)
+}
+
+fn default_no_speech_threshold() -> Option<f32> {
+    Some(0.6)
+}
+
+fn default_detect_repetition() -> bool {
+    true
 }
 
 impl Default for WhisperConfig {
@@ -50,25 +329,50 @@ impl Default for WhisperConfig {
         Self {
             model_path: PathBuf::from("models/ggml-base.bin"),
             language: Some("auto".to_string()), // 自动检测
+            language_detection_seconds: default_language_detection_seconds(),
+            language_top_n: default_language_top_n(),
             translate: false,
-            n_threads: 4,
+            n_threads: default_n_threads(),
+            use_gpu: default_use_gpu(),
+            gpu_device: 0,
+            flash_attn: false,
+            dtw_preset: None,
             print_timestamps: true,
             print_progress: false,
             print_special: false,
             temperature: 0.2,
+            sampling_strategy: DecodingStrategy::default(),
+            temperature_increment: default_temperature_increment(),
+            compression_ratio_threshold: default_compression_ratio_threshold(),
+            logprob_threshold: default_logprob_threshold(),
+            no_speech_threshold: default_no_speech_threshold(),
+            detect_repetition: default_detect_repetition(),
             max_segment_length: None,
             initial_prompt: None,
-            enable_vad: true,   // 默认禁用 VAD，保持向后兼容
+            word_timestamps: false,
+            vocabulary: Vec::new(),
+            enable_vad: true,    // 默认禁用 VAD，保持向后兼容
             vad_threshold: 0.01, // 默认 VAD 阈值
+            vad_mode: VadMode::TrimLeading,
+            vad_padding_ms: default_vad_padding_ms(),
+            vad_merge_gap_ms: default_vad_merge_gap_ms(),
+            resample_quality: ResampleQuality::Standard,
+            max_concurrent_inference: default_max_concurrent_inference(),
+            output_script: OutputScript::AsIs,
+            post_process: None,
         }
     }
 }
 
 impl WhisperConfig {
     /// 创建新的配置
-    pub fn new<P: Into<PathBuf>>(model_path: P) -> Self {
+    ///
+    /// `model` 可以是模型文件路径，也可以是模型名称（如 `"small"`、`"medium.en"`）；
+    /// 名称在验证配置或创建转录器时于默认模型目录中查找，并检查文件头是否完整（不计算校验和），
+    /// 需要校验和时使用 [`ModelRegistry::resolve`](crate::registry::ModelRegistry::resolve)。
+    pub fn new<P: Into<PathBuf>>(model: P) -> Self {
         Self {
-            model_path: model_path.into(),
+            model_path: model.into(),
             ..Default::default()
         }
     }
@@ -79,6 +383,25 @@ impl WhisperConfig {
         self
     }
 
+    /// 设置语言检测使用的音频时长（秒）
+    pub fn with_language_detection_seconds(mut self, seconds: f32) -> Self {
+        self.language_detection_seconds = seconds;
+        self
+    }
+
+    /// 设置语言检测结果保留的语言数量
+    pub fn with_language_top_n(mut self, top_n: usize) -> Self {
+        self.language_top_n = top_n.max(1);
+        self
+    }
+
+    /// 配置中明确指定的语言（None 或 "auto" 时返回 None）
+    pub fn fixed_language(&self) -> Option<&str> {
+        self.language
+            .as_deref()
+            .filter(|language| !language.is_empty() && *language != "auto")
+    }
+
     /// 设置是否翻译
     pub fn with_translate(mut self, translate: bool) -> Self {
         self.translate = translate;
@@ -91,18 +414,176 @@ impl WhisperConfig {
         self
     }
 
+    /// 设置是否使用 GPU（false 时即使是 GPU 构建也只用 CPU）
+    pub fn with_gpu(mut self, use_gpu: bool) -> Self {
+        self.use_gpu = use_gpu;
+        self
+    }
+
+    /// 设置 GPU 设备编号
+    pub fn with_gpu_device(mut self, device: i32) -> Self {
+        self.gpu_device = device;
+        self
+    }
+
+    /// 设置是否启用 flash attention
+    pub fn with_flash_attn(mut self, enable: bool) -> Self {
+        self.flash_attn = enable;
+        self
+    }
+
+    /// 设置 DTW 词级时间戳对齐预设
+    pub fn with_dtw_preset(mut self, preset: Option<DtwPreset>) -> Self {
+        self.dtw_preset = preset;
+        self
+    }
+
+    /// 实际使用的推理后端（由配置与编译特性决定）
+    pub fn backend(&self) -> ComputeBackend {
+        match ComputeBackend::compiled_gpu() {
+            Some(gpu) if self.use_gpu => gpu,
+            _ => ComputeBackend::Cpu,
+        }
+    }
+
+    /// 构建模型上下文参数
+    fn context_params(&self) -> WhisperContextParameters<'static> {
+        let mut params = WhisperContextParameters::default();
+        params
+            .use_gpu(self.backend() != ComputeBackend::Cpu)
+            .gpu_device(self.gpu_device)
+            .flash_attn(self.flash_attn);
+        if let Some(preset) = self.dtw_preset {
+            params.dtw_parameters(DtwParameters {
+                mode: preset.to_dtw_mode(),
+                ..Default::default()
+            });
+        }
+        params
+    }
+
     /// 设置温度参数
     pub fn with_temperature(mut self, temperature: f32) -> Self {
         self.temperature = temperature.clamp(0.0, 1.0);
         self
     }
 
+    /// 设置解码采样策略
+    pub fn with_sampling_strategy(mut self, strategy: DecodingStrategy) -> Self {
+        self.sampling_strategy = strategy;
+        self
+    }
+
+    /// 使用贪心解码
+    pub fn with_greedy(self, best_of: i32) -> Self {
+        self.with_sampling_strategy(DecodingStrategy::Greedy { best_of })
+    }
+
+    /// 使用束搜索解码
+    pub fn with_beam_search(self, beam_size: i32, patience: f32) -> Self {
+        self.with_sampling_strategy(DecodingStrategy::BeamSearch {
+            beam_size,
+            patience,
+        })
+    }
+
+    /// 设置温度回退步长（0 表示不回退）
+    pub fn with_temperature_increment(mut self, increment: f32) -> Self {
+        self.temperature_increment = increment.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 设置压缩比阈值
+    pub fn with_compression_ratio_threshold(mut self, threshold: Option<f32>) -> Self {
+        self.compression_ratio_threshold = threshold;
+        self
+    }
+
+    /// 设置平均对数概率阈值
+    pub fn with_logprob_threshold(mut self, threshold: Option<f32>) -> Self {
+        self.logprob_threshold = threshold;
+        self
+    }
+
+    /// 设置无语音概率阈值
+    pub fn with_no_speech_threshold(mut self, threshold: Option<f32>) -> Self {
+        self.no_speech_threshold = threshold.map(|t| t.clamp(0.0, 1.0));
+        self
+    }
+
+    /// 设置是否检测重复循环
+    pub fn with_repetition_detection(mut self, enable: bool) -> Self {
+        self.detect_repetition = enable;
+        self
+    }
+
+    /// 质量检查阈值
+    pub fn quality_thresholds(&self) -> QualityThresholds {
+        QualityThresholds {
+            compression_ratio: self.compression_ratio_threshold,
+            logprob: self.logprob_threshold,
+            no_speech: self.no_speech_threshold,
+            detect_repetition: self.detect_repetition,
+        }
+    }
+
     /// 设置初始提示
     pub fn with_initial_prompt<S: Into<String>>(mut self, prompt: S) -> Self {
         self.initial_prompt = Some(prompt.into());
         self
     }
 
+    /// 设置是否输出词级时间戳
+    pub fn with_word_timestamps(mut self, enable: bool) -> Self {
+        self.word_timestamps = enable;
+        self
+    }
+
+    /// 设置热词/领域词汇表（去除空白项与重复项）
+    pub fn with_vocabulary<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.vocabulary.clear();
+        for word in words {
+            let word = word.into().trim().to_string();
+            if !word.is_empty() && !self.vocabulary.contains(&word) {
+                self.vocabulary.push(word);
+            }
+        }
+        self
+    }
+
+    /// 渲染提示词：字形提示在前，其后依次为初始提示与词汇表
+    ///
+    /// 字形提示只在明确指定中文时加入；全部为空时返回 None。
+    pub fn render_prompt(&self) -> Option<String> {
+        let mut parts = Vec::new();
+
+        if self.fixed_language().is_some_and(is_chinese_language) {
+            if let Some(prompt) = self.output_script.prompt() {
+                parts.push(prompt.to_string());
+            }
+        }
+
+        if let Some(prompt) = self.initial_prompt.as_deref().map(str::trim) {
+            if !prompt.is_empty() {
+                parts.push(prompt.to_string());
+            }
+        }
+
+        if !self.vocabulary.is_empty() {
+            parts.push(format!("{}.", self.vocabulary.join(", ")));
+        }
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" "))
+        }
+    }
+
     /// 设置是否启用 VAD
     pub fn with_vad(mut self, enable_vad: bool) -> Self {
         self.enable_vad = enable_vad;
@@ -115,14 +596,61 @@ impl WhisperConfig {
         self
     }
 
+    /// 设置 VAD 模式
+    pub fn with_vad_mode(mut self, mode: VadMode) -> Self {
+        self.vad_mode = mode;
+        self
+    }
+
+    /// 只转录语音区域（启用 VAD 并切换到 [`VadMode::SpeechOnly`]）
+    pub fn with_speech_only(self, padding_ms: u64, merge_gap_ms: u64) -> Self {
+        let mut config = self.with_vad(true).with_vad_mode(VadMode::SpeechOnly);
+        config.vad_padding_ms = padding_ms;
+        config.vad_merge_gap_ms = merge_gap_ms;
+        config
+    }
+
+    /// 设置同时运行的推理数上限（至少为 1）
+    pub fn with_max_concurrent_inference(mut self, max_concurrent: usize) -> Self {
+        self.max_concurrent_inference = max_concurrent.max(1);
+        self
+    }
+
+    /// 设置重采样质量
+    pub fn with_resample_quality(mut self, quality: ResampleQuality) -> Self {
+        self.resample_quality = quality;
+        self
+    }
+
+    /// 设置中文输出字形
+    pub fn with_output_script(mut self, script: OutputScript) -> Self {
+        self.output_script = script;
+        self
+    }
+
+    /// 设置转录文本后处理
+    pub fn with_post_process(mut self, config: Option<PostProcessConfig>) -> Self {
+        self.post_process = config;
+        self
+    }
+
     /// 验证配置
+    ///
+    /// 除配置参数外还会解析模型文件头，截断或非 ggml 的文件返回 [`SttError::ModelLoadError`]。
     pub fn validate(&self) -> SttResult<()> {
-        if !self.model_path.exists() {
+        self.validate_with_model_info().map(|_| ())
+    }
+
+    /// 验证配置并返回解析出的模型元数据
+    fn validate_with_model_info(&self) -> SttResult<ModelInfo> {
+        let model_path = resolve_model_path(&self.model_path)?;
+        if !model_path.exists() {
             return Err(SttError::ModelLoadError(format!(
                 "模型文件不存在: {}",
-                self.model_path.display()
+                model_path.display()
             )));
         }
+        let model_info = read_model_info(&model_path)?;
 
         if self.n_threads <= 0 {
             return Err(SttError::ConfigError("线程数必须大于0".to_string()));
@@ -134,13 +662,64 @@ impl WhisperConfig {
             ));
         }
 
+        if self.language_detection_seconds <= 0.0 {
+            return Err(SttError::ConfigError("语言检测时长必须大于0".to_string()));
+        }
+
+        if !(0.0..=1.0).contains(&self.temperature_increment) {
+            return Err(SttError::ConfigError(
+                "温度回退步长必须在0.0-1.0之间".to_string(),
+            ));
+        }
+
+        if self.compression_ratio_threshold.is_some_and(|t| t <= 0.0) {
+            return Err(SttError::ConfigError("压缩比阈值必须大于0".to_string()));
+        }
+
+        if self
+            .no_speech_threshold
+            .is_some_and(|t| !(0.0..=1.0).contains(&t))
+        {
+            return Err(SttError::ConfigError(
+                "无语音概率阈值必须在0.0-1.0之间".to_string(),
+            ));
+        }
+
+        match self.sampling_strategy {
+            DecodingStrategy::Greedy { best_of } if best_of < 1 => {
+                return Err(SttError::ConfigError("best_of必须大于0".to_string()));
+            }
+            DecodingStrategy::BeamSearch { beam_size, .. } if beam_size < 1 => {
+                return Err(SttError::ConfigError("束宽必须大于0".to_string()));
+            }
+            _ => {}
+        }
+
         if !(0.0..=1.0).contains(&self.vad_threshold) {
             return Err(SttError::ConfigError(
                 "VAD阈值必须在0.0-1.0之间".to_string(),
             ));
         }
 
-        Ok(())
+        if self.gpu_device < 0 {
+            return Err(SttError::ConfigError("GPU设备编号不能为负数".to_string()));
+        }
+
+        if self.flash_attn && self.dtw_preset.is_some() {
+            return Err(SttError::ConfigError(
+                "flash attention 与 DTW 时间戳不能同时启用".to_string(),
+            ));
+        }
+
+        if matches!(self.dtw_preset, Some(DtwPreset::TopMost { n_top }) if n_top < 1) {
+            return Err(SttError::ConfigError("DTW n_top必须大于0".to_string()));
+        }
+
+        if self.max_concurrent_inference == 0 {
+            return Err(SttError::ConfigError("推理并发数必须大于0".to_string()));
+        }
+
+        Ok(model_info)
     }
 }
 
@@ -155,6 +734,46 @@ pub struct TranscriptionSegment {
     pub text: String,
     /// 置信度（0.0-1.0）
     pub confidence: f32,
+    /// 词级时间戳（需开启 `WhisperConfig::word_timestamps`）
+    #[serde(default)]
+    pub words: Vec<TranscriptionWord>,
+}
+
+impl TranscriptionSegment {
+    /// 将段与词级时间戳整体平移
+    pub(crate) fn offset_by(&mut self, offset_ms: u64) {
+        self.start_time += offset_ms;
+        self.end_time += offset_ms;
+        for word in &mut self.words {
+            word.start_time += offset_ms;
+            word.end_time += offset_ms;
+        }
+    }
+
+    /// 段文本中是否存在重复循环
+    pub fn has_repetition(&self) -> bool {
+        detect_repetition(&self.text).is_some()
+    }
+}
+
+/// 单种语言的概率
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageProbability {
+    /// 语言代码（如 "zh", "en"）
+    pub language: String,
+    /// 概率（0.0-1.0）
+    pub probability: f32,
+}
+
+/// 语言检测结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageDetection {
+    /// 检测到的语言代码
+    pub language: String,
+    /// 检测到的语言的概率
+    pub probability: f32,
+    /// 概率最高的若干种语言（按概率降序）
+    pub top_languages: Vec<LanguageProbability>,
 }
 
 /// 转录结果
@@ -164,12 +783,21 @@ pub struct TranscriptionResult {
     pub text: String,
     /// 检测到的语言
     pub language: Option<String>,
+    /// 语言检测详情（配置中指定了语言时为 None）
+    #[serde(default)]
+    pub language_detection: Option<LanguageDetection>,
     /// 分段结果
     pub segments: Vec<TranscriptionSegment>,
     /// 处理时长（毫秒）
     pub processing_time: u64,
     /// 音频时长（毫秒）
     pub audio_duration: u64,
+    /// 逐段质量决策（温度回退、无语音丢弃等）
+    #[serde(default)]
+    pub decisions: Vec<SegmentDecision>,
+    /// 被 VAD 跳过、未送入模型解码的音频时长（毫秒）
+    #[serde(default)]
+    pub skipped_duration: u64,
 }
 
 impl TranscriptionResult {
@@ -209,29 +837,121 @@ impl TranscriptionResult {
         TranscriptionResult {
             text: filtered_text,
             language: self.language.clone(),
+            language_detection: self.language_detection.clone(),
             segments: filtered_segments,
             processing_time: self.processing_time,
             audio_duration: self.audio_duration,
+            decisions: self.decisions.clone(),
+            skipped_duration: self.skipped_duration,
+        }
+    }
+}
+
+/// 单次解码的输入
+struct DecodeInput<'a> {
+    /// 16kHz 单声道样本
+    samples: &'a [f32],
+    /// 提示 token
+    prompt_tokens: &'a [WhisperTokenId],
+    /// 解码语言（None 表示由 whisper 自动检测）
+    language: Option<&'a str>,
+    /// 取消令牌
+    cancellation: Option<&'a CancellationToken>,
+}
+
+impl DecodeInput<'_> {
+    /// 已请求取消时返回 [`SttError::Cancelled`]
+    fn check_cancelled(&self) -> SttResult<()> {
+        self.cancellation.map_or(Ok(()), CancellationToken::check)
+    }
+}
+
+/// 一次解码提取出的段与决策（时间相对于解码输入）
+struct DecodedSegments {
+    /// 保留的段
+    segments: Vec<TranscriptionSegment>,
+    /// 逐段质量决策
+    decisions: Vec<SegmentDecision>,
+    /// 实际解码使用的语言
+    language: Option<String>,
+}
+
+/// 一次解码尝试在段级别的附加输出
+struct AttemptOutput {
+    /// 置信度
+    confidence: f32,
+    /// 词级时间戳
+    words: Vec<TranscriptionWord>,
+}
+
+/// 推理并发限制
+///
+/// 推理在 tokio 的阻塞线程池上执行，不占用异步执行线程；同时运行的推理数不超过上限。
+#[derive(Debug, Clone)]
+pub(crate) struct InferenceLimiter {
+    permits: Arc<Semaphore>,
+}
+
+impl InferenceLimiter {
+    pub(crate) fn new(max_concurrent: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_concurrent.max(1))),
         }
     }
+
+    /// 等待空闲名额后在阻塞线程上执行 `job`
+    ///
+    /// 名额随任务一起移动到阻塞线程，调用方放弃等待结果时，正在执行的推理仍占用名额直到结束。
+    pub(crate) async fn run<T, F>(&self, job: F) -> SttResult<T>
+    where
+        F: FnOnce() -> SttResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let permit = Arc::clone(&self.permits)
+            .acquire_owned()
+            .await
+            .map_err(|e| SttError::TranscriptionError(format!("推理队列已关闭: {e}")))?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            job()
+        })
+        .await
+        .map_err(|e| SttError::TranscriptionError(format!("推理线程异常退出: {e}")))?
+    }
 }
 
 /// Whisper 转录器
+///
+/// 克隆开销很小：克隆共享同一个模型上下文、配置与推理并发限制，可在多个任务间传递。
+#[derive(Clone)]
 pub struct WhisperTranscriber {
     /// Whisper模型上下文
     context: Arc<WhisperContext>,
     /// Whisper配置
-    config: WhisperConfig,
+    config: Arc<WhisperConfig>,
+    /// 推理并发限制
+    limiter: InferenceLimiter,
+    /// 从模型文件头解析出的元数据
+    model_info: Arc<ModelInfo>,
 }
 
 impl WhisperTranscriber {
     /// 创建新的转录器
-    pub fn new(config: WhisperConfig) -> SttResult<Self> {
-        config.validate()?;
-
-        info!("加载Whisper模型: {}", config.model_path.display());
-
-        let ctx_params = WhisperContextParameters::default();
+    pub fn new(mut config: WhisperConfig) -> SttResult<Self> {
+        // 模型名称只解析并校验一次，之后按解析出的路径加载
+        config.model_path = resolve_model_path(&config.model_path)?;
+        let mut model_info = config.validate_with_model_info()?;
+        model_info.backend = Some(config.backend());
+
+        info!("加载{model_info}");
+
+        let ctx_params = config.context_params();
+        info!(
+            "推理后端: {}，flash attention: {}",
+            config.backend(),
+            config.flash_attn
+        );
         let context = WhisperContext::new_with_params(
             config.model_path.to_string_lossy().as_ref(),
             ctx_params,
@@ -242,7 +962,9 @@ impl WhisperTranscriber {
 
         Ok(Self {
             context: Arc::new(context),
-            config,
+            limiter: InferenceLimiter::new(config.max_concurrent_inference),
+            config: Arc::new(config),
+            model_info: Arc::new(model_info),
         })
     }
 
@@ -251,171 +973,321 @@ impl WhisperTranscriber {
         &self,
         audio_path: P,
     ) -> SttResult<TranscriptionResult> {
-        let audio_path = audio_path.as_ref();
+        self.transcribe_file_with_options(audio_path, TranscribeOptions::default())
+            .await
+    }
 
-        info!("开始转录文件: {}", audio_path.display());
+    /// 从文件转录，支持取消与进度回调
+    ///
+    /// 取消后返回 [`SttError::Cancelled`]；进度回调在推理线程上调用。
+    pub async fn transcribe_file_with_options<P: AsRef<Path>>(
+        &self,
+        audio_path: P,
+        options: TranscribeOptions,
+    ) -> SttResult<TranscriptionResult> {
+        options.check_cancelled()?;
+        // 格式转换与读取不占用推理名额，避免慢速转换阻塞其他转录
+        let audio_data = load_audio_file(audio_path.as_ref()).await?;
+
+        let transcriber = self.clone();
+        self.limiter
+            .run(move || {
+                options.check_cancelled()?;
+                let mut state = transcriber.create_state()?;
+                transcriber.transcribe_with_state(&mut state, &audio_data, None, &options)
+            })
+            .await
+    }
 
-        // 确保输入音频转为 Whisper 兼容（mono/16k/WAV）
-        let converted =
-            audio_lib::ensure_whisper_compatible(audio_path, None).map_err(|e| match e {
-                audio_lib::AudioError::FileNotFound(path) => {
-                    SttError::AudioProcessingError(format!("音频文件不存在: {path}"))
-                }
-                audio_lib::AudioError::NotAFile(path) => {
-                    SttError::AudioProcessingError(format!("路径不是音频文件: {path}"))
-                }
-                audio_lib::AudioError::FormatNotSupported { format, supported } => {
-                    SttError::AudioProcessingError(format!(
-                        "音频格式不支持: {format}, 支持的格式: {supported}"
-                    ))
-                }
-                audio_lib::AudioError::SampleRateMismatch { expected, actual } => {
-                    SttError::AudioProcessingError(format!(
-                        "采样率不匹配: 期望 {expected}, 实际 {actual}"
-                    ))
-                }
-                audio_lib::AudioError::ChannelMismatch { expected, actual } => {
-                    SttError::AudioProcessingError(format!(
-                        "通道数不匹配: 期望 {expected}, 实际 {actual}"
-                    ))
-                }
-                audio_lib::AudioError::FfmpegConfig(msg)
-                | audio_lib::AudioError::FfmpegExecution(msg) => {
-                    SttError::AudioProcessingError(format!("FFmpeg 错误: {msg}"))
-                }
-                audio_lib::AudioError::DecodeError { reason } => {
-                    SttError::AudioProcessingError(format!("音频解码失败: {reason}"))
-                }
-                audio_lib::AudioError::InvalidSampleRate { rate, min, max } => {
-                    SttError::AudioProcessingError(format!(
-                        "无效采样率: {rate}, 有效范围: {min}-{max}"
-                    ))
-                }
-                audio_lib::AudioError::ResampleError(msg) => {
-                    SttError::AudioProcessingError(format!("重采样失败: {msg}"))
-                }
-                _ => SttError::AudioProcessingError(format!("音频处理失败: {e}")),
-            })?;
+    /// 转录音频数据
+    pub async fn transcribe_audio_data(
+        &self,
+        audio_data: &AudioData,
+    ) -> SttResult<TranscriptionResult> {
+        self.transcribe_audio_data_with_context(audio_data, None)
+            .await
+    }
 
-        // 读取 WAV 到内存（内部工具）
-        let audio_data = crate::audio::utils::read_wav_file(&converted.path)?;
+    /// 转录音频数据，并以前文作为解码上下文
+    ///
+    /// `context` 通常是上一块音频已确认的转录文本（长音频分块、流式场景），
+    /// 会追加在配置的初始提示与词汇表之后；超出提示窗口时优先截断前文开头，
+    /// 保证词汇表在每一块中都生效。
+    pub async fn transcribe_audio_data_with_context(
+        &self,
+        audio_data: &AudioData,
+        context: Option<&str>,
+    ) -> SttResult<TranscriptionResult> {
+        self.transcribe_audio_data_with_options(audio_data, context, TranscribeOptions::default())
+            .await
+    }
 
-        // 转录音频数据
-        self.transcribe_audio_data(&audio_data).await
+    /// 转录音频数据，支持前文、取消与进度回调
+    pub async fn transcribe_audio_data_with_options(
+        &self,
+        audio_data: &AudioData,
+        context: Option<&str>,
+        options: TranscribeOptions,
+    ) -> SttResult<TranscriptionResult> {
+        // 推理在阻塞线程上执行，避免长时间解码占用异步执行线程
+        let transcriber = self.clone();
+        let audio_data = audio_data.clone();
+        let context = context.map(str::to_string);
+        self.limiter
+            .run(move || {
+                options.check_cancelled()?;
+                let mut state = transcriber.create_state()?;
+                transcriber.transcribe_with_state(
+                    &mut state,
+                    &audio_data,
+                    context.as_deref(),
+                    &options,
+                )
+            })
+            .await
     }
 
-    /// 转录音频数据
-    pub async fn transcribe_audio_data(
+    /// 使用给定的解码状态转录音频数据（同步执行）
+    pub(crate) fn transcribe_with_state(
         &self,
+        state: &mut WhisperState,
         audio_data: &AudioData,
+        context: Option<&str>,
+        options: &TranscribeOptions,
     ) -> SttResult<TranscriptionResult> {
         let start_time = std::time::Instant::now();
 
-        // 检查音频格式兼容性
-        if !audio_data.is_whisper_compatible() {
-            warn!("音频格式不兼容Whisper，建议转换为16kHz单声道");
-        }
-
-        // 准备音频数据
-        let audio_samples = self.prepare_audio_samples(audio_data)?;
-
-        // VAD 检测（如果启用）
-        let mut processed_samples = audio_samples.clone();
-        let audio_duration = audio_data.duration();
-        let mut audio_duration_adj = audio_duration;
-        let mut start_offset_ms = 0;
-        
-        if self.config.enable_vad {
-            let vad = SimpleVad::new(self.config.vad_threshold);
-            
-            // 检测语音段
-            let speech_segments = vad.detect_speech_segments(&audio_samples);
-            
-            if speech_segments.is_empty() {
-                info!("VAD检测到无语音活动，跳过转录");
-                return Ok(TranscriptionResult {
-                    text: String::new(),
-                    language: self.config.language.clone(),
-                    segments: Vec::new(),
-                    processing_time: start_time.elapsed().as_millis() as u64,
-                    audio_duration: (audio_duration * 1000.0) as u64,
-                });
-            } else {
-                // 裁剪开头的静音部分，使用第一个语音段
-                let first_segment = speech_segments.first().unwrap();
-                
-                if first_segment.0 > 0 {
-                    // 裁剪音频样本
-                    processed_samples = audio_samples[first_segment.0..].to_vec();
-                    
-                    // 计算裁剪后的音频时长
-                    let sample_rate = audio_data.config.sample_rate as f64;
-                    start_offset_ms = (first_segment.0 as f64 / sample_rate * 1000.0) as u64;
-                    audio_duration_adj = audio_duration - (first_segment.0 as f64 / sample_rate);
-                    
-                    info!(
-                        "VAD裁剪掉开头静音部分，偏移量: {}毫秒，原长度: {:.2}秒，裁剪后长度: {:.2}秒",
-                        start_offset_ms, audio_duration, audio_duration_adj
-                    );
+        // 准备音频数据（必要时下混为单声道并重采样到 16kHz）
+        let audio_samples = Self::prepare_audio_samples(audio_data, self.config.resample_quality)?;
+        let audio_duration_ms = (audio_data.duration() * 1000.0) as u64;
+
+        let mut result = TranscriptionResult {
+            text: String::new(),
+            language: self.config.fixed_language().map(str::to_string),
+            language_detection: None,
+            segments: Vec::new(),
+            processing_time: 0,
+            audio_duration: audio_duration_ms,
+            decisions: Vec::new(),
+            skipped_duration: 0,
+        };
+
+        // VAD 检测（如果启用），确定需要解码的区域
+        let regions = self.speech_regions(&audio_samples);
+        if regions.is_empty() {
+            info!("VAD检测到无语音活动，跳过转录");
+            result.skipped_duration = audio_duration_ms;
+            result.processing_time = start_time.elapsed().as_millis() as u64;
+            return Ok(result);
+        }
+
+        let decoded_samples: usize = regions.iter().map(|(start, end)| end - start).sum();
+        result.skipped_duration = samples_to_ms(audio_samples.len() - decoded_samples);
+        info!(
+            "开始Whisper推理,音频长度: {:.2}秒",
+            decoded_samples as f64 / WHISPER_SAMPLE_RATE as f64
+        );
+
+        let prompt_tokens = self.prompt_tokens(context)?;
+
+        // 未指定语言时先对第一个语音区域做语言识别，后续解码（含温度回退）都使用检测结果
+        let language_detection = match self.config.fixed_language() {
+            Some(_) => None,
+            None if !self.context.is_multilingual() => None,
+            None => {
+                let (start, end) = regions[0];
+                match self.detect_language_with_state(state, &audio_samples[start..end]) {
+                    Ok(detection) => {
+                        info!(
+                            "检测到语言: {} (概率 {:.2})",
+                            detection.language, detection.probability
+                        );
+                        Some(detection)
+                    }
+                    Err(e) => {
+                        warn!("语言检测失败，交由Whisper自动检测: {e}");
+                        None
+                    }
                 }
-                
-                debug!("VAD检测到{}个语音段，继续转录", speech_segments.len());
             }
-        }
-        
-        info!("开始Whisper推理,音频长度: {:.2}秒", audio_duration_adj);
-        
-        // 创建Whisper状态
-        let mut state = self
-            .context
-            .create_state()
-            .map_err(|e| SttError::WhisperError(format!("创建Whisper状态失败: {e}")))?;
-        
-        // 设置参数
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-        
-        // 配置参数
-        params.set_n_threads(self.config.n_threads);
-        params.set_translate(self.config.translate);
-        params.set_print_timestamps(self.config.print_timestamps);
-        params.set_print_progress(self.config.print_progress);
-        params.set_print_special(self.config.print_special);
-        params.set_temperature(self.config.temperature);
-        
-        // 设置语言
-        if let Some(ref language) = self.config.language {
-            params.set_language(Some(language.as_str()));
-        }
-        
-        // 注意：whisper-rs可能不支持set_initial_prompt方法
-        // 如果需要初始提示功能，请查阅whisper-rs文档获取正确的API
-        // if let Some(prompt) = &self.config.initial_prompt {
-        //     // params.set_initial_prompt(Some(prompt.as_str()));
-        // }
-        
-        // 执行转录
-        state
-            .full(params, &processed_samples)
-            .map_err(|e| SttError::TranscriptionError(format!("Whisper转录失败: {e}")))?;
-        
-        // 提取结果
-        let mut result = self.extract_transcription_result(&state, audio_duration_adj, start_time)?;
-        
-        // 调整时间戳以反映裁剪后的音频
-        if start_offset_ms > 0 {
-            for segment in &mut result.segments {
-                segment.start_time += start_offset_ms;
-                segment.end_time += start_offset_ms;
+        };
+        let language = self
+            .config
+            .fixed_language()
+            .or(language_detection.as_ref().map(|d| d.language.as_str()));
+
+        let progress = ProgressTracker::new(options.on_progress.clone(), decoded_samples);
+        let mut done_samples = 0;
+
+        for &(region_start, region_end) in &regions {
+            options.check_cancelled()?;
+
+            // whisper.cpp 拒绝短于 1 秒的输入，用静音补齐
+            let mut padded = Vec::new();
+            let samples = if region_end - region_start < MIN_DECODE_SAMPLES {
+                padded.extend_from_slice(&audio_samples[region_start..region_end]);
+                padded.resize(MIN_DECODE_SAMPLES, 0.0);
+                &padded[..]
+            } else {
+                &audio_samples[region_start..region_end]
+            };
+
+            let input = DecodeInput {
+                samples,
+                prompt_tokens: &prompt_tokens,
+                language,
+                cancellation: options.cancellation.as_ref(),
+            };
+
+            // 设置参数（初始提示 + 词汇表 + 前文）
+            let mut params = self.full_params(&input, self.config.temperature);
+            let region_samples = region_end - region_start;
+            if progress.is_enabled() {
+                let progress = progress.clone();
+                let done = done_samples;
+                params.set_progress_callback_safe(move |percent: i32| {
+                    progress.report(done, region_samples, percent as f32)
+                });
+            }
+
+            // 执行转录（被取消时 whisper 中止解码，返回取消错误）
+            let decode_result = state.full(params, input.samples);
+            input.check_cancelled()?;
+            decode_result
+                .map_err(|e| SttError::TranscriptionError(format!("Whisper转录失败: {e}")))?;
+            done_samples += region_samples;
+            progress.report(done_samples, 0, 0.0);
+
+            // 提取结果（逐段质量检查，必要时温度回退）
+            let decoded = self.extract_transcription_result(state, &input)?;
+
+            // 调整时间戳以映射回原始音频时间轴
+            let offset_ms = samples_to_ms(region_start);
+            for mut segment in decoded.segments {
+                segment.offset_by(offset_ms);
+                if !result.text.is_empty() {
+                    result.text.push(' ');
+                }
+                result.text.push_str(&segment.text);
+                result.segments.push(segment);
             }
+            result
+                .decisions
+                .extend(decoded.decisions.into_iter().map(|mut decision| {
+                    decision.start_time += offset_ms;
+                    decision.end_time += offset_ms;
+                    decision
+                }));
+            if result.language.is_none() {
+                result.language = decoded.language;
+            }
+        }
+
+        result.language_detection = language_detection;
+        self.config.output_script.apply(&mut result);
+        if let Some(post_process) = &self.config.post_process {
+            post_process.apply(&mut result);
         }
+        result.processing_time = start_time.elapsed().as_millis() as u64;
+        progress.finish();
 
         info!("转录完成，实时因子: {:.2}x", result.real_time_factor());
 
         Ok(result)
     }
 
+    /// 根据 VAD 配置确定需要解码的样本区域，无语音时返回空
+    fn speech_regions(&self, samples: &[f32]) -> Vec<(usize, usize)> {
+        if !self.config.enable_vad {
+            return vec![(0, samples.len())];
+        }
+
+        let vad = SimpleVad::new(self.config.vad_threshold);
+        match self.config.vad_mode {
+            VadMode::TrimLeading => {
+                // 检测语音段，只裁剪第一个语音段之前的静音
+                let speech_segments = vad.detect_speech_segments(samples);
+                let Some(&(first_start, _)) = speech_segments.first() else {
+                    return Vec::new();
+                };
+
+                if first_start > 0 {
+                    info!(
+                        "VAD裁剪掉开头静音部分，偏移量: {}毫秒，原长度: {:.2}秒，裁剪后长度: {:.2}秒",
+                        samples_to_ms(first_start),
+                        samples.len() as f64 / WHISPER_SAMPLE_RATE as f64,
+                        (samples.len() - first_start) as f64 / WHISPER_SAMPLE_RATE as f64
+                    );
+                }
+                debug!("VAD检测到{}个语音段，继续转录", speech_segments.len());
+
+                vec![(first_start, samples.len())]
+            }
+            VadMode::SpeechOnly => {
+                let regions = vad.speech_regions(
+                    samples,
+                    self.config.vad_padding_ms,
+                    self.config.vad_merge_gap_ms,
+                );
+                debug!("VAD检测到{}个语音区域，只转录语音部分", regions.len());
+
+                regions
+            }
+        }
+    }
+
+    /// 创建 Whisper 解码状态
+    pub(crate) fn create_state(&self) -> SttResult<WhisperState> {
+        self.context
+            .create_state()
+            .map_err(|e| SttError::WhisperError(format!("创建Whisper状态失败: {e}")))
+    }
+
+    /// 构建解码参数
+    ///
+    /// whisper.cpp 内置的温度回退与静音窗口跳过均被关闭，改为逐段检查，
+    /// 以便把每次尝试记录到转录结果上。
+    fn full_params<'a>(&self, input: &DecodeInput<'a>, temperature: f32) -> FullParams<'a, 'a> {
+        let mut params = FullParams::new(self.config.sampling_strategy.to_sampling_strategy());
+
+        params.set_n_threads(self.config.n_threads);
+        params.set_translate(self.config.translate);
+        params.set_print_timestamps(self.config.print_timestamps);
+        params.set_print_progress(self.config.print_progress);
+        params.set_print_special(self.config.print_special);
+        params.set_temperature(temperature);
+        params.set_temperature_inc(0.0);
+        params.set_no_speech_thold(1.0);
+        params.set_token_timestamps(self.config.word_timestamps);
+
+        // 设置语言（None 时交由 whisper 自动检测）
+        params.set_language(Some(input.language.unwrap_or("auto")));
+
+        // 设置提示词
+        if !input.prompt_tokens.is_empty() {
+            params.set_tokens(input.prompt_tokens);
+        }
+
+        // 取消时让 whisper 尽快中止解码
+        if let Some(token) = input.cancellation {
+            let token = token.clone();
+            // whisper-rs 的中止回调按闭包类型本身解引用用户数据，
+            // 这里直接传入装箱的闭包，保证类型与其内部存储一致
+            let abort: Box<dyn FnMut() -> bool> = Box::new(move || token.is_cancelled());
+            params.set_abort_callback_safe::<_, Box<dyn FnMut() -> bool>>(Some(abort));
+        }
+
+        params
+    }
+
     /// 准备音频样本数据
-    fn prepare_audio_samples(&self, audio_data: &AudioData) -> SttResult<Vec<f32>> {
+    ///
+    /// 输出始终为 16kHz 单声道。重采样会补偿滤波器延迟，
+    /// 因此转录得到的时间戳与调用方原始音频的时间轴一致。
+    pub(crate) fn prepare_audio_samples(
+        audio_data: &AudioData,
+        quality: ResampleQuality,
+    ) -> SttResult<Vec<f32>> {
         let mut samples = audio_data.samples.clone();
 
         // 转换为单声道（如果需要）
@@ -426,32 +1298,68 @@ impl WhisperTranscriber {
         }
 
         // Whisper期望的采样率是16kHz
-        if audio_data.config.sample_rate != 16000 {
-            warn!(
-                "音频采样率为{}Hz，Whisper推荐16kHz",
-                audio_data.config.sample_rate
+        let sample_rate = audio_data.config.sample_rate;
+        if sample_rate != WHISPER_SAMPLE_RATE {
+            debug!(
+                "音频采样率为{sample_rate}Hz，重采样到{WHISPER_SAMPLE_RATE}Hz (质量: {quality:?})"
             );
-            // 这里可以添加重采样逻辑
+            let resampler = AdvancedResampler::new(sample_rate, WHISPER_SAMPLE_RATE, 1, quality)?;
+            samples = resampler.resample_with_quality(&samples)?;
         }
 
         Ok(samples)
     }
 
+    /// 将提示词与前文转换为 prompt token
+    ///
+    /// whisper 只保留最近 `n_text_ctx / 2` 个提示 token，过长时会丢弃开头，
+    /// 因此这里预先截断前文，确保初始提示与词汇表不会被挤出窗口。
+    fn prompt_tokens(&self, context: Option<&str>) -> SttResult<Vec<WhisperTokenId>> {
+        let max_tokens = (self.context.n_text_ctx() / 2).max(0) as usize;
+
+        let mut tokens = match self.config.render_prompt() {
+            Some(prompt) => self.tokenize(&prompt)?,
+            None => Vec::new(),
+        };
+        if tokens.len() > max_tokens {
+            warn!(
+                "提示词过长({}个token)，超出部分将被截断: 上限{}",
+                tokens.len(),
+                max_tokens
+            );
+            tokens.truncate(max_tokens);
+        }
+
+        if let Some(context) = context.map(str::trim).filter(|c| !c.is_empty()) {
+            let context_tokens = self.tokenize(&format!(" {context}"))?;
+            let budget = max_tokens - tokens.len();
+            let skip = context_tokens.len().saturating_sub(budget);
+            tokens.extend_from_slice(&context_tokens[skip..]);
+        }
+
+        Ok(tokens)
+    }
+
+    /// 文本分词
+    fn tokenize(&self, text: &str) -> SttResult<Vec<WhisperTokenId>> {
+        // token 数不会超过字节数，按字节数分配避免 whisper_tokenize 截断
+        self.context
+            .tokenize(text, text.len() + 1)
+            .map_err(|e| SttError::WhisperError(format!("提示词分词失败: {e}")))
+    }
+
     /// 提取转录结果
     fn extract_transcription_result(
         &self,
-        state: &WhisperState,
-        audio_duration: f64,
-        start_time: std::time::Instant,
-    ) -> SttResult<TranscriptionResult> {
-        let processing_time = start_time.elapsed().as_millis() as u64;
-        let audio_duration_ms = (audio_duration * 1000.0) as u64;
+        state: &mut WhisperState,
+        input: &DecodeInput,
+    ) -> SttResult<DecodedSegments> {
+        let thresholds = self.config.quality_thresholds();
 
         let num_segments = state.full_n_segments();
 
-        let mut segments = Vec::new();
-        let mut full_text = String::new();
-
+        // 温度回退会复用同一个解码状态，先读出首次解码的全部段与语言
+        let mut first_pass = Vec::new();
         for i in 0..num_segments {
             // 获取段对象
             let Some(segment) = state.get_segment(i) else {
@@ -468,32 +1376,282 @@ impl WhisperTranscriber {
             let start_time = (segment.start_timestamp() as u64) * 10;
             let end_time = (segment.end_timestamp() as u64) * 10;
 
-            // 计算置信度（简化实现）
-            let confidence = self.calculate_segment_confidence(state, i)?;
+            let (logprob_sum, n_text_tokens) = self.segment_logprob(&segment);
+            let first = Self::build_attempt(
+                &thresholds,
+                self.config.temperature,
+                segment_text,
+                logprob_sum / n_text_tokens.max(1) as f32,
+                segment.no_speech_probability(),
+            );
+            let first_output = AttemptOutput {
+                confidence: self.calculate_segment_confidence(state, i)?,
+                words: self.segment_words(&segment, 0, end_time),
+            };
+            first_pass.push((start_time, end_time, first, first_output));
+        }
+
+        // 实际解码使用的语言
+        let language = input
+            .language
+            .or_else(|| get_lang_str(state.full_lang_id_from_state()))
+            .map(str::to_string);
+
+        let mut segments = Vec::new();
+        let mut decisions = Vec::new();
+        for (start_time, end_time, first, first_output) in first_pass {
+            let no_speech = thresholds.is_no_speech(first.no_speech_prob, first.avg_logprob);
+            let (decision, output) = if no_speech || first.passed() {
+                if no_speech {
+                    debug!(
+                        "丢弃无语音段 [{start_time}-{end_time}ms]: 无语音概率 {:.2}, 平均对数概率 {:.2}",
+                        first.no_speech_prob, first.avg_logprob
+                    );
+                }
+                let decision = SegmentDecision {
+                    start_time,
+                    end_time,
+                    attempts: vec![first],
+                    verdict: if no_speech {
+                        SegmentVerdict::DroppedNoSpeech
+                    } else {
+                        SegmentVerdict::Accepted
+                    },
+                };
+                (decision, first_output)
+            } else {
+                self.decode_with_fallback(
+                    state,
+                    &thresholds,
+                    input,
+                    (start_time, end_time),
+                    first,
+                    first_output,
+                )?
+            };
+
+            if let Some(chosen) = decision.chosen() {
+                segments.push(TranscriptionSegment {
+                    start_time,
+                    end_time,
+                    text: chosen.text.clone(),
+                    confidence: output.confidence,
+                    words: output.words,
+                });
+            }
+            decisions.push(decision);
+        }
+
+        Ok(DecodedSegments {
+            segments,
+            decisions,
+            language,
+        })
+    }
+
+    /// 提高温度重新解码未通过质量检查的段
+    ///
+    /// 按 `temperature_increment` 逐级提高温度，对该段的音频单独解码，
+    /// 取第一次通过检查的结果；全部未通过时保留平均对数概率最高的一次。
+    /// 返回段决策与最终采用结果的置信度、词级时间戳。
+    /// 每次回退都复用调用方的解码状态，不额外分配 whisper 缓冲区。
+    fn decode_with_fallback(
+        &self,
+        state: &mut WhisperState,
+        thresholds: &QualityThresholds,
+        input: &DecodeInput,
+        (start_time, end_time): (u64, u64),
+        first: DecodeAttempt,
+        first_output: AttemptOutput,
+    ) -> SttResult<(SegmentDecision, AttemptOutput)> {
+        let samples = input.samples;
+        let samples_per_ms = WHISPER_SAMPLE_RATE as usize / 1000;
+        let from = (start_time as usize * samples_per_ms).min(samples.len());
+        let to = (end_time as usize * samples_per_ms).clamp(from, samples.len());
+
+        // whisper.cpp 拒绝短于 1 秒的输入，用静音补齐
+        let mut segment_samples = samples[from..to].to_vec();
+        if segment_samples.len() < MIN_DECODE_SAMPLES {
+            segment_samples.resize(MIN_DECODE_SAMPLES, 0.0);
+        }
+
+        debug!(
+            "段 [{start_time}-{end_time}ms] 未通过质量检查 {:?}，开始温度回退",
+            first.issues
+        );
+
+        let mut attempts = vec![first];
+        let mut outputs = vec![first_output];
+        let mut verdict = SegmentVerdict::Unresolved;
+
+        let increment = self.config.temperature_increment;
+        let temperatures = (1..)
+            .map(|step| self.config.temperature + increment * step as f32)
+            .take_while(|t| increment > 0.0 && *t <= 1.0 + f32::EPSILON);
+        for temperature in temperatures {
+            input.check_cancelled()?;
+            let mut params = self.full_params(input, temperature);
+            params.set_single_segment(true);
+            let decode_result = state.full(params, &segment_samples);
+            input.check_cancelled()?;
+            decode_result
+                .map_err(|e| SttError::TranscriptionError(format!("温度回退解码失败: {e}")))?;
+
+            let mut text = String::new();
+            let mut logprob_sum = 0.0;
+            let mut n_text_tokens = 0;
+            let mut no_speech_prob = 0.0;
+            let mut confidence_sum = 0.0;
+            let mut words = Vec::new();
+            let num_segments = state.full_n_segments();
+            for i in 0..num_segments {
+                let Some(segment) = state.get_segment(i) else {
+                    continue;
+                };
+                if i == 0 {
+                    no_speech_prob = segment.no_speech_probability();
+                }
+                let (sum, count) = self.segment_logprob(&segment);
+                logprob_sum += sum;
+                n_text_tokens += count;
+                confidence_sum += self.calculate_segment_confidence(state, i)?;
+                // 回退解码的时间相对于段起点
+                words.extend(self.segment_words(&segment, start_time, end_time));
+                let segment_text = segment.to_str().unwrap_or("").trim();
+                if !segment_text.is_empty() {
+                    if !text.is_empty() {
+                        text.push(' ');
+                    }
+                    text.push_str(segment_text);
+                }
+            }
+
+            if text.is_empty() || n_text_tokens == 0 {
+                debug!("温度 {temperature:.1} 解码结果为空，跳过");
+                continue;
+            }
+
+            let attempt = Self::build_attempt(
+                thresholds,
+                temperature,
+                text,
+                logprob_sum / n_text_tokens as f32,
+                no_speech_prob,
+            );
+            let passed = attempt.passed();
+            attempts.push(attempt);
+            outputs.push(AttemptOutput {
+                confidence: confidence_sum / num_segments.max(1) as f32,
+                words,
+            });
+
+            if passed {
+                verdict = SegmentVerdict::Fallback;
+                break;
+            }
+        }
+
+        let decision = SegmentDecision {
+            start_time,
+            end_time,
+            attempts,
+            verdict,
+        };
+        let chosen_index = match verdict {
+            SegmentVerdict::Fallback => decision.attempts.len() - 1,
+            _ => decision
+                .attempts
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.avg_logprob.total_cmp(&b.avg_logprob))
+                .map_or(0, |(index, _)| index),
+        };
+
+        if verdict == SegmentVerdict::Unresolved {
+            warn!("段 [{start_time}-{end_time}ms] 在所有温度下均未通过质量检查，保留最佳结果");
+        }
+
+        Ok((decision, outputs.swap_remove(chosen_index)))
+    }
+
+    /// 构建一次解码尝试并执行质量检查
+    fn build_attempt(
+        thresholds: &QualityThresholds,
+        temperature: f32,
+        text: String,
+        avg_logprob: f32,
+        no_speech_prob: f32,
+    ) -> DecodeAttempt {
+        let ratio = compression_ratio(&text);
+        let issues = thresholds.check(&text, avg_logprob, ratio);
+        DecodeAttempt {
+            temperature,
+            text,
+            avg_logprob,
+            compression_ratio: ratio,
+            no_speech_prob,
+            issues,
+        }
+    }
 
-            segments.push(TranscriptionSegment {
+    /// 提取段内的词级时间戳
+    ///
+    /// `offset_ms` 会加到 token 时间上，结果限制在 `end_ms` 之前；未开启词级时间戳时返回空。
+    fn segment_words(
+        &self,
+        segment: &WhisperSegment,
+        offset_ms: u64,
+        end_ms: u64,
+    ) -> Vec<TranscriptionWord> {
+        if !self.config.word_timestamps {
+            return Vec::new();
+        }
+
+        let eot = self.context.token_eot();
+        let mut pieces = Vec::new();
+        for token_index in 0..segment.n_tokens() {
+            let Some(token) = segment.get_token(token_index) else {
+                continue;
+            };
+            let data = token.token_data();
+            if data.id >= eot {
+                continue;
+            }
+            // token 时间单位为 10 毫秒；开启 DTW 时以对齐得到的时间点作为开始时间
+            let t0 = match self.config.dtw_preset {
+                Some(_) if data.t_dtw >= 0 => data.t_dtw,
+                _ => data.t0,
+            };
+            let start_time = (offset_ms + t0.max(0) as u64 * 10).min(end_ms);
+            let end_time = (offset_ms + data.t1.max(0) as u64 * 10).clamp(start_time, end_ms);
+            pieces.push(TokenPiece {
+                bytes: token.to_bytes().unwrap_or_default().to_vec(),
                 start_time,
                 end_time,
-                text: segment_text.clone(),
-                confidence,
+                probability: data.p,
             });
+        }
+
+        merge_tokens(&pieces)
+    }
 
-            if !full_text.is_empty() {
-                full_text.push(' ');
+    /// 统计段内文本 token 的对数概率之和与 token 数（不含时间戳等特殊 token）
+    fn segment_logprob(&self, segment: &WhisperSegment) -> (f32, usize) {
+        let eot = self.context.token_eot();
+        let mut sum = 0.0;
+        let mut count = 0;
+
+        for token_index in 0..segment.n_tokens() {
+            if let Some(token) = segment.get_token(token_index) {
+                let data = token.token_data();
+                if data.id < eot {
+                    sum += data.plog;
+                    count += 1;
+                }
             }
-            full_text.push_str(&segment_text);
         }
 
-        // 尝试检测语言
-        let language = self.detect_language(state);
-
-        Ok(TranscriptionResult {
-            text: full_text,
-            language,
-            segments,
-            processing_time,
-            audio_duration: audio_duration_ms,
-        })
+        (sum, count)
     }
 
     /// 计算段置信度（简化实现）
@@ -532,30 +1690,203 @@ impl WhisperTranscriber {
         }
     }
 
-    /// 检测语言
-    fn detect_language(&self, _state: &WhisperState) -> Option<String> {
-        // 简化实现，返回配置的语言或None
-        self.config.language.clone()
+    /// 识别音频的语种
+    ///
+    /// 只使用开头 `language_detection_seconds` 秒的音频，适合在完整转录前按语言分流。
+    /// 返回概率最高的语言以及前 `language_top_n` 种语言的概率分布。
+    pub async fn detect_language(&self, audio_data: &AudioData) -> SttResult<LanguageDetection> {
+        if !self.context.is_multilingual() {
+            return Err(SttError::WhisperError(
+                "模型不支持多语言，无法进行语言检测".to_string(),
+            ));
+        }
+
+        let transcriber = self.clone();
+        let audio_data = audio_data.clone();
+        let detection = self
+            .limiter
+            .run(move || {
+                let samples =
+                    Self::prepare_audio_samples(&audio_data, transcriber.config.resample_quality)?;
+                let mut state = transcriber.create_state()?;
+                transcriber.detect_language_with_state(&mut state, &samples)
+            })
+            .await?;
+
+        info!(
+            "检测到语言: {} (概率 {:.2})",
+            detection.language, detection.probability
+        );
+
+        Ok(detection)
     }
 
-    /// 获取模型信息
-    pub fn model_info(&self) -> SttResult<String> {
-        // 这里可以返回模型的详细信息
-        Ok(format!("Whisper模型: {}", self.config.model_path.display()))
+    /// 在给定状态上对开头一段音频做语言识别
+    fn detect_language_with_state(
+        &self,
+        state: &mut WhisperState,
+        samples: &[f32],
+    ) -> SttResult<LanguageDetection> {
+        let window = (self.config.language_detection_seconds * WHISPER_SAMPLE_RATE as f32) as usize;
+        let samples = &samples[..samples.len().min(window)];
+        let threads = self.config.n_threads.max(1) as usize;
+
+        state
+            .pcm_to_mel(samples, threads)
+            .map_err(|e| SttError::WhisperError(format!("计算梅尔频谱失败: {e}")))?;
+        let (_, probs) = state
+            .lang_detect(0, threads)
+            .map_err(|e| SttError::WhisperError(format!("语言检测失败: {e}")))?;
+
+        let top_languages: Vec<_> = rank_languages(&probs, self.config.language_top_n)
+            .into_iter()
+            .filter_map(|(id, probability)| {
+                get_lang_str(id).map(|language| LanguageProbability {
+                    language: language.to_string(),
+                    probability,
+                })
+            })
+            .collect();
+
+        let best = top_languages
+            .first()
+            .cloned()
+            .ok_or_else(|| SttError::WhisperError("语言检测未返回结果".to_string()))?;
+
+        Ok(LanguageDetection {
+            language: best.language,
+            probability: best.probability,
+            top_languages,
+        })
     }
 
-    /// 检查模型是否支持多语言
-    pub fn is_multilingual(&self) -> bool {
-        // 简化实现，根据模型文件名判断
-        let model_name = self
-            .config
-            .model_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("");
+    /// 获取转录配置
+    pub fn config(&self) -> &WhisperConfig {
+        &self.config
+    }
 
-        !model_name.contains(".en")
+    /// 所有克隆共享的推理并发限制
+    pub(crate) fn limiter(&self) -> &InferenceLimiter {
+        &self.limiter
     }
+
+    /// 获取模型元数据（模型类型、层数、量化类型、文件大小与推理后端）
+    pub fn model_info(&self) -> &ModelInfo {
+        &self.model_info
+    }
+
+    /// 共享同一模型、但固定使用 `language` 解码的转录器（不再做语言识别）
+    pub(crate) fn with_language(&self, language: &str) -> Self {
+        let mut config = (*self.config).clone();
+        config.language = Some(language.to_string());
+        Self {
+            config: Arc::new(config),
+            ..self.clone()
+        }
+    }
+
+    /// 统计文本经模型分词后的 token 数
+    pub fn count_tokens(&self, text: &str) -> SttResult<usize> {
+        // token 数不会超过字节数
+        let tokens = self.context.tokenize(text, text.len() + 1)?;
+        Ok(tokens.len())
+    }
+
+    /// 检查模型是否支持多语言（根据模型文件头中的词表大小判断）
+    pub fn is_multilingual(&self) -> bool {
+        self.model_info.multilingual
+    }
+}
+
+/// 将音频文件转换为 Whisper 兼容格式并读入内存
+///
+/// 转换（可能调用 FFmpeg）与读取在阻塞线程上执行，不经过推理并发限制。
+pub(crate) async fn load_audio_file(audio_path: &Path) -> SttResult<AudioData> {
+    info!("开始转录文件: {}", audio_path.display());
+    let audio_path = audio_path.to_path_buf();
+    run_audio_job(move || {
+        // 确保输入音频转为 Whisper 兼容（mono/16k/WAV）
+        let converted = convert_for_whisper(&audio_path)?;
+        crate::audio::utils::read_wav_file(&converted)
+    })
+    .await
+}
+
+/// 在阻塞线程上执行音频转换、读取或重采样，不经过推理并发限制
+pub(crate) async fn run_audio_job<T, F>(job: F) -> SttResult<T>
+where
+    F: FnOnce() -> SttResult<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(job)
+        .await
+        .map_err(|e| SttError::AudioProcessingError(format!("音频处理线程异常退出: {e}")))?
+}
+
+/// 将任意音频文件转换为 Whisper 兼容的 WAV（单声道 16kHz），返回转换后的文件路径
+pub(crate) fn convert_for_whisper(audio_path: &Path) -> SttResult<PathBuf> {
+    let converted =
+        audio_lib::ensure_whisper_compatible(audio_path, None).map_err(|e| match e {
+            audio_lib::AudioError::FileNotFound(path) => {
+                SttError::AudioProcessingError(format!("音频文件不存在: {path}"))
+            }
+            audio_lib::AudioError::NotAFile(path) => {
+                SttError::AudioProcessingError(format!("路径不是音频文件: {path}"))
+            }
+            audio_lib::AudioError::FormatNotSupported { format, supported } => {
+                SttError::AudioProcessingError(format!(
+                    "音频格式不支持: {format}, 支持的格式: {supported}"
+                ))
+            }
+            audio_lib::AudioError::SampleRateMismatch { expected, actual } => {
+                SttError::AudioProcessingError(format!(
+                    "采样率不匹配: 期望 {expected}, 实际 {actual}"
+                ))
+            }
+            audio_lib::AudioError::ChannelMismatch { expected, actual } => {
+                SttError::AudioProcessingError(format!(
+                    "通道数不匹配: 期望 {expected}, 实际 {actual}"
+                ))
+            }
+            audio_lib::AudioError::FfmpegConfig(msg)
+            | audio_lib::AudioError::FfmpegExecution(msg) => {
+                SttError::AudioProcessingError(format!("FFmpeg 错误: {msg}"))
+            }
+            audio_lib::AudioError::DecodeError { reason } => {
+                SttError::AudioProcessingError(format!("音频解码失败: {reason}"))
+            }
+            audio_lib::AudioError::InvalidSampleRate { rate, min, max } => {
+                SttError::AudioProcessingError(format!("无效采样率: {rate}, 有效范围: {min}-{max}"))
+            }
+            audio_lib::AudioError::ResampleError(msg) => {
+                SttError::AudioProcessingError(format!("重采样失败: {msg}"))
+            }
+            _ => SttError::AudioProcessingError(format!("音频处理失败: {e}")),
+        })?;
+
+    Ok(converted.path)
+}
+
+/// 16kHz 样本数换算为毫秒
+pub(crate) fn samples_to_ms(samples: usize) -> u64 {
+    samples as u64 * 1000 / WHISPER_SAMPLE_RATE as u64
+}
+
+/// 毫秒换算为 16kHz 样本数
+pub(crate) fn ms_to_samples(ms: u64) -> usize {
+    (ms * WHISPER_SAMPLE_RATE as u64 / 1000) as usize
+}
+
+/// 按概率降序排列语言，返回前 `top_n` 个 (语言 ID, 概率)
+fn rank_languages(probs: &[f32], top_n: usize) -> Vec<(i32, f32)> {
+    let mut ranked: Vec<(i32, f32)> = probs
+        .iter()
+        .enumerate()
+        .map(|(id, &probability)| (id as i32, probability))
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked.truncate(top_n);
+    ranked
 }
 
 /// 便捷函数：快速转录文件
@@ -609,8 +1940,7 @@ where
 pub async fn transcribe_file_with_transcriber<P: AsRef<Path>>(
     transcriber: &WhisperTranscriber,
     audio_path: P,
-) -> SttResult<TranscriptionResult>
-{
+) -> SttResult<TranscriptionResult> {
     transcriber.transcribe_file(audio_path).await
 }
 
@@ -618,6 +1948,19 @@ pub async fn transcribe_file_with_transcriber<P: AsRef<Path>>(
 mod tests {
     use super::*;
 
+    /// 结构完整的最小 ggml 模型文件，使校验能进行到配置参数
+    fn test_model() -> PathBuf {
+        static MODEL: OnceLock<PathBuf> = OnceLock::new();
+        MODEL
+            .get_or_init(|| {
+                let path = std::env::temp_dir()
+                    .join(format!("stt_whisper_test_{}.bin", std::process::id()));
+                crate::model::write_test_model(&path, 51865, 4, 4);
+                path
+            })
+            .clone()
+    }
+
     #[test]
     fn test_config_defaults() {
         let cfg = WhisperConfig::default();
@@ -633,6 +1976,17 @@ mod tests {
         assert!(msg.contains("模型文件不存在"));
     }
 
+    #[test]
+    fn test_validate_model_header() {
+        assert!(WhisperConfig::new(test_model()).validate().is_ok());
+
+        let cfg = WhisperConfig::new(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"));
+        match cfg.validate() {
+            Err(SttError::ModelLoadError(msg)) => assert!(msg.contains("ggml")),
+            other => panic!("应返回模型加载错误: {other:?}"),
+        }
+    }
+
     #[test]
     fn test_vad_config() {
         let config = WhisperConfig::default()
@@ -643,6 +1997,41 @@ mod tests {
         assert_eq!(config.vad_threshold, 0.05);
     }
 
+    #[test]
+    fn test_speech_only_config() {
+        let config = WhisperConfig::default();
+        assert_eq!(config.vad_mode, VadMode::TrimLeading);
+
+        let config = WhisperConfig::default().with_speech_only(300, 800);
+        assert!(config.enable_vad);
+        assert_eq!(config.vad_mode, VadMode::SpeechOnly);
+        assert_eq!(config.vad_padding_ms, 300);
+        assert_eq!(config.vad_merge_gap_ms, 800);
+    }
+
+    #[test]
+    fn test_segment_offset_by() {
+        let mut segment = TranscriptionSegment {
+            start_time: 100,
+            end_time: 900,
+            text: "hello".to_string(),
+            confidence: 0.9,
+            words: vec![TranscriptionWord {
+                start_time: 150,
+                end_time: 800,
+                text: "hello".to_string(),
+                probability: 0.9,
+            }],
+        };
+
+        segment.offset_by(5_000);
+        assert_eq!((segment.start_time, segment.end_time), (5_100, 5_900));
+        assert_eq!(
+            (segment.words[0].start_time, segment.words[0].end_time),
+            (5_150, 5_800)
+        );
+    }
+
     #[test]
     fn test_vad_threshold_clamping() {
         // 测试阈值被正确限制在 0.0-1.0 范围内
@@ -656,6 +2045,345 @@ mod tests {
         assert_eq!(config3.vad_threshold, 0.5);
     }
 
+    #[test]
+    fn test_sampling_strategy_config() {
+        let config = WhisperConfig::default();
+        assert_eq!(
+            config.sampling_strategy,
+            DecodingStrategy::Greedy { best_of: 1 }
+        );
+
+        let config = config.with_beam_search(5, -1.0);
+        assert_eq!(
+            config.sampling_strategy,
+            DecodingStrategy::BeamSearch {
+                beam_size: 5,
+                patience: -1.0
+            }
+        );
+
+        let config = config.with_greedy(5);
+        assert_eq!(
+            config.sampling_strategy,
+            DecodingStrategy::Greedy { best_of: 5 }
+        );
+    }
+
+    #[test]
+    fn test_sampling_strategy_validation() {
+        let config = WhisperConfig::new(test_model()).with_beam_search(0, -1.0);
+        match config.validate() {
+            Err(SttError::ConfigError(msg)) => assert!(msg.contains("束宽")),
+            other => panic!("应返回束宽配置错误: {other:?}"),
+        }
+
+        let config = config.with_greedy(0);
+        assert!(matches!(config.validate(), Err(SttError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_fallback_config() {
+        let config = WhisperConfig::default();
+        assert_eq!(config.temperature_increment, 0.2);
+        assert_eq!(config.compression_ratio_threshold, Some(2.4));
+        assert_eq!(config.logprob_threshold, Some(-1.0));
+        assert_eq!(config.no_speech_threshold, Some(0.6));
+        assert!(config.detect_repetition);
+
+        let config = config
+            .with_temperature_increment(0.0)
+            .with_no_speech_threshold(None)
+            .with_repetition_detection(false);
+        let thresholds = config.quality_thresholds();
+        assert_eq!(thresholds.no_speech, None);
+        assert!(!thresholds.detect_repetition);
+
+        let config = WhisperConfig::new(test_model()).with_compression_ratio_threshold(Some(0.0));
+        assert!(matches!(config.validate(), Err(SttError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_segment_has_repetition() {
+        let segment = TranscriptionSegment {
+            start_time: 0,
+            end_time: 3000,
+            text: "谢谢观看，谢谢观看，谢谢观看".to_string(),
+            confidence: 0.4,
+            words: Vec::new(),
+        };
+        assert!(segment.has_repetition());
+
+        let segment = TranscriptionSegment {
+            text: "今天天气很好".to_string(),
+            ..segment
+        };
+        assert!(!segment.has_repetition());
+    }
+
+    #[test]
+    fn test_fixed_language() {
+        let config = WhisperConfig::default();
+        assert_eq!(config.language.as_deref(), Some("auto"));
+        assert_eq!(config.fixed_language(), None);
+
+        let config = config.with_language("zh");
+        assert_eq!(config.fixed_language(), Some("zh"));
+
+        let config = WhisperConfig {
+            language: None,
+            ..config
+        };
+        assert_eq!(config.fixed_language(), None);
+    }
+
+    #[test]
+    fn test_rank_languages() {
+        let probs = [0.05, 0.7, 0.0, 0.2, 0.05];
+        let ranked = rank_languages(&probs, 3);
+        assert_eq!(ranked, vec![(1, 0.7), (3, 0.2), (0, 0.05)]);
+
+        assert!(rank_languages(&[], 5).is_empty());
+    }
+
+    #[test]
+    fn test_vocabulary_config() {
+        let config =
+            WhisperConfig::default().with_vocabulary(["朝朝宝", " 招商银行 ", "", "朝朝宝"]);
+        assert_eq!(config.vocabulary, vec!["朝朝宝", "招商银行"]);
+    }
+
+    #[test]
+    fn test_render_prompt() {
+        let config = WhisperConfig::default();
+        assert_eq!(config.render_prompt(), None);
+
+        let config = config.with_initial_prompt("以下是银行客服通话。");
+        assert_eq!(
+            config.render_prompt().as_deref(),
+            Some("以下是银行客服通话。")
+        );
+
+        let config = config.with_vocabulary(["朝朝宝", "招商银行"]);
+        assert_eq!(
+            config.render_prompt().as_deref(),
+            Some("以下是银行客服通话。 朝朝宝, 招商银行.")
+        );
+
+        let config = WhisperConfig::default()
+            .with_initial_prompt("  ")
+            .with_vocabulary(["Kubernetes"]);
+        assert_eq!(config.render_prompt().as_deref(), Some("Kubernetes."));
+
+        let config = WhisperConfig::default()
+            .with_output_script(OutputScript::Simplified)
+            .with_vocabulary(["朝朝宝"]);
+        assert_eq!(config.render_prompt().as_deref(), Some("朝朝宝."));
+        let config = config.with_language("zh");
+        assert_eq!(
+            config.render_prompt().as_deref(),
+            Some("以下是普通话的句子。 朝朝宝.")
+        );
+    }
+
+    #[test]
+    fn test_resample_quality_config() {
+        let config = WhisperConfig::default();
+        assert_eq!(config.resample_quality, ResampleQuality::Standard);
+
+        let config = config.with_resample_quality(ResampleQuality::High);
+        assert_eq!(config.resample_quality, ResampleQuality::High);
+    }
+
+    #[test]
+    fn test_prepare_audio_samples_resamples_to_16k() {
+        use crate::audio::AudioConfig;
+
+        // 8kHz 电话音频，1秒
+        let telephony = AudioData::new(vec![0.0; 8000], AudioConfig::new(8000, 1, 16));
+        let samples =
+            WhisperTranscriber::prepare_audio_samples(&telephony, ResampleQuality::Fast).unwrap();
+        assert_eq!(samples.len(), 16000);
+
+        // 48kHz 立体声 WebRTC 音频，0.5秒
+        let webrtc = AudioData::new(vec![0.0; 48000], AudioConfig::new(48000, 2, 16));
+        let samples =
+            WhisperTranscriber::prepare_audio_samples(&webrtc, ResampleQuality::Fast).unwrap();
+        assert_eq!(samples.len(), 8000);
+    }
+
+    #[test]
+    fn test_prepare_audio_samples_preserves_timeline() {
+        use crate::audio::AudioConfig;
+
+        // 48kHz 音频中在 0.5 秒处放置一个短脉冲，重采样后应仍位于 0.5 秒附近
+        let mut input = vec![0.0f32; 48000];
+        for sample in &mut input[24000..24048] {
+            *sample = 1.0;
+        }
+        let audio = AudioData::new(input, AudioConfig::new(48000, 1, 16));
+        let samples =
+            WhisperTranscriber::prepare_audio_samples(&audio, ResampleQuality::Standard).unwrap();
+
+        let peak_index = samples
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+            .map(|(i, _)| i)
+            .unwrap();
+        let peak_ms = peak_index as f64 / WHISPER_SAMPLE_RATE as f64 * 1000.0;
+        assert!((peak_ms - 500.0).abs() < 3.0, "脉冲位置偏移: {peak_ms}ms");
+    }
+
+    #[test]
+    fn test_transcriber_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync + Clone + 'static>() {}
+        assert_send_sync::<WhisperTranscriber>();
+    }
+
+    #[tokio::test]
+    async fn test_transcription_keeps_runtime_responsive() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let root_dir = crate_dir.parent().expect("stt crate has parent");
+        let registry = crate::ModelRegistry::new([root_dir.join("fixtures/models")]);
+        let audio = root_dir.join("fixtures/audio/jfk.wav");
+        let Some(model) = registry.find("tiny").filter(|_| audio.exists()) else {
+            eprintln!(
+                "跳过: 缺少 fixtures 模型或音频 ({:?} , {})",
+                registry.dirs(),
+                audio.display()
+            );
+            return;
+        };
+
+        let transcriber = WhisperTranscriber::new(WhisperConfig::new(model)).unwrap();
+        let audio_data = crate::audio::utils::read_wav_file(&audio).unwrap();
+
+        // 单线程运行时：解码若阻塞执行线程，计时任务在解码期间将无法运行
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let ticker = {
+            let ticks = Arc::clone(&ticks);
+            tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    ticks.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+        };
+
+        let start = std::time::Instant::now();
+        let result = transcriber
+            .transcribe_audio_data(&audio_data)
+            .await
+            .unwrap();
+        let elapsed = start.elapsed().as_millis() as usize;
+        ticker.abort();
+
+        assert!(!result.text.trim().is_empty());
+        let ticks = ticks.load(Ordering::SeqCst);
+        assert!(
+            ticks >= elapsed / 20,
+            "解码期间运行时应保持响应：耗时 {elapsed}ms，计时 {ticks} 次"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_inference_concurrency_limit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        let limiter = InferenceLimiter::new(2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let jobs: Vec<_> = (0..6)
+            .map(|_| {
+                let limiter = limiter.clone();
+                let running = Arc::clone(&running);
+                let peak = Arc::clone(&peak);
+                tokio::spawn(async move {
+                    limiter
+                        .run(move || {
+                            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                            peak.fetch_max(now, Ordering::SeqCst);
+                            std::thread::sleep(Duration::from_millis(30));
+                            running.fetch_sub(1, Ordering::SeqCst);
+                            Ok(())
+                        })
+                        .await
+                })
+            })
+            .collect();
+        for job in jobs {
+            job.await.unwrap().unwrap();
+        }
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_load_audio_file_missing() {
+        let result = load_audio_file(Path::new("/nonexistent/audio.wav")).await;
+        assert!(matches!(result, Err(SttError::AudioProcessingError(_))));
+    }
+
+    #[test]
+    fn test_default_threads_split_across_inference() {
+        let config = WhisperConfig::default();
+        let total = config.n_threads as usize * config.max_concurrent_inference;
+        assert!(total <= physical_cores().max(config.max_concurrent_inference));
+    }
+
+    #[test]
+    fn test_max_concurrent_inference_config() {
+        assert_eq!(WhisperConfig::default().max_concurrent_inference, 2);
+        let config = WhisperConfig::default().with_max_concurrent_inference(0);
+        assert_eq!(config.max_concurrent_inference, 1);
+    }
+
+    #[test]
+    fn test_backend_config() {
+        let config = WhisperConfig::default();
+        assert_eq!(config.use_gpu, ComputeBackend::compiled_gpu().is_some());
+
+        // 强制 CPU
+        let config = WhisperConfig::default().with_gpu(false).with_gpu_device(1);
+        assert_eq!(config.backend(), ComputeBackend::Cpu);
+        assert_eq!(config.gpu_device, 1);
+
+        let config = WhisperConfig::default().with_gpu(true);
+        assert_eq!(
+            config.backend(),
+            ComputeBackend::compiled_gpu().unwrap_or(ComputeBackend::Cpu)
+        );
+    }
+
+    #[test]
+    fn test_flash_attn_dtw_conflict() {
+        let config = WhisperConfig::new(test_model())
+            .with_flash_attn(true)
+            .with_dtw_preset(Some(DtwPreset::Base));
+        match config.validate() {
+            Err(SttError::ConfigError(msg)) => assert!(msg.contains("DTW")),
+            other => panic!("应返回 DTW 配置错误: {other:?}"),
+        }
+
+        let config = config.with_dtw_preset(Some(DtwPreset::TopMost { n_top: 0 }));
+        assert!(matches!(config.validate(), Err(SttError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_default_threads_physical_cores() {
+        let physical = System::new().physical_core_count();
+        let config = WhisperConfig::default();
+        if let Some(cores) = physical {
+            assert_eq!(config.n_threads, cores.max(1) as i32);
+        }
+        assert!(config.n_threads >= 1);
+    }
+
     #[test]
     fn test_vad_validation() {
         // 测试 VAD 阈值验证 - 需要设置一个不存在的模型路径来避免模型文件检查
@@ -0,0 +1,246 @@
+//! 词级时间戳
+//!
+//! 将 whisper 的 BPE token 合并回完整单词；中日韩文字按字切分。
+
+use crate::text::is_cjk;
+use serde::{Deserialize, Serialize};
+
+/// 转录结果中的单词（中日韩文字为单字）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptionWord {
+    /// 开始时间（毫秒）
+    pub start_time: u64,
+    /// 结束时间（毫秒）
+    pub end_time: u64,
+    /// 单词文本
+    pub text: String,
+    /// 组成该单词的 token 的平均概率（0.0-1.0）
+    pub probability: f32,
+}
+
+/// 一个文本 token 的原始数据
+#[derive(Debug, Clone)]
+pub(crate) struct TokenPiece {
+    /// token 原始字节（可能只是一个 UTF-8 字符的一部分）
+    pub bytes: Vec<u8>,
+    /// 开始时间（毫秒）
+    pub start_time: u64,
+    /// 结束时间（毫秒）
+    pub end_time: u64,
+    /// token 概率
+    pub probability: f32,
+}
+
+/// 正在拼接的单词
+struct WordBuilder {
+    text: String,
+    start_time: u64,
+    end_time: u64,
+    probability_sum: f32,
+    token_count: usize,
+}
+
+impl WordBuilder {
+    fn new(start_time: u64) -> Self {
+        Self {
+            text: String::new(),
+            start_time,
+            end_time: start_time,
+            probability_sum: 0.0,
+            token_count: 0,
+        }
+    }
+
+    fn finish(self) -> TranscriptionWord {
+        TranscriptionWord {
+            start_time: self.start_time,
+            end_time: self.end_time,
+            text: self.text,
+            probability: self.probability_sum / self.token_count.max(1) as f32,
+        }
+    }
+}
+
+/// 将 token 合并为单词
+///
+/// 跨 token 的多字节字符先拼回完整字符；以空格开头的 token 开启新单词，
+/// 中日韩文字每个字单独成词，标点附着在前一个单词上。
+/// 一个 token 内包含多个字时，按字数平分该 token 的时间范围。
+pub(crate) fn merge_tokens(pieces: &[TokenPiece]) -> Vec<TranscriptionWord> {
+    let mut words: Vec<TranscriptionWord> = Vec::new();
+    let mut current: Option<WordBuilder> = None;
+
+    for (text, start_time, end_time, probability) in decode_pieces(pieces) {
+        let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+        if text.starts_with(char::is_whitespace) {
+            words.extend(current.take().map(WordBuilder::finish));
+        }
+        if chars.is_empty() {
+            continue;
+        }
+
+        let duration = end_time.saturating_sub(start_time);
+        let n_chars = chars.len() as u64;
+        let mut counted = false;
+
+        for (index, c) in chars.into_iter().enumerate() {
+            let char_start = start_time + duration * index as u64 / n_chars;
+            let char_end = start_time + duration * (index as u64 + 1) / n_chars;
+
+            if is_cjk(c) {
+                words.extend(current.take().map(WordBuilder::finish));
+                words.push(TranscriptionWord {
+                    start_time: char_start,
+                    end_time: char_end,
+                    text: c.to_string(),
+                    probability,
+                });
+                continue;
+            }
+
+            // 标点附着在前一个单词上
+            if !c.is_alphanumeric() && current.is_none() {
+                if let Some(last) = words.last_mut() {
+                    last.text.push(c);
+                    last.end_time = last.end_time.max(char_end);
+                    continue;
+                }
+            }
+
+            let word = current.get_or_insert_with(|| WordBuilder::new(char_start));
+            word.text.push(c);
+            word.end_time = char_end;
+            if !counted {
+                word.probability_sum += probability;
+                word.token_count += 1;
+                counted = true;
+            }
+        }
+    }
+
+    words.extend(current.take().map(WordBuilder::finish));
+    words
+}
+
+/// 把 token 字节拼成完整的 UTF-8 文本片段
+///
+/// 不完整的字符会与后续 token 合并，合并后的时间范围与概率取并集与平均值。
+fn decode_pieces(pieces: &[TokenPiece]) -> Vec<(String, u64, u64, f32)> {
+    let mut decoded = Vec::new();
+    let mut pending: Vec<u8> = Vec::new();
+    let mut pending_start = 0;
+    let mut probability_sum = 0.0;
+    let mut pending_count = 0;
+
+    for piece in pieces {
+        if pending.is_empty() {
+            pending_start = piece.start_time;
+        }
+        pending.extend_from_slice(&piece.bytes);
+        probability_sum += piece.probability;
+        pending_count += 1;
+
+        if let Ok(text) = std::str::from_utf8(&pending) {
+            decoded.push((
+                text.to_string(),
+                pending_start,
+                piece.end_time.max(pending_start),
+                probability_sum / pending_count as f32,
+            ));
+            pending.clear();
+            probability_sum = 0.0;
+            pending_count = 0;
+        }
+    }
+
+    // 结尾残留的无效字节按有损方式输出
+    if !pending.is_empty() {
+        let end_time = pieces.last().map_or(pending_start, |p| p.end_time);
+        decoded.push((
+            String::from_utf8_lossy(&pending).into_owned(),
+            pending_start,
+            end_time.max(pending_start),
+            probability_sum / pending_count as f32,
+        ));
+    }
+
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn piece(bytes: &[u8], start_time: u64, end_time: u64, probability: f32) -> TokenPiece {
+        TokenPiece {
+            bytes: bytes.to_vec(),
+            start_time,
+            end_time,
+            probability,
+        }
+    }
+
+    #[test]
+    fn test_merge_bpe_tokens_into_words() {
+        let pieces = [
+            piece(b" And", 0, 200, 0.9),
+            piece(b" so", 200, 400, 0.8),
+            piece(b" Amer", 400, 600, 0.6),
+            piece(b"icans", 600, 900, 0.8),
+            piece(b",", 900, 950, 0.9),
+        ];
+
+        let words = merge_tokens(&pieces);
+        let texts: Vec<_> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, vec!["And", "so", "Americans,"]);
+
+        let americans = &words[2];
+        assert_eq!(americans.start_time, 400);
+        assert_eq!(americans.end_time, 950);
+        assert!((americans.probability - (0.6 + 0.8 + 0.9) / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_split_cjk_per_character() {
+        // "你好" 在一个 token 内，"世" 的 UTF-8 字节被拆到两个 token
+        let shi = "世".as_bytes();
+        let pieces = [
+            piece("你好".as_bytes(), 0, 400, 0.9),
+            piece(&shi[..1], 400, 500, 0.5),
+            piece(&shi[1..], 500, 600, 0.7),
+            piece("界。".as_bytes(), 600, 800, 0.8),
+        ];
+
+        let words = merge_tokens(&pieces);
+        let texts: Vec<_> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, vec!["你", "好", "世", "界。"]);
+
+        assert_eq!((words[0].start_time, words[0].end_time), (0, 200));
+        assert_eq!((words[1].start_time, words[1].end_time), (200, 400));
+        assert_eq!((words[2].start_time, words[2].end_time), (400, 600));
+        assert!((words[2].probability - 0.6).abs() < 1e-6);
+        assert_eq!((words[3].start_time, words[3].end_time), (600, 800));
+    }
+
+    #[test]
+    fn test_mixed_cjk_and_latin() {
+        let pieces = [
+            piece("打开".as_bytes(), 0, 400, 0.9),
+            piece(b" Wi", 400, 500, 0.7),
+            piece(b"-Fi", 500, 700, 0.7),
+            piece("设置".as_bytes(), 700, 1100, 0.8),
+        ];
+
+        let words = merge_tokens(&pieces);
+        let texts: Vec<_> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, vec!["打", "开", "Wi-Fi", "设", "置"]);
+        assert_eq!(words[2].start_time, 400);
+        assert_eq!(words[2].end_time, 700);
+    }
+
+    #[test]
+    fn test_merge_empty() {
+        assert!(merge_tokens(&[]).is_empty());
+        assert!(merge_tokens(&[piece(b" ", 0, 10, 0.5)]).is_empty());
+    }
+}